@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+/// Emitted when a player attack hits a creature
+#[derive(Event, Message, Debug)]
+pub struct DamageDealt {
+    #[allow(dead_code)]
+    pub target: Entity,
+    pub amount: i32,
+}
+
+/// Emitted when a creature attack or a hazard hurts the player
+#[derive(Event, Message, Debug)]
+pub struct PlayerDamaged {
+    pub amount: i32,
+    /// The hit landed on the player's block
+    pub blocked: bool,
+}
@@ -1,9 +1,11 @@
 pub mod components;
+pub mod events;
 pub mod hit_detection;
 pub mod mesh;
 pub mod systems;
 
 pub use components::*;
+pub use events::*;
 pub use hit_detection::snap_to_cardinal;
 pub use mesh::*;
 pub use systems::*;
@@ -1,13 +1,13 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::constants::{PROVOKED_SPEED, WEAPON_OFFSET, Z_BLOOD, Z_WEAPON};
-use crate::core::{ellipse_push, Blocking, Dead, DeathAnimation, GameAction, GameConfig, Health, HitCollider, InputBindings, Knockback, StaticCollider, Stunned};
-use crate::creatures::{Activated, AttackOffset, CardinalAttacks, ContextMapCache, Creature, FlankPreference, Goblin, Hostile};
-use crate::player::{HurtAnimation, Player, PlayerSmashAttack, PlayerState};
+use crate::constants::{WEAPON_OFFSET, Z_BLOOD, Z_WEAPON};
+use crate::core::{ellipse_push, Blocking, Dead, DeathAnimation, GameAction, GameConfig, Health, HitCollider, InputBindings, Knockback, StaticCollider, Stunned, WalkCollider};
+use crate::creatures::{Activated, AttackOffset, CardinalAttacks, ContextMapCache, Creature, FlankPreference, Goblin, Hostile, NavPath};
+use crate::player::{HurtAnimation, Player, PlayerBonuses, PlayerSmashAttack, PlayerState};
 use crate::state_machine::StateMachine;
 use crate::props::{BarrelSprite, CrateSprite, Crate2Sprite, Destructible, Prop, PropRegistry, PropType};
-use super::{CreatureRangeIndicator, GoblinAttackIndicator, PlayerRangeIndicator, WeaponRangeIndicator};
+use super::{CreatureRangeIndicator, DamageDealt, GoblinAttackIndicator, PlayerDamaged, PlayerRangeIndicator, WeaponRangeIndicator};
 use crate::inventory::weapons::{weapon_catalog, AttackType, Drawn, Fist, PlayerWeapon, Weapon, WeaponSwing, WeaponVisualMesh};
 use crate::effects::{BloodParticle, HitHighlight, Hitstop, ScreenShake, TargetOutline, spawn_damage_number};
 use crate::core::CharacterAssets;
@@ -22,7 +22,6 @@ const BLOOD_SPREAD_RANGE: std::ops::Range<f32> = -0.8..0.8;
 const BLOOD_SPEED_RANGE: std::ops::Range<f32> = 80.0..200.0;
 const BLOOD_OFFSET_RANGE: std::ops::Range<f32> = -5.0..5.0;
 const BLOOD_LIFETIME_RANGE: std::ops::Range<f32> = 0.4..1.2;
-const RECOIL_MULTIPLIER: f32 = 0.15;
 
 fn spawn_blood_particles(
     commands: &mut Commands,
@@ -117,6 +116,7 @@ pub fn handle_block(
 
 pub fn apply_mesh_attack_hits(
     mut commands: Commands,
+    mut damage_dealt: MessageWriter<DamageDealt>,
     config: Res<GameConfig>,
     mut hitstop: ResMut<Hitstop>,
     mut screen_shake: ResMut<ScreenShake>,
@@ -172,6 +172,7 @@ pub fn apply_mesh_attack_hits(
             let damage = weapon.roll_damage();
             health.0 -= damage;
             spawn_damage_number(&mut commands, creature_pos, damage);
+            damage_dealt.write(DamageDealt { target: entity, amount: damage });
 
             // Knockback direction: from attack origin toward creature
             let knockback_dir = (creature_pos - hit_cone.origin).normalize_or_zero();
@@ -197,7 +198,7 @@ pub fn apply_mesh_attack_hits(
                 });
             } else if hostile.is_none() {
                 // Make non-hostile creature become hostile when hit (provoked = direct pursuit)
-                commands.entity(entity).insert((Hostile { speed: PROVOKED_SPEED }, crate::creatures::Provoked));
+                commands.entity(entity).insert((Hostile { speed: config.creature_provoked_speed() }, crate::creatures::Provoked));
 
                 // Swap steering config to provoked behavior
                 if let Some(provoked_config) = provoked_steering {
@@ -249,7 +250,7 @@ pub fn apply_mesh_attack_hits(
 
     // Apply recoil and game feel effects when hitting
     if hit_any {
-        let recoil_force = weapon.knockback_force() * RECOIL_MULTIPLIER;
+        let recoil_force = weapon.knockback_force() * config.recoil_multiplier;
         commands.entity(player_entity).insert(Knockback {
             velocity: -attack_dir * recoil_force,
             timer: 0.0,
@@ -263,6 +264,7 @@ pub fn apply_mesh_attack_hits(
 
 pub fn apply_smash_attack_hits(
     mut commands: Commands,
+    mut damage_dealt: MessageWriter<DamageDealt>,
     config: Res<GameConfig>,
     mut hitstop: ResMut<Hitstop>,
     mut screen_shake: ResMut<ScreenShake>,
@@ -310,6 +312,7 @@ pub fn apply_smash_attack_hits(
             let damage = weapon.roll_damage();
             health.0 -= damage;
             spawn_damage_number(&mut commands, creature_pos, damage);
+            damage_dealt.write(DamageDealt { target: entity, amount: damage });
 
             let knockback_dir = (creature_pos - hit_cone.origin).normalize_or_zero();
             weapon.apply_on_hit(&mut commands, entity, knockback_dir);
@@ -332,7 +335,7 @@ pub fn apply_smash_attack_hits(
                     stage: 0,
                 });
             } else if hostile.is_none() {
-                commands.entity(entity).insert((Hostile { speed: PROVOKED_SPEED }, crate::creatures::Provoked));
+                commands.entity(entity).insert((Hostile { speed: config.creature_provoked_speed() }, crate::creatures::Provoked));
 
                 if let Some(provoked_config) = provoked_steering {
                     commands.entity(entity).insert(CreatureSteering(provoked_config.0.clone()));
@@ -405,7 +408,7 @@ pub fn apply_smash_attack_hits(
     }
 
     if hit_any {
-        let recoil_force = weapon.knockback_force() * RECOIL_MULTIPLIER;
+        let recoil_force = weapon.knockback_force() * config.recoil_multiplier;
         commands.entity(player_entity).insert(Knockback {
             velocity: -attack_dir * recoil_force,
             timer: 0.0,
@@ -468,12 +471,14 @@ pub fn aim_weapon(
     }
 }
 
+/// How close a patrolling creature has to get to a waypoint to count as there
+const WAYPOINT_REACH_DISTANCE: f32 = 6.0;
+
 pub fn patrol_ai(
     mut commands: Commands,
     time: Res<Time>,
     current_level: Res<crate::levels::CurrentLevel>,
     mut transitions: MessageWriter<crate::state_machine::RequestTransition<crate::creatures::CreatureState>>,
-    player_query: Query<&Transform, (With<Player>, Without<Creature>, Without<StaticCollider>)>,
     collider_query: Query<(&Transform, &StaticCollider), (Without<Player>, Without<Creature>)>,
     mut creature_query: Query<(
         Entity,
@@ -483,36 +488,34 @@ pub fn patrol_ai(
         &crate::state_machine::StateMachine<crate::creatures::CreatureState>,
         &crate::creatures::PatrolOrigin,
         &mut crate::creatures::PatrolWander,
+        &crate::creatures::Awareness,
+        Option<&mut crate::creatures::PatrolRoute>,
         Option<&mut ContextMapCache>,
         Option<&crate::creatures::Rushing>,
     ), (Without<Dead>, Without<DeathAnimation>, Without<Player>, Without<Stunned>, Without<StaticCollider>)>,
 ) {
-    use crate::creatures::{ContextMap, ContextMapCache, CreatureState, obstacle_danger, pit_danger, patrol_interest, patrol_boundary_danger, seek_interest};
+    use crate::creatures::{ContextMap, ContextMapCache, CreatureState, obstacle_danger, patrol_interest, patrol_boundary_danger, seek_interest, shape_danger, BARRIER_DANGER_WEIGHT, HAZARD_DANGER_WEIGHT};
     use rand::Rng;
 
-    let Ok(player_transform) = player_query.single() else { return };
-    let player_pos = player_transform.translation.truncate();
-
     let collider_data: Vec<(Vec2, Vec2)> = collider_query
         .iter()
         .map(|(t, c)| (Vec2::new(t.translation.x, t.translation.y + c.offset_y), Vec2::new(c.radius_x, c.radius_y)))
         .collect();
 
-    let pit_data: Vec<(Vec2, f32)> = current_level.pits()
-        .iter()
-        .map(|p| (p.position, p.edge_radius))
-        .collect();
+    let hazards = current_level.hazard_areas(time.elapsed_secs());
+
+    let barriers = current_level.barriers();
 
-    for (entity, mut transform, hostile, steering, state_machine, patrol_origin, mut patrol_wander, context_cache, rushing) in &mut creature_query {
+    for (entity, mut transform, hostile, steering, state_machine, patrol_origin, mut patrol_wander, awareness, mut patrol_route, context_cache, rushing) in &mut creature_query {
         if *state_machine.current() != CreatureState::Patrol {
             continue;
         }
 
         let config = &steering.0;
         let creature_pos = transform.translation.truncate();
-        let distance_to_player = player_pos.distance(creature_pos);
 
-        if distance_to_player <= config.sight_range {
+        // Anything noticed makes the creature stop and look; see update_awareness
+        if awareness.0 > 0.0 {
             transitions.write(crate::state_machine::RequestTransition::new(
                 entity,
                 crate::creatures::CreatureState::Alert,
@@ -532,7 +535,27 @@ pub fn patrol_ai(
 
         use crate::creatures::PatrolAction;
 
-        if !is_rushing {
+        // Creatures with a route walk from waypoint to waypoint instead of wandering
+        let mut route_target = None;
+        if let Some(route) = patrol_route.as_deref_mut().filter(|_| !is_rushing) {
+            if route.wait_timer > 0.0 {
+                route.wait_timer -= time.delta_secs();
+                patrol_wander.action = PatrolAction::Idle;
+                continue;
+            }
+            let Some(mut target) = route.target() else { continue };
+            if creature_pos.distance(target) <= WAYPOINT_REACH_DISTANCE {
+                route.advance();
+                target = route.target().unwrap_or(target);
+                if route.wait_timer > 0.0 || creature_pos.distance(target) <= WAYPOINT_REACH_DISTANCE {
+                    patrol_wander.action = PatrolAction::Idle;
+                    continue;
+                }
+            }
+            patrol_wander.direction = (target - creature_pos).normalize_or(patrol_wander.direction);
+            patrol_wander.action = PatrolAction::Moving;
+            route_target = Some(target);
+        } else if !is_rushing {
             patrol_wander.action_timer -= time.delta_secs();
             if patrol_wander.action_timer <= 0.0 {
                 let mut rng = rand::rng();
@@ -556,6 +579,8 @@ pub fn patrol_ai(
 
         if is_rushing {
             seek_interest(&mut context, creature_pos, patrol_origin.position);
+        } else if let Some(target) = route_target {
+            seek_interest(&mut context, creature_pos, target);
         } else {
             patrol_interest(
                 &mut context,
@@ -574,7 +599,8 @@ pub fn patrol_ai(
         }
 
         obstacle_danger(&mut context, creature_pos, &collider_data, config.obstacle_look_ahead);
-        pit_danger(&mut context, creature_pos, &pit_data, config.obstacle_look_ahead);
+        shape_danger(&mut context, creature_pos, &hazards, config.obstacle_look_ahead, HAZARD_DANGER_WEIGHT);
+        shape_danger(&mut context, creature_pos, &barriers, config.obstacle_look_ahead, BARRIER_DANGER_WEIGHT);
 
         let (direction, strength) = context.resolve();
 
@@ -603,34 +629,25 @@ pub fn patrol_ai(
     }
 }
 
-const ALERT_DURATION: f32 = 1.0;
-
+/// Alert creatures give chase once fully aware of the player and go back to patrolling
+/// once they have lost track of them
 pub fn alert_ai(
     mut commands: Commands,
     mut transitions: MessageWriter<crate::state_machine::RequestTransition<crate::creatures::CreatureState>>,
-    player_query: Query<&Transform, (With<Player>, Without<Creature>, Without<StaticCollider>)>,
     creature_query: Query<(
         Entity,
-        &Transform,
-        &crate::creatures::CreatureSteering,
+        &crate::creatures::Awareness,
         &crate::state_machine::StateMachine<crate::creatures::CreatureState>,
     ), (With<Hostile>, Without<Dead>, Without<DeathAnimation>, Without<Stunned>, Without<StaticCollider>)>,
 ) {
     use crate::creatures::CreatureState;
 
-    let Ok(player_transform) = player_query.single() else { return };
-    let player_pos = player_transform.translation.truncate();
-
-    for (entity, transform, steering, state_machine) in &creature_query {
+    for (entity, awareness, state_machine) in &creature_query {
         if *state_machine.current() != CreatureState::Alert {
             continue;
         }
 
-        let config = &steering.0;
-        let creature_pos = transform.translation.truncate();
-        let distance_to_player = player_pos.distance(creature_pos);
-
-        if distance_to_player > config.sight_range {
+        if awareness.0 <= 0.0 {
             transitions.write(crate::state_machine::RequestTransition::new(
                 entity,
                 CreatureState::Patrol,
@@ -638,7 +655,7 @@ pub fn alert_ai(
             continue;
         }
 
-        if state_machine.time_in_state() >= ALERT_DURATION {
+        if awareness.0 >= 1.0 {
             commands.entity(entity).insert(Activated);
             transitions.write(crate::state_machine::RequestTransition::new(
                 entity,
@@ -652,18 +669,20 @@ pub fn hostile_ai(
     mut commands: Commands,
     time: Res<Time>,
     current_level: Res<crate::levels::CurrentLevel>,
-    player_query: Query<(&Transform, Option<&HitCollider>), (With<Player>, Without<Creature>, Without<StaticCollider>)>,
+    nav_grid: Res<crate::creatures::NavGrid>,
+    player_query: Query<(&Transform, Option<&HitCollider>, Option<&WalkCollider>), (With<Player>, Without<Creature>, Without<StaticCollider>)>,
     collider_query: Query<(&Transform, &StaticCollider), (Without<Player>, Without<Creature>)>,
     mut creature_queries: ParamSet<(
         Query<(Entity, &Transform), (With<Creature>, Without<Dead>, Without<StaticCollider>)>,
-        Query<(Entity, &mut Transform, &Hostile, &crate::creatures::CreatureSteering, &crate::state_machine::StateMachine<crate::creatures::CreatureState>, Option<&mut ContextMapCache>, Option<&FlankPreference>, Option<&Activated>), (Without<Dead>, Without<DeathAnimation>, Without<Player>, Without<Stunned>, Without<StaticCollider>)>,
+        Query<(Entity, &mut Transform, &Hostile, &crate::creatures::CreatureSteering, &crate::state_machine::StateMachine<crate::creatures::CreatureState>, Option<&mut ContextMapCache>, Option<&FlankPreference>, Option<&Activated>, Option<&WalkCollider>, Option<&mut NavPath>), (Without<Dead>, Without<DeathAnimation>, Without<Player>, Without<Stunned>, Without<StaticCollider>)>,
     )>,
 ) {
-    use crate::creatures::{ContextMap, ContextMapCache, CreatureState, FlankPreference, SteeringStrategy, seek_interest, seek_with_flank, obstacle_danger, separation_danger, player_proximity_danger, occupied_angle_danger, pit_danger};
+    use crate::creatures::{ContextMap, ContextMapCache, CreatureState, FlankPreference, SteeringStrategy, seek_interest, seek_with_flank, obstacle_danger, separation_danger, player_proximity_danger, occupied_angle_danger, shape_danger, BARRIER_DANGER_WEIGHT, HAZARD_DANGER_WEIGHT};
     use rand::Rng;
 
-    let Ok((player_transform, player_hit_collider)) = player_query.single() else { return };
+    let Ok((player_transform, player_hit_collider, player_walk_collider)) = player_query.single() else { return };
     let player_pos = player_transform.translation.truncate();
+    let player_feet = player_pos + Vec2::new(0.0, player_walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
     let player_range_bonus = player_hit_collider
         .map(|h| (h.max_radius() - h.max_offset()).max(0.0))
         .unwrap_or(0.0);
@@ -679,7 +698,10 @@ pub fn hostile_ai(
         .map(|(t, c)| (Vec2::new(t.translation.x, t.translation.y + c.offset_y), Vec2::new(c.radius_x, c.radius_y)))
         .collect();
 
-    for (entity, mut transform, hostile, steering, state_machine, context_cache, flank_pref, activated) in creature_queries.p1().iter_mut() {
+    let barriers = current_level.barriers();
+    let hazards = current_level.hazard_areas(time.elapsed_secs());
+
+    for (entity, mut transform, hostile, steering, state_machine, context_cache, flank_pref, activated, walk_collider, nav_path) in creature_queries.p1().iter_mut() {
         if *state_machine.current() != CreatureState::Chase {
             continue;
         }
@@ -705,32 +727,45 @@ pub fn hostile_ai(
         if distance > effective_min_distance {
             let mut context = ContextMap::new();
 
-            match config.strategy {
-                SteeringStrategy::Direct => {
-                    seek_interest(&mut context, creature_pos, player_pos);
-                }
-                SteeringStrategy::Flanking => {
-                    let flank_angle = if let Some(pref) = flank_pref {
-                        pref.0
-                    } else {
-                        let mut rng = rand::rng();
-                        let sign = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
-                        let magnitude = rng.random_range(config.flank_angle_min..config.flank_angle_max);
-                        let angle = sign * magnitude;
-                        commands.entity(entity).insert(FlankPreference(angle));
-                        angle
-                    };
-                    seek_with_flank(&mut context, creature_pos, player_pos, flank_angle);
+            // Walls or pillars in the way: follow a path around them instead of heading straight in
+            let creature_feet = creature_pos + Vec2::new(0.0, walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+            let detour = if nav_grid.line_clear(creature_feet, player_feet) {
+                None
+            } else if let Some(mut path) = nav_path {
+                path.next_waypoint(&nav_grid, creature_feet, player_feet)
+            } else {
+                let mut path = NavPath::default();
+                let waypoint = path.next_waypoint(&nav_grid, creature_feet, player_feet);
+                commands.entity(entity).insert(path);
+                waypoint
+            };
+
+            if let Some(waypoint) = detour {
+                seek_interest(&mut context, creature_feet, waypoint);
+            } else {
+                match config.strategy {
+                    SteeringStrategy::Direct => {
+                        seek_interest(&mut context, creature_pos, player_pos);
+                    }
+                    SteeringStrategy::Flanking => {
+                        let flank_angle = if let Some(pref) = flank_pref {
+                            pref.0
+                        } else {
+                            let mut rng = rand::rng();
+                            let sign = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+                            let magnitude = rng.random_range(config.flank_angle_min..config.flank_angle_max);
+                            let angle = sign * magnitude;
+                            commands.entity(entity).insert(FlankPreference(angle));
+                            angle
+                        };
+                        seek_with_flank(&mut context, creature_pos, player_pos, flank_angle);
+                    }
                 }
             }
 
             obstacle_danger(&mut context, creature_pos, &collider_data, config.obstacle_look_ahead);
-
-            let pit_data: Vec<(Vec2, f32)> = current_level.pits()
-                .iter()
-                .map(|p| (p.position, p.edge_radius))
-                .collect();
-            pit_danger(&mut context, creature_pos, &pit_data, config.obstacle_look_ahead);
+            shape_danger(&mut context, creature_pos, &hazards, config.obstacle_look_ahead, HAZARD_DANGER_WEIGHT);
+            shape_danger(&mut context, creature_pos, &barriers, config.obstacle_look_ahead, BARRIER_DANGER_WEIGHT);
 
             let others: Vec<Vec2> = creature_positions.iter()
                 .filter(|(e, _)| *e != entity)
@@ -816,6 +851,7 @@ fn calculate_block(
     player_weapon: Option<(&Weapon, &Transform)>,
     block_facing_offset: f32,
     block_angle_threshold: f32,
+    bonus_reduction: f32,
 ) -> (f32, f32, bool) {
     if !is_blocking {
         return (1.0, 1.0, false);
@@ -835,7 +871,7 @@ fn calculate_block(
     let to_attacker_len = to_attacker.length();
 
     if to_attacker_len > 0.001 && facing_dir.dot(to_attacker) > block_angle_threshold * to_attacker_len {
-        let dmg_mult = 1.0 - weapon.block_damage_reduction();
+        let dmg_mult = (1.0 - weapon.block_damage_reduction() - bonus_reduction).max(0.0);
         let kb_mult = 1.0 - weapon.block_knockback_reduction();
         (dmg_mult, kb_mult, true)
     } else {
@@ -843,7 +879,8 @@ fn calculate_block(
     }
 }
 
-/// Apply creature attack effects to player (damage, knockback, visual effects)
+/// Apply creature attack effects to player (damage, knockback, visual effects).
+/// Returns the damage dealt.
 fn apply_attack_to_player(
     commands: &mut Commands,
     player_entity: Entity,
@@ -858,11 +895,12 @@ fn apply_attack_to_player(
     hitstop: &mut Hitstop,
     screen_shake: &mut ScreenShake,
     config: &GameConfig,
-) {
-    // Apply damage
-    let final_damage = ((weapon.roll_damage() as f32) * damage_mult).floor() as i32;
-    player_health.0 -= final_damage.max(0);
-    spawn_damage_number(commands, player_pos, final_damage.max(0));
+) -> i32 {
+    // Apply damage, scaled by the difficulty
+    let damage_mult = damage_mult * config.creature_damage_scale * config.player_damage_taken_scale;
+    let final_damage = (((weapon.roll_damage() as f32) * damage_mult).floor() as i32).max(0);
+    player_health.0 -= final_damage;
+    spawn_damage_number(commands, player_pos, final_damage);
 
     // Knockback player
     let knockback_dir = (player_pos - attacker_pos).normalize();
@@ -891,6 +929,8 @@ fn apply_attack_to_player(
         },
         HurtAnimation::default(),
     ));
+
+    final_damage
 }
 
 /// Process creature attacks against player
@@ -899,6 +939,8 @@ fn apply_attack_to_player(
 pub fn process_creature_attacks(
     mut commands: Commands,
     config: Res<GameConfig>,
+    bonuses: Res<PlayerBonuses>,
+    mut player_damaged: MessageWriter<PlayerDamaged>,
     mut hitstop: ResMut<Hitstop>,
     mut screen_shake: ResMut<ScreenShake>,
     mut player_query: Query<(Entity, &Transform, &mut Health, Option<&HitCollider>, &StateMachine<PlayerState>), (With<Player>, Without<Creature>, Without<Dead>, Without<DeathAnimation>)>,
@@ -981,10 +1023,11 @@ pub fn process_creature_attacks(
             player_weapon,
             config.block_facing_offset,
             config.block_angle_threshold,
+            bonuses.block_reduction,
         );
 
         // Apply damage and effects
-        apply_attack_to_player(
+        let amount = apply_attack_to_player(
             &mut commands,
             player_entity,
             attacker_entity,
@@ -999,6 +1042,7 @@ pub fn process_creature_attacks(
             &mut screen_shake,
             &config,
         );
+        player_damaged.write(PlayerDamaged { amount, blocked });
 
         // Only one hit per frame
         return;
@@ -22,10 +22,14 @@ pub const Z_UI_WORLD: f32 = 5.0;
 pub const PIXEL_SCALE: f32 = 4.0;
 
 // Camera
-pub const CAMERA_BASE_SCALE: f32 = 1.2 / PIXEL_SCALE;  // Default zoom level
-pub const CAMERA_ZOOM_OUT_SCALE: f32 = 1.35 / PIXEL_SCALE;  // Zoomed out when moving
+pub const CAMERA_BASE_ZOOM: f32 = 1.2;  // Default zoom level
+pub const CAMERA_ZOOM_OUT: f32 = 1.35;  // Zoomed out when moving
+pub const CAMERA_BASE_SCALE: f32 = CAMERA_BASE_ZOOM / PIXEL_SCALE;
 pub const CAMERA_ZOOM_SPEED: f32 = 3.0;  // How fast to lerp between zoom levels
 
+// Player
+pub const PLAYER_HEALTH: i32 = 10;  // Health at the start of a run, before upgrades
+
 // Movement
 pub const PLAYER_SPEED: f32 = 100.0;
 pub const PLAYER_ACCELERATION: f32 = 2000.0;  // Near-instant response
@@ -55,6 +59,7 @@ pub const HIT_HIGHLIGHT_DURATION: f32 = 0.15; // Red flash duration
 pub const ATTACK_HIT_DELAY_PERCENT: f32 = 0.5;  // Hit at 50% into swing animation
 pub const ATTACK_COOLDOWN_DURATION: f32 = 1.5;  // Creature wait time between attacks
 pub const COMBO_TIMEOUT: f32 = 1.5;             // Time before combo resets to attack1
+pub const RECOIL_MULTIPLIER: f32 = 0.15;        // Share of weapon knockback pushing the player back on hit
 
 // Combat - Attack Geometry
 pub const WEAPON_OFFSET: (f32, f32) = (-4.0, 6.5);  // Weapon position relative to player
@@ -81,6 +86,8 @@ pub const BLOCK_ANGLE_THRESHOLD: f32 = 0.5; // Dot product threshold for valid b
 pub const PUSH_RADIUS: f32 = COLLISION_RADIUS * 2.2;  // Larger than all collision checks
 pub const PUSH_STRENGTH: f32 = 100.0;                 // Push force multiplier
 pub const GOBLIN_SIGHT_RANGE: f32 = 100.0;
+pub const GOBLIN_VIEW_ANGLE: f32 = 140.0;             // Degrees; goblins don't see behind them
+pub const DETECTION_TIME: f32 = 1.0;                  // Seconds of sight before a creature gives chase
 
 // Combat - Visual Indicators
 pub const RANGE_INDICATOR_ANGLE: f32 = std::f32::consts::FRAC_PI_6 / 2.0;  // 15 degrees for range indicator
@@ -101,9 +108,6 @@ pub const DEATH_COLLAPSE_DURATION: f32 = 0.3;
 pub const CORPSE_LIFETIME: f32 = 3.0;
 pub const PARTICLE_LIFETIME: f32 = 3.0;
 
-// World Generation
-pub const WORLD_SIZE: i32 = 20;
-pub const GRID_SPACING: f32 = 25.0;
-pub const CREATURE_SPAWN_CHANCE: f64 = 0.6;
-pub const HOSTILE_CHANCE: f64 = 0.15;
-pub const GLOWING_CHANCE: f64 = 0.3;
+// Levels
+pub const WIN_ZONE_TIME: f32 = 5.0;        // Seconds to hold a win zone without its own time
+pub const PIT_EDGE_RESISTANCE: f32 = 80.0; // Pull back from a pit edge, grows with depth squared
@@ -1,8 +1,13 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy, Deserialize, Serialize)]
 pub struct Health(pub i32);
 
+/// Health that healing never goes above
+#[derive(Component, Clone, Copy)]
+pub struct MaxHealth(pub i32);
+
 #[derive(Component)]
 pub struct Dead;
 
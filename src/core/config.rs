@@ -1,8 +1,109 @@
 use bevy::prelude::*;
-use configparser::ini::Ini;
-use std::path::Path;
+use std::fmt;
+use std::io::ErrorKind;
+use std::str::FromStr;
+use std::time::SystemTime;
 
 use crate::constants::*;
+use super::Difficulty;
+
+/// Config file read at startup and watched for changes
+pub const CONFIG_FILE: &str = "config.ini";
+/// Seconds between checks of the config file for changes
+const CONFIG_WATCH_INTERVAL: f32 = 0.5;
+/// Command line flag that refuses to run with any config problem
+pub const STRICT_CONFIG_FLAG: &str = "--strict-config";
+
+/// Emitted after `GameConfig` was reloaded from a changed config file
+#[derive(Event, Message, Debug)]
+pub struct ConfigReloaded;
+
+/// Tracks the modification times of the config stack for hot-reloading
+#[derive(Resource, Default)]
+pub struct ConfigFileWatcher {
+    /// False until the first check, which only records the modification times
+    pub watching: bool,
+    /// One entry per file of the stack, None for missing files
+    pub last_modified: Vec<Option<SystemTime>>,
+    pub check_timer: f32,
+}
+
+/// Set by `--strict-config`: config problems stop the game from starting and block hot-reloads
+#[derive(Resource, Default)]
+pub struct StrictConfig(pub bool);
+
+/// A problem with a config entry, which keeps its default
+#[derive(Debug, Clone)]
+pub struct ConfigDiagnostic {
+    pub path: String,
+    /// None when the problem is with the file as a whole
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path, line, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+/// How one file of the config stack was read
+#[derive(Debug, Clone)]
+pub struct ConfigSource {
+    pub path: String,
+    /// False when the file doesn't exist and is skipped
+    pub found: bool,
+    /// Entries applied from the file
+    pub applied: usize,
+}
+
+/// Outcome of reading a config stack
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ConfigReport {
+    /// Files in the order they were applied
+    pub sources: Vec<ConfigSource>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl ConfigReport {
+    /// One line summary, e.g. "config.ini: 44 entries applied, difficulty/hard.ini: not found, 2 problems"
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .sources
+            .iter()
+            .map(|source| match source.found {
+                true => format!(
+                    "{}: {} {} applied",
+                    source.path,
+                    source.applied,
+                    if source.applied == 1 { "entry" } else { "entries" },
+                ),
+                false => format!("{}: not found", source.path),
+            })
+            .collect();
+        parts.push(format!(
+            "{} {}",
+            self.diagnostics.len(),
+            if self.diagnostics.len() == 1 { "problem" } else { "problems" },
+        ));
+        parts.join(", ")
+    }
+
+    /// Log the summary followed by every problem
+    pub fn log(&self) {
+        if self.diagnostics.is_empty() {
+            info!("Config {}", self.summary());
+            return;
+        }
+        warn!("Config {}", self.summary());
+        for diagnostic in &self.diagnostics {
+            warn!("{}", diagnostic);
+        }
+    }
+}
 
 /// Runtime game configuration loaded from INI file with fallback to compiled defaults
 #[derive(Resource, Clone)]
@@ -30,10 +131,17 @@ pub struct GameConfig {
 
     // === CREATURE CONFIG ===
     pub hostile_speed: f32,
+    /// Chase speed of creatures turned hostile by a hit
+    pub provoked_speed: f32,
     pub goblin_sight_range: f32,
+    pub goblin_view_angle: f32,
+    pub detection_time: f32,
     pub attack_cooldown_duration: f32,
     pub player_min_distance: f32,
     pub push_radius: f32,
+    pub separation_radius: f32,
+    pub obstacle_look_ahead: f32,
+    pub corpse_lifetime: f32,
 
     // === SHARED COMBAT CONFIG ===
     pub knockback_force: f32,
@@ -42,6 +150,9 @@ pub struct GameConfig {
     pub collision_radius: f32,
     pub push_strength: f32,
     pub stun_duration: f32,
+    pub combo_timeout: f32,
+    /// Share of the weapon's knockback that pushes the player back on a hit
+    pub recoil_multiplier: f32,
 
     // === GAME FEEL ===
     pub hitstop_duration: f32,
@@ -49,6 +160,26 @@ pub struct GameConfig {
     pub screen_shake_duration: f32,
     pub hit_highlight_duration: f32,
 
+    // === CAMERA ===
+    /// Zoom levels before `PIXEL_SCALE` is applied
+    pub camera_base_zoom: f32,
+    pub camera_zoom_out: f32,
+    pub camera_zoom_speed: f32,
+
+    // === LEVEL ===
+    pub win_zone_time: f32,
+    pub pit_edge_resistance: f32,
+
+    // === DIFFICULTY ===
+    // Multipliers set by the difficulty preset layered over config.ini
+    pub creature_health_scale: f32,
+    pub creature_damage_scale: f32,
+    pub creature_speed_scale: f32,
+    pub attack_cooldown_scale: f32,
+    pub sight_range_scale: f32,
+    /// Scales all damage the player takes, creature hits and hazards alike
+    pub player_damage_taken_scale: f32,
+
     // === WEAPON CONFIG ===
     pub fist_range: f32,
     pub knife_range: f32,
@@ -82,10 +213,16 @@ impl Default for GameConfig {
             block_angle_threshold: BLOCK_ANGLE_THRESHOLD,
             // Creature
             hostile_speed: HOSTILE_SPEED,
+            provoked_speed: PROVOKED_SPEED,
             goblin_sight_range: GOBLIN_SIGHT_RANGE,
+            goblin_view_angle: GOBLIN_VIEW_ANGLE,
+            detection_time: DETECTION_TIME,
             attack_cooldown_duration: ATTACK_COOLDOWN_DURATION,
             player_min_distance: PLAYER_MIN_DISTANCE,
             push_radius: PUSH_RADIUS,
+            separation_radius: SEPARATION_RADIUS,
+            obstacle_look_ahead: OBSTACLE_LOOK_AHEAD,
+            corpse_lifetime: CORPSE_LIFETIME,
             // Shared Combat
             knockback_force: KNOCKBACK_FORCE,
             attack_hit_delay_percent: ATTACK_HIT_DELAY_PERCENT,
@@ -93,11 +230,27 @@ impl Default for GameConfig {
             collision_radius: COLLISION_RADIUS,
             push_strength: PUSH_STRENGTH,
             stun_duration: STUN_DURATION,
+            combo_timeout: COMBO_TIMEOUT,
+            recoil_multiplier: RECOIL_MULTIPLIER,
             // Game Feel
             hitstop_duration: HITSTOP_DURATION,
             screen_shake_intensity: SCREEN_SHAKE_INTENSITY,
             screen_shake_duration: SCREEN_SHAKE_DURATION,
             hit_highlight_duration: HIT_HIGHLIGHT_DURATION,
+            // Camera
+            camera_base_zoom: CAMERA_BASE_ZOOM,
+            camera_zoom_out: CAMERA_ZOOM_OUT,
+            camera_zoom_speed: CAMERA_ZOOM_SPEED,
+            // Level
+            win_zone_time: WIN_ZONE_TIME,
+            pit_edge_resistance: PIT_EDGE_RESISTANCE,
+            // Difficulty
+            creature_health_scale: 1.0,
+            creature_damage_scale: 1.0,
+            creature_speed_scale: 1.0,
+            attack_cooldown_scale: 1.0,
+            sight_range_scale: 1.0,
+            player_damage_taken_scale: 1.0,
             // Weapon Config
             fist_range: FIST_RANGE,
             knife_range: KNIFE_RANGE,
@@ -109,87 +262,314 @@ impl Default for GameConfig {
     }
 }
 
+/// Declares every accepted config entry: its INI section and key, the `GameConfig` field
+/// it sets, the value type and the optional accepted range
+macro_rules! config_schema {
+    (@bound) => { None };
+    (@bound $value:literal) => { Some($value as f64) };
+    ($($section:literal {
+        $($key:literal => $field:ident: $kind:ident $(min $min:literal)? $(max $max:literal)?),* $(,)?
+    })*) => {
+        /// Sections accepted in the config file
+        const CONFIG_SECTIONS: &[&str] = &[$($section),*];
+
+        impl GameConfig {
+            /// Parse, range-check and apply one entry; the error describes what is wrong with it
+            fn set_value(&mut self, section: &str, key: &str, value: &str) -> Result<(), String> {
+                match (section, key) {
+                    $($(($section, $key) => {
+                        self.$field = parse_value::<$kind>(
+                            section,
+                            key,
+                            value,
+                            config_schema!(@bound $($min)?),
+                            config_schema!(@bound $($max)?),
+                        )?;
+                        Ok(())
+                    })*)*
+                    _ => Err(format!("unknown key '{}' in [{}]", key, section)),
+                }
+            }
+        }
+    };
+}
+
+config_schema! {
+    "player_movement" {
+        "speed" => player_speed: f32 min 0.0,
+        "acceleration" => player_acceleration: f32 min 0.0,
+        "friction" => player_friction: f32 min 0.0,
+    }
+    "player_dash" {
+        "speed" => dash_speed: f32 min 0.0,
+        "duration" => dash_duration: f32 min 0.0,
+        "cooldown" => dash_cooldown: f32 min 0.0,
+    }
+    "player_sprint" {
+        "min_multiplier" => sprint_min_multiplier: f32 min 0.0,
+        "max_multiplier" => sprint_max_multiplier: f32 min 0.0,
+        "ramp_time" => sprint_ramp_time: f32 min 0.01,
+        "momentum_friction" => sprint_momentum_friction: f32 min 0.0,
+        "decel_threshold" => sprint_decel_threshold: f32 min 0.0,
+    }
+    "player_blocking" {
+        "speed_multiplier" => blocking_speed_multiplier: f32 min 0.0 max 1.0,
+        "knockback" => block_knockback: f32 min 0.0,
+        "facing_offset" => block_facing_offset: f32,
+        "angle_threshold" => block_angle_threshold: f32 min -1.0 max 1.0,
+    }
+    "creature" {
+        "hostile_speed" => hostile_speed: f32 min 0.0,
+        "provoked_speed" => provoked_speed: f32 min 0.0,
+        "goblin_sight_range" => goblin_sight_range: f32 min 0.0,
+        "goblin_view_angle" => goblin_view_angle: f32 min 0.0 max 360.0,
+        "detection_time" => detection_time: f32 min 0.0,
+        "attack_cooldown" => attack_cooldown_duration: f32 min 0.0,
+        "player_min_distance" => player_min_distance: f32 min 0.0,
+        "push_radius" => push_radius: f32 min 0.0,
+        "separation_radius" => separation_radius: f32 min 0.0,
+        "obstacle_look_ahead" => obstacle_look_ahead: f32 min 0.0,
+        "corpse_lifetime" => corpse_lifetime: f32 min 0.0,
+    }
+    "combat" {
+        "knockback_force" => knockback_force: f32 min 0.0,
+        "attack_hit_delay_percent" => attack_hit_delay_percent: f32 min 0.0 max 1.0,
+        "attack_center_offset_y" => attack_center_offset_y: f32,
+        "collision_radius" => collision_radius: f32 min 0.0,
+        "push_strength" => push_strength: f32 min 0.0,
+        "stun_duration" => stun_duration: f32 min 0.0,
+        "combo_timeout" => combo_timeout: f32 min 0.0,
+        "recoil_multiplier" => recoil_multiplier: f32 min 0.0,
+    }
+    "game_feel" {
+        "hitstop_duration" => hitstop_duration: f32 min 0.0,
+        "screen_shake_intensity" => screen_shake_intensity: f32 min 0.0,
+        "screen_shake_duration" => screen_shake_duration: f32 min 0.0,
+        "hit_highlight_duration" => hit_highlight_duration: f32 min 0.0,
+    }
+    "camera" {
+        "base_zoom" => camera_base_zoom: f32 min 0.1 max 10.0,
+        "zoom_out" => camera_zoom_out: f32 min 0.1 max 10.0,
+        "zoom_speed" => camera_zoom_speed: f32 min 0.0,
+    }
+    "level" {
+        "win_zone_time" => win_zone_time: f32 min 0.0,
+        "pit_edge_resistance" => pit_edge_resistance: f32 min 0.0,
+    }
+    "difficulty" {
+        "creature_health" => creature_health_scale: f32 min 0.1 max 10.0,
+        "creature_damage" => creature_damage_scale: f32 min 0.0 max 10.0,
+        "creature_speed" => creature_speed_scale: f32 min 0.1 max 10.0,
+        "attack_cooldown" => attack_cooldown_scale: f32 min 0.1 max 10.0,
+        "sight_range" => sight_range_scale: f32 min 0.0 max 10.0,
+        "player_damage_taken" => player_damage_taken_scale: f32 min 0.0 max 10.0,
+    }
+    "weapon" {
+        "fist_range" => fist_range: f32 min 0.0,
+        "knife_range" => knife_range: f32 min 0.0,
+        "club_range" => club_range: f32 min 0.0,
+        "club_speed" => club_speed: i32 min 1 max 5,
+        "stick_range" => stick_range: f32 min 0.0,
+        "sword_range" => sword_range: f32 min 0.0,
+    }
+}
+
+/// Value types a config entry can hold
+trait ConfigValue: FromStr + Copy {
+    /// What the value should look like, for type errors
+    const EXPECTED: &'static str;
+    fn to_f64(self) -> f64;
+}
+
+impl ConfigValue for f32 {
+    const EXPECTED: &'static str = "a number";
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl ConfigValue for i32 {
+    const EXPECTED: &'static str = "a whole number";
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+fn parse_value<T: ConfigValue>(
+    section: &str,
+    key: &str,
+    value: &str,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> Result<T, String> {
+    let parsed = value
+        .parse::<T>()
+        .ok()
+        .filter(|parsed| parsed.to_f64().is_finite())
+        .ok_or_else(|| format!("[{}] {} expects {}, found '{}'", section, key, T::EXPECTED, value))?;
+
+    let number = parsed.to_f64();
+    let out_of_range = min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max);
+    if out_of_range {
+        let expected = match (min, max) {
+            (Some(min), Some(max)) => format!("between {} and {}", min, max),
+            (Some(min), None) => format!("at least {}", min),
+            (None, Some(max)) => format!("at most {}", max),
+            (None, None) => unreachable!(),
+        };
+        return Err(format!("[{}] {} = {} is out of range, expected {}", section, key, value, expected));
+    }
+    Ok(parsed)
+}
+
 impl GameConfig {
-    /// Load configuration from INI file with fallback to defaults for missing entries
-    pub fn load_from_file(path: &str) -> Self {
+    /// Goblin sight range on the current difficulty
+    pub fn creature_sight_range(&self) -> f32 {
+        self.goblin_sight_range * self.sight_range_scale
+    }
+
+    /// Seconds creatures wait between attacks on the current difficulty
+    pub fn creature_attack_cooldown(&self) -> f32 {
+        self.attack_cooldown_duration * self.attack_cooldown_scale
+    }
+
+    /// Chase speed of provoked creatures on the current difficulty
+    pub fn creature_provoked_speed(&self) -> f32 {
+        self.provoked_speed * self.creature_speed_scale
+    }
+
+    /// Load a stack of INI files over the compiled defaults. Files are applied in order, so
+    /// an entry set by several files takes its value from the last one. Missing files are
+    /// skipped; unreadable files and invalid entries keep the defaults and are listed in the report.
+    pub fn load_from_files(paths: &[impl AsRef<str>]) -> (Self, ConfigReport) {
         let mut config = Self::default();
+        let mut report = ConfigReport::default();
 
-        if !Path::new(path).exists() {
-            info!("Config file '{}' not found, using defaults", path);
-            return config;
+        for path in paths.iter().map(AsRef::as_ref) {
+            let mut source = ConfigSource { path: path.to_string(), found: true, applied: 0 };
+            match std::fs::read_to_string(path) {
+                Ok(contents) => source.applied = config.apply_ini(path, &contents, &mut report.diagnostics),
+                Err(error) if error.kind() == ErrorKind::NotFound => source.found = false,
+                Err(error) => report.diagnostics.push(ConfigDiagnostic {
+                    path: path.to_string(),
+                    line: None,
+                    message: format!("failed to read: {}", error),
+                }),
+            }
+            report.sources.push(source);
         }
 
-        let mut ini = Ini::new();
-        if let Err(e) = ini.load(path) {
-            warn!("Failed to parse config file '{}': {}, using defaults", path, e);
-            return config;
-        }
+        (config, report)
+    }
+
+    /// Apply every valid entry of an INI document, reporting the rest by line.
+    /// Returns the number of entries applied.
+    fn apply_ini(&mut self, path: &str, contents: &str, diagnostics: &mut Vec<ConfigDiagnostic>) -> usize {
+        let mut applied = 0;
+        let mut section: Option<&str> = None;
+        // Entries of an unknown section are skipped; the section itself was already reported
+        let mut in_unknown_section = false;
 
-        info!("Loading config from '{}'", path);
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let text = line.trim();
+            if text.is_empty() || text.starts_with(';') || text.starts_with('#') {
+                continue;
+            }
 
-        // Helper macro to reduce boilerplate
-        macro_rules! load_float {
-            ($section:expr, $key:expr, $field:ident) => {
-                if let Some(val) = ini.getfloat($section, $key).ok().flatten() {
-                    config.$field = val as f32;
+            let problem = if let Some(header) = text.strip_prefix('[') {
+                match header.strip_suffix(']').map(|name| name.trim().to_lowercase()) {
+                    Some(name) => {
+                        section = CONFIG_SECTIONS.iter().copied().find(|known| *known == name);
+                        in_unknown_section = section.is_none();
+                        in_unknown_section.then(|| format!("unknown section [{}]", name))
+                    }
+                    None => Some(format!("malformed section header '{}'", text)),
+                }
+            } else if let Some((key, value)) = text.split_once('=') {
+                let key = key.trim().to_lowercase();
+                match section {
+                    Some(section) => match self.set_value(section, &key, value.trim()) {
+                        Ok(()) => {
+                            applied += 1;
+                            None
+                        }
+                        Err(message) => Some(message),
+                    },
+                    None if in_unknown_section => None,
+                    None => Some(format!("key '{}' is outside of any section", key)),
                 }
+            } else {
+                Some(format!("expected 'key = value', found '{}'", text))
             };
+
+            if let Some(message) = problem {
+                diagnostics.push(ConfigDiagnostic {
+                    path: path.to_string(),
+                    line: Some(line_number),
+                    message,
+                });
+            }
         }
+        applied
+    }
+}
+
+/// Show the config summary read before the app started
+pub fn log_config_report(report: Res<ConfigReport>) {
+    report.log();
+}
+
+/// Poll the config stack and reload `GameConfig` when one of its files changes on disk,
+/// or right away when the difficulty swaps the preset layer.
+/// In strict mode a stack with problems is reported and the running config is left as it is.
+pub fn watch_config_file(
+    time: Res<Time>,
+    strict: Res<StrictConfig>,
+    difficulty: Res<Difficulty>,
+    mut watcher: ResMut<ConfigFileWatcher>,
+    mut config: ResMut<GameConfig>,
+    mut report: ResMut<ConfigReport>,
+    mut reloaded: MessageWriter<ConfigReloaded>,
+) {
+    let difficulty_changed = difficulty.is_changed() && !difficulty.is_added();
+    watcher.check_timer += time.delta_secs();
+    if watcher.check_timer < CONFIG_WATCH_INTERVAL && !difficulty_changed {
+        return;
+    }
+    watcher.check_timer = 0.0;
 
-        // Player Movement
-        load_float!("player_movement", "speed", player_speed);
-        load_float!("player_movement", "acceleration", player_acceleration);
-        load_float!("player_movement", "friction", player_friction);
-
-        // Player Dash
-        load_float!("player_dash", "speed", dash_speed);
-        load_float!("player_dash", "duration", dash_duration);
-        load_float!("player_dash", "cooldown", dash_cooldown);
-
-        // Player Sprint
-        load_float!("player_sprint", "min_multiplier", sprint_min_multiplier);
-        load_float!("player_sprint", "max_multiplier", sprint_max_multiplier);
-        load_float!("player_sprint", "ramp_time", sprint_ramp_time);
-        load_float!("player_sprint", "momentum_friction", sprint_momentum_friction);
-        load_float!("player_sprint", "decel_threshold", sprint_decel_threshold);
-
-        // Player Blocking
-        load_float!("player_blocking", "speed_multiplier", blocking_speed_multiplier);
-        load_float!("player_blocking", "knockback", block_knockback);
-        load_float!("player_blocking", "facing_offset", block_facing_offset);
-        load_float!("player_blocking", "angle_threshold", block_angle_threshold);
-
-        // Creature
-        load_float!("creature", "hostile_speed", hostile_speed);
-        load_float!("creature", "goblin_sight_range", goblin_sight_range);
-        load_float!("creature", "attack_cooldown", attack_cooldown_duration);
-        load_float!("creature", "player_min_distance", player_min_distance);
-        load_float!("creature", "push_radius", push_radius);
-
-        // Shared Combat
-        load_float!("combat", "knockback_force", knockback_force);
-        load_float!("combat", "attack_hit_delay_percent", attack_hit_delay_percent);
-        load_float!("combat", "attack_center_offset_y", attack_center_offset_y);
-        load_float!("combat", "collision_radius", collision_radius);
-        load_float!("combat", "push_strength", push_strength);
-        load_float!("combat", "stun_duration", stun_duration);
-
-        // Game Feel
-        load_float!("game_feel", "hitstop_duration", hitstop_duration);
-        load_float!("game_feel", "screen_shake_intensity", screen_shake_intensity);
-        load_float!("game_feel", "screen_shake_duration", screen_shake_duration);
-        load_float!("game_feel", "hit_highlight_duration", hit_highlight_duration);
-
-        // Weapon Config
-        load_float!("weapon", "fist_range", fist_range);
-        load_float!("weapon", "knife_range", knife_range);
-        load_float!("weapon", "club_range", club_range);
-        if let Some(val) = ini.getint("weapon", "club_speed").ok().flatten() {
-            config.club_speed = val as i32;
+    let stack = difficulty.config_stack();
+    let modified: Vec<Option<SystemTime>> = stack
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect();
+
+    // The config was read at startup; start watching without reloading it
+    if !watcher.watching && !difficulty_changed {
+        watcher.watching = true;
+        watcher.last_modified = modified;
+        return;
+    }
+    watcher.watching = true;
+
+    if !difficulty_changed {
+        // A file that vanished is most likely being saved; wait for it to come back
+        let vanished = modified.iter().zip(&watcher.last_modified).any(|(now, before)| now.is_none() && before.is_some());
+        if vanished || modified == watcher.last_modified {
+            return;
         }
-        load_float!("weapon", "stick_range", stick_range);
-        load_float!("weapon", "sword_range", sword_range);
+    }
+    watcher.last_modified = modified;
 
-        config
+    let (new_config, new_report) = GameConfig::load_from_files(&stack);
+    new_report.log();
+    if strict.0 && !new_report.diagnostics.is_empty() {
+        error!("Strict config: keeping the running config until the problems are fixed");
+        return;
     }
+    info!("Reloaded config for {} difficulty", difficulty.name());
+    *config = new_config;
+    *report = new_report;
+    reloaded.write(ConfigReloaded);
 }
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::CONFIG_FILE;
+
+/// Folder holding one config overlay per difficulty
+pub const DIFFICULTY_DIR: &str = "difficulty";
+
+/// Selected difficulty; picks the preset layered over config.ini
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    /// The next harder difficulty, wrapping around to Easy
+    pub fn next(&self) -> Difficulty {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn preset_file(&self) -> String {
+        format!("{}/{}.ini", DIFFICULTY_DIR, self.name().to_lowercase())
+    }
+
+    /// Config files in precedence order, lowest first: the base config, then the preset
+    pub fn config_stack(&self) -> Vec<String> {
+        vec![CONFIG_FILE.to_string(), self.preset_file()]
+    }
+}
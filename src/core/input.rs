@@ -1,8 +1,13 @@
 use bevy::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Controls file holding the player's bindings
+pub const CONTROLS_FILE: &str = "controls.ron";
 
 /// All game actions that can be bound to input
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Deserialize, Serialize)]
 pub enum GameAction {
     // Movement
     MoveUp,
@@ -27,52 +32,178 @@ pub enum GameAction {
     InventoryUse,
     InventoryPickup,
 
+    // World
+    Interact,
+
     // UI
     Pause,
+    QuickSave,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 20] = [
+        GameAction::MoveUp,
+        GameAction::MoveDown,
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::Sprint,
+        GameAction::Dash,
+        GameAction::Attack,
+        GameAction::Block,
+        GameAction::ToggleWeapon,
+        GameAction::ToggleInventory,
+        GameAction::Hotbar1,
+        GameAction::Hotbar2,
+        GameAction::Hotbar3,
+        GameAction::Hotbar4,
+        GameAction::Hotbar5,
+        GameAction::InventoryUse,
+        GameAction::InventoryPickup,
+        GameAction::Interact,
+        GameAction::Pause,
+        GameAction::QuickSave,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameAction::MoveUp => "Move up",
+            GameAction::MoveDown => "Move down",
+            GameAction::MoveLeft => "Move left",
+            GameAction::MoveRight => "Move right",
+            GameAction::Sprint => "Sprint",
+            GameAction::Dash => "Dash",
+            GameAction::Attack => "Attack",
+            GameAction::Block => "Block",
+            GameAction::ToggleWeapon => "Weapon",
+            GameAction::ToggleInventory => "Inventory",
+            GameAction::Hotbar1 => "Hotbar 1",
+            GameAction::Hotbar2 => "Hotbar 2",
+            GameAction::Hotbar3 => "Hotbar 3",
+            GameAction::Hotbar4 => "Hotbar 4",
+            GameAction::Hotbar5 => "Hotbar 5",
+            GameAction::InventoryUse => "Use item",
+            GameAction::InventoryPickup => "Move item",
+            GameAction::Interact => "Interact",
+            GameAction::Pause => "Pause",
+            GameAction::QuickSave => "Quick save",
+        }
+    }
+
+    /// Actions that can't be left unbound, or the run could no longer be paused or saved
+    pub fn is_required(&self) -> bool {
+        matches!(self, GameAction::Pause | GameAction::QuickSave)
+    }
+
+    /// Whether the action listens while playing and while the inventory is open
+    fn contexts(&self) -> (bool, bool) {
+        match self {
+            GameAction::InventoryUse | GameAction::InventoryPickup => (false, true),
+            GameAction::ToggleInventory | GameAction::Pause => (true, true),
+            _ => (true, false),
+        }
+    }
+
+    /// True when both actions can fire at the same time, so they must not share an input
+    fn overlaps(&self, other: GameAction) -> bool {
+        let (play, inventory) = self.contexts();
+        let (other_play, other_inventory) = other.contexts();
+        (play && other_play) || (inventory && other_inventory)
+    }
 }
 
 /// Input binding - either keyboard or mouse
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub enum InputBinding {
     Key(KeyCode),
     Mouse(MouseButton),
 }
 
-/// Central resource for all input bindings
-#[derive(Resource)]
+impl InputBinding {
+    /// Short name for the HUD and the controls menu, e.g. "W", "Space", "LMB"
+    pub fn label(&self) -> String {
+        match self {
+            InputBinding::Key(key) => match key {
+                KeyCode::ShiftLeft => "Shift".to_string(),
+                KeyCode::ShiftRight => "RShift".to_string(),
+                KeyCode::ControlLeft => "Ctrl".to_string(),
+                KeyCode::ControlRight => "RCtrl".to_string(),
+                KeyCode::AltLeft => "Alt".to_string(),
+                KeyCode::AltRight => "RAlt".to_string(),
+                KeyCode::Escape => "Esc".to_string(),
+                key => {
+                    let name = format!("{:?}", key);
+                    let short = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit"));
+                    short.map_or_else(|| name.clone(), str::to_string)
+                }
+            },
+            InputBinding::Mouse(button) => match button {
+                MouseButton::Left => "LMB".to_string(),
+                MouseButton::Right => "RMB".to_string(),
+                MouseButton::Middle => "MMB".to_string(),
+                MouseButton::Back => "Mouse4".to_string(),
+                MouseButton::Forward => "Mouse5".to_string(),
+                MouseButton::Other(index) => format!("Mouse{}", index),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputBindingsError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: ron::error::SpannedError },
+    Serialize { path: String, error: ron::Error },
+}
+
+impl fmt::Display for InputBindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputBindingsError::Io { path, error } => write!(f, "Failed to access controls file {}: {}", path, error),
+            InputBindingsError::Parse { path, error } => write!(f, "Failed to parse controls file {}: {}", path, error),
+            InputBindingsError::Serialize { path, error } => write!(f, "Failed to serialize controls for {}: {}", path, error),
+        }
+    }
+}
+
+/// Central resource for all input bindings; an action fires from any of its bindings
+#[derive(Resource, Clone, Deserialize, Serialize)]
 pub struct InputBindings {
-    bindings: HashMap<GameAction, InputBinding>,
+    bindings: BTreeMap<GameAction, Vec<InputBinding>>,
 }
 
 impl Default for InputBindings {
     fn default() -> Self {
-        let mut bindings = HashMap::new();
+        let mut bindings = BTreeMap::new();
 
         // Movement
-        bindings.insert(GameAction::MoveUp, InputBinding::Key(KeyCode::KeyW));
-        bindings.insert(GameAction::MoveDown, InputBinding::Key(KeyCode::KeyS));
-        bindings.insert(GameAction::MoveLeft, InputBinding::Key(KeyCode::KeyA));
-        bindings.insert(GameAction::MoveRight, InputBinding::Key(KeyCode::KeyD));
-        bindings.insert(GameAction::Sprint, InputBinding::Key(KeyCode::ShiftLeft));
-        bindings.insert(GameAction::Dash, InputBinding::Key(KeyCode::Space));
+        bindings.insert(GameAction::MoveUp, vec![InputBinding::Key(KeyCode::KeyW)]);
+        bindings.insert(GameAction::MoveDown, vec![InputBinding::Key(KeyCode::KeyS)]);
+        bindings.insert(GameAction::MoveLeft, vec![InputBinding::Key(KeyCode::KeyA)]);
+        bindings.insert(GameAction::MoveRight, vec![InputBinding::Key(KeyCode::KeyD)]);
+        bindings.insert(GameAction::Sprint, vec![InputBinding::Key(KeyCode::ShiftLeft)]);
+        bindings.insert(GameAction::Dash, vec![InputBinding::Key(KeyCode::Space)]);
 
         // Combat
-        bindings.insert(GameAction::Attack, InputBinding::Mouse(MouseButton::Left));
-        bindings.insert(GameAction::Block, InputBinding::Mouse(MouseButton::Right));
-        bindings.insert(GameAction::ToggleWeapon, InputBinding::Key(KeyCode::KeyR));
+        bindings.insert(GameAction::Attack, vec![InputBinding::Mouse(MouseButton::Left)]);
+        bindings.insert(GameAction::Block, vec![InputBinding::Mouse(MouseButton::Right)]);
+        bindings.insert(GameAction::ToggleWeapon, vec![InputBinding::Key(KeyCode::KeyR)]);
 
         // Inventory
-        bindings.insert(GameAction::ToggleInventory, InputBinding::Key(KeyCode::Tab));
-        bindings.insert(GameAction::Hotbar1, InputBinding::Key(KeyCode::Digit1));
-        bindings.insert(GameAction::Hotbar2, InputBinding::Key(KeyCode::Digit2));
-        bindings.insert(GameAction::Hotbar3, InputBinding::Key(KeyCode::Digit3));
-        bindings.insert(GameAction::Hotbar4, InputBinding::Key(KeyCode::Digit4));
-        bindings.insert(GameAction::Hotbar5, InputBinding::Key(KeyCode::Digit5));
-        bindings.insert(GameAction::InventoryUse, InputBinding::Mouse(MouseButton::Right));
-        bindings.insert(GameAction::InventoryPickup, InputBinding::Mouse(MouseButton::Left));
+        bindings.insert(GameAction::ToggleInventory, vec![InputBinding::Key(KeyCode::Tab)]);
+        bindings.insert(GameAction::Hotbar1, vec![InputBinding::Key(KeyCode::Digit1)]);
+        bindings.insert(GameAction::Hotbar2, vec![InputBinding::Key(KeyCode::Digit2)]);
+        bindings.insert(GameAction::Hotbar3, vec![InputBinding::Key(KeyCode::Digit3)]);
+        bindings.insert(GameAction::Hotbar4, vec![InputBinding::Key(KeyCode::Digit4)]);
+        bindings.insert(GameAction::Hotbar5, vec![InputBinding::Key(KeyCode::Digit5)]);
+        bindings.insert(GameAction::InventoryUse, vec![InputBinding::Mouse(MouseButton::Right)]);
+        bindings.insert(GameAction::InventoryPickup, vec![InputBinding::Mouse(MouseButton::Left)]);
+
+        // World
+        bindings.insert(GameAction::Interact, vec![InputBinding::Key(KeyCode::KeyE)]);
 
         // UI
-        bindings.insert(GameAction::Pause, InputBinding::Key(KeyCode::Escape));
+        bindings.insert(GameAction::Pause, vec![InputBinding::Key(KeyCode::Escape)]);
+        bindings.insert(GameAction::QuickSave, vec![InputBinding::Key(KeyCode::F5)]);
 
         Self { bindings }
     }
@@ -86,11 +217,10 @@ impl InputBindings {
         keyboard: &ButtonInput<KeyCode>,
         mouse: &ButtonInput<MouseButton>,
     ) -> bool {
-        match self.bindings.get(&action) {
-            Some(InputBinding::Key(key)) => keyboard.pressed(*key),
-            Some(InputBinding::Mouse(btn)) => mouse.pressed(*btn),
-            None => false,
-        }
+        self.get(action).iter().any(|binding| match binding {
+            InputBinding::Key(key) => keyboard.pressed(*key),
+            InputBinding::Mouse(btn) => mouse.pressed(*btn),
+        })
     }
 
     /// Check if action was just pressed this frame
@@ -100,11 +230,10 @@ impl InputBindings {
         keyboard: &ButtonInput<KeyCode>,
         mouse: &ButtonInput<MouseButton>,
     ) -> bool {
-        match self.bindings.get(&action) {
-            Some(InputBinding::Key(key)) => keyboard.just_pressed(*key),
-            Some(InputBinding::Mouse(btn)) => mouse.just_pressed(*btn),
-            None => false,
-        }
+        self.get(action).iter().any(|binding| match binding {
+            InputBinding::Key(key) => keyboard.just_pressed(*key),
+            InputBinding::Mouse(btn) => mouse.just_pressed(*btn),
+        })
     }
 
     /// Check if action was just released this frame
@@ -114,10 +243,104 @@ impl InputBindings {
         keyboard: &ButtonInput<KeyCode>,
         mouse: &ButtonInput<MouseButton>,
     ) -> bool {
-        match self.bindings.get(&action) {
-            Some(InputBinding::Key(key)) => keyboard.just_released(*key),
-            Some(InputBinding::Mouse(btn)) => mouse.just_released(*btn),
-            None => false,
+        self.get(action).iter().any(|binding| match binding {
+            InputBinding::Key(key) => keyboard.just_released(*key),
+            InputBinding::Mouse(btn) => mouse.just_released(*btn),
+        })
+    }
+
+    /// Bindings of an action; empty when it is unbound
+    pub fn get(&self, action: GameAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Labels of an action's bindings joined for display, e.g. "Space/RShift"
+    pub fn label(&self, action: GameAction) -> String {
+        let labels: Vec<String> = self.get(action).iter().map(InputBinding::label).collect();
+        if labels.is_empty() {
+            return "-".to_string();
+        }
+        labels.join("/")
+    }
+
+    /// An action that can fire alongside `action` and is already bound to `binding`
+    pub fn conflict(&self, action: GameAction, binding: InputBinding) -> Option<GameAction> {
+        self.bindings
+            .iter()
+            .find(|(other, bindings)| **other != action && action.overlaps(**other) && bindings.contains(&binding))
+            .map(|(other, _)| *other)
+    }
+
+    /// Every pair of actions that fire from the same input, each pair listed once
+    pub fn conflicts(&self) -> Vec<(InputBinding, GameAction, GameAction)> {
+        let mut conflicts = Vec::new();
+        for (action, bindings) in &self.bindings {
+            for (other, other_bindings) in self.bindings.iter().filter(|(other, _)| *other > action) {
+                for binding in bindings.iter().filter(|binding| other_bindings.contains(binding)) {
+                    if action.overlaps(*other) {
+                        conflicts.push((*binding, *action, *other));
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Bind another input to an action. Fails with the conflicting action when the
+    /// input already triggers an action that can fire at the same time.
+    pub fn add(&mut self, action: GameAction, binding: InputBinding) -> Result<(), GameAction> {
+        if let Some(other) = self.conflict(action, binding) {
+            return Err(other);
         }
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        Ok(())
+    }
+
+    /// Remove every binding of an action. Returns false and keeps the bindings for required actions.
+    pub fn clear(&mut self, action: GameAction) -> bool {
+        if action.is_required() {
+            return false;
+        }
+        self.bindings.insert(action, Vec::new());
+        true
+    }
+
+    /// Read bindings from a controls file. Actions missing from the file keep their defaults,
+    /// as do required actions the file leaves unbound.
+    pub fn load_from_file(path: &str) -> Result<Self, InputBindingsError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| InputBindingsError::Io { path: path.to_string(), error })?;
+        let loaded: Self = ron::from_str(&contents)
+            .map_err(|error| InputBindingsError::Parse { path: path.to_string(), error })?;
+
+        let mut bindings = Self::default();
+        bindings.bindings.extend(
+            loaded.bindings.into_iter().filter(|(action, inputs)| !(action.is_required() && inputs.is_empty())),
+        );
+        Ok(bindings)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), InputBindingsError> {
+        let config = ron::ser::PrettyConfig::new().indentor("    ".to_string());
+        let contents = ron::ser::to_string_pretty(self, config)
+            .map_err(|error| InputBindingsError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| InputBindingsError::Io { path: path.to_string(), error })
+    }
+}
+
+/// Read the controls file at startup and report inputs shared by actions that fire together.
+/// Without a controls file the default bindings are used.
+pub fn load_input_bindings(mut bindings: ResMut<InputBindings>) {
+    match InputBindings::load_from_file(CONTROLS_FILE) {
+        Ok(loaded) => *bindings = loaded,
+        Err(InputBindingsError::Io { error, .. }) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => error!("{}", error),
+    }
+    for (binding, action, other) in bindings.conflicts() {
+        warn!("{} is bound to both {} and {}", binding.label(), action.name(), other.name());
     }
 }
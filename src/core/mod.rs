@@ -3,6 +3,7 @@ pub mod collisions;
 pub mod components;
 pub mod config;
 pub mod depth;
+pub mod difficulty;
 pub mod input;
 pub mod state;
 pub mod systems;
@@ -12,6 +13,7 @@ pub use collisions::*;
 pub use components::*;
 pub use config::*;
 pub use depth::*;
+pub use difficulty::*;
 pub use input::*;
 pub use state::*;
 pub use systems::*;
@@ -22,7 +24,12 @@ pub struct CorePlugin;
 
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (update_stun, update_despawn_timer))
+        app.init_resource::<ConfigFileWatcher>()
+            .init_resource::<StrictConfig>()
+            .init_resource::<Difficulty>()
+            .add_message::<ConfigReloaded>()
+            .add_systems(Startup, (log_config_report, load_input_bindings))
+            .add_systems(Update, (update_stun, update_despawn_timer, watch_config_file))
             .add_systems(
                 PostUpdate,
                 update_y_depth.run_if(in_state(GameState::Playing)),
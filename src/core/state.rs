@@ -6,6 +6,11 @@ pub enum GameState {
     Loading,
     Playing,
     Paused,
+    LevelComplete,
+    Respawning,
+    LoadingSave,
     Dead,
     Victory,
+    LevelError,
+    Editor,
 }
@@ -1,11 +1,16 @@
 use bevy::prelude::*;
 
+use crate::levels::{PatrolRouteData, PatrolRouteMode};
 use super::data::SteeringConfig;
 
 /// Creature marker component
 #[derive(Component)]
 pub struct Creature;
 
+/// Creature type name from its `CreatureDefinition`, e.g. "Goblin"
+#[derive(Component, Clone, Debug)]
+pub struct CreatureKind(pub String);
+
 #[derive(Component)]
 pub struct CreatureAnimation {
     pub phase: f32,
@@ -80,3 +85,58 @@ pub struct PatrolWander {
 
 #[derive(Component)]
 pub struct Rushing;
+
+/// Authored waypoints a patrolling creature walks instead of wandering
+#[derive(Component)]
+pub struct PatrolRoute {
+    pub route: PatrolRouteData,
+    /// Index of the waypoint being walked to
+    pub next: usize,
+    /// Walking the waypoints backwards, for ping-pong routes
+    pub reversing: bool,
+    /// Seconds left standing at the waypoint just reached
+    pub wait_timer: f32,
+}
+
+impl PatrolRoute {
+    pub fn new(route: PatrolRouteData) -> Self {
+        Self { route, next: 0, reversing: false, wait_timer: 0.0 }
+    }
+
+    pub fn target(&self) -> Option<Vec2> {
+        self.route.waypoints.get(self.next).copied()
+    }
+
+    /// Head for the waypoint after the current one and start waiting
+    pub fn advance(&mut self) {
+        let count = self.route.waypoints.len();
+        self.wait_timer = self.route.wait;
+        if count < 2 {
+            return;
+        }
+        match self.route.mode {
+            PatrolRouteMode::Loop => self.next = (self.next + 1) % count,
+            PatrolRouteMode::PingPong => {
+                if self.next == 0 {
+                    self.reversing = false;
+                } else if self.next == count - 1 {
+                    self.reversing = true;
+                }
+                self.next = if self.reversing { self.next - 1 } else { self.next + 1 };
+            }
+        }
+    }
+
+    /// Rejoin the route at the waypoint closest to `position`
+    pub fn rejoin(&mut self, position: Vec2) {
+        let closest = self.route.waypoints
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.distance_squared(position).total_cmp(&b.distance_squared(position)))
+            .map(|(index, _)| index);
+        if let Some(index) = closest {
+            self.next = index;
+        }
+        self.wait_timer = 0.0;
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
-use crate::constants::{OBSTACLE_LOOK_AHEAD, PLAYER_MIN_DISTANCE, SEPARATION_RADIUS};
+use crate::constants::{DETECTION_TIME, HOSTILE_SPEED, OBSTACLE_LOOK_AHEAD, PLAYER_MIN_DISTANCE, SEPARATION_RADIUS};
+use crate::core::GameConfig;
 
 /// Steering behavior strategy
 #[derive(Clone, Copy, Default, Debug)]
@@ -15,6 +16,10 @@ pub enum SteeringStrategy {
 pub struct SteeringConfig {
     pub strategy: SteeringStrategy,
     pub sight_range: f32,
+    /// Width of the view cone in radians; TAU sees all around
+    pub view_angle: f32,
+    /// Seconds the player must stay in sight before the creature gives chase
+    pub detection_time: f32,
     pub chase_range: f32,
     pub patrol_radius: f32,
     pub obstacle_look_ahead: f32,
@@ -30,6 +35,8 @@ impl Default for SteeringConfig {
         Self {
             strategy: SteeringStrategy::Direct,
             sight_range: 150.0,
+            view_angle: std::f32::consts::TAU,
+            detection_time: DETECTION_TIME,
             chase_range: 300.0,
             patrol_radius: 60.0,
             obstacle_look_ahead: OBSTACLE_LOOK_AHEAD,
@@ -42,6 +49,14 @@ impl Default for SteeringConfig {
     }
 }
 
+impl SteeringConfig {
+    /// Take the obstacle and crowd avoidance distances from the config
+    pub fn apply_config(&mut self, config: &GameConfig) {
+        self.obstacle_look_ahead = config.obstacle_look_ahead;
+        self.separation_radius = config.separation_radius;
+    }
+}
+
 /// Identifier for creature types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CreatureId {
@@ -94,6 +109,19 @@ pub struct CreatureDefinition {
     pub provoked_steering: SteeringConfig,
 }
 
+impl CreatureDefinition {
+    /// Chase speed once hostile. Catalog speeds are tuned against `HOSTILE_SPEED`,
+    /// so the config's `hostile_speed` scales every creature type alike.
+    pub fn hostile_speed(&self, config: &GameConfig) -> f32 {
+        self.speed * config.hostile_speed / HOSTILE_SPEED * config.creature_speed_scale
+    }
+
+    /// Starting health on the current difficulty, never below 1
+    pub fn scaled_health(&self, config: &GameConfig) -> i32 {
+        ((self.health as f32 * config.creature_health_scale).round() as i32).max(1)
+    }
+}
+
 pub mod creature_catalog {
     use super::*;
 
@@ -7,3 +7,10 @@ pub struct PlayerInRange {
     #[allow(dead_code)]
     pub distance: f32,
 }
+
+/// Emitted when a creature finishes dying and drops its loot
+#[derive(Event, Message, Debug)]
+pub struct CreatureKilled {
+    /// Type name of the creature, from `CreatureKind`
+    pub kind: String,
+}
@@ -1,6 +1,8 @@
 pub mod components;
 pub mod data;
 pub mod events;
+pub mod navigation;
+pub mod perception;
 pub mod spawner;
 pub mod state;
 pub mod state_handlers;
@@ -10,6 +12,8 @@ pub mod systems;
 pub use components::*;
 pub use data::*;
 pub use events::*;
+pub use navigation::*;
+pub use perception::*;
 pub use spawner::*;
 pub use state::*;
 pub use state_handlers::*;
@@ -32,7 +36,9 @@ impl Plugin for CreaturePlugin {
         register_state_type::<CreatureState>(app);
 
         // Register creature events
-        app.add_message::<PlayerInRange>();
+        app.add_message::<PlayerInRange>()
+            .add_message::<CreatureKilled>();
+        app.init_resource::<NavGrid>();
 
         app.add_systems(
             Update,
@@ -44,6 +50,8 @@ impl Plugin for CreaturePlugin {
                 on_stun_recovered.in_set(StateMachineSet::OnExit),
                 on_hostile_start_patrol.in_set(StateMachineSet::OnEnter),
                 on_activated_to_chase.in_set(StateMachineSet::OnEnter),
+                on_patrol_rejoin_route.in_set(StateMachineSet::OnEnter),
+                on_patrol_reset_awareness.in_set(StateMachineSet::OnEnter),
                 on_alert_enter.in_set(StateMachineSet::OnEnter),
                 on_alert_exit.in_set(StateMachineSet::OnExit),
                 on_deactivated_to_patrol.in_set(StateMachineSet::OnExit),
@@ -55,8 +63,10 @@ impl Plugin for CreaturePlugin {
             Update,
             (
                 detect_player_proximity.in_set(StateMachineSet::Behavior),
+                update_awareness.in_set(StateMachineSet::Behavior).before(patrol_ai).before(alert_ai),
                 patrol_ai.in_set(StateMachineSet::Behavior),
                 alert_ai.in_set(StateMachineSet::Behavior),
+                rebuild_nav_grid.before(StateMachineSet::Behavior),
                 hostile_ai.in_set(StateMachineSet::Behavior),
                 hostile_fist_aim.in_set(StateMachineSet::Behavior),
                 hostile_attack.in_set(StateMachineSet::Behavior).after(detect_player_proximity),
@@ -80,5 +90,7 @@ impl Plugin for CreaturePlugin {
             )
                 .run_if(in_state(GameState::Playing)),
         );
+
+        app.add_systems(Update, apply_config_to_creatures);
     }
 }
@@ -0,0 +1,237 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::*;
+
+use crate::core::StaticCollider;
+use crate::levels::{CurrentLevel, LevelData};
+use super::collider_footprints;
+
+/// Side of a navigation cell in pixels
+const NAV_CELL_SIZE: f32 = 12.0;
+/// Room kept around colliders so paths don't hug them
+const NAV_CLEARANCE: f32 = 7.0;
+/// How many cells around a blocked position to search for a free one
+const NAV_SNAP_CELLS: i32 = 4;
+/// Repath once the goal has moved this far from where the path was planned to
+const NAV_REPATH_DISTANCE: f32 = 48.0;
+/// Distance at which a path waypoint counts as reached
+const NAV_WAYPOINT_REACH: f32 = 8.0;
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// Grid of walkable cells over the level: walkable ground minus static colliders, pits and barriers.
+/// Positions are feet positions, like the colliders they avoid.
+#[derive(Resource, Default)]
+pub struct NavGrid {
+    origin: Vec2,
+    width: i32,
+    height: i32,
+    blocked: Vec<bool>,
+    /// Bumped on every rebuild so paths planned on an older grid get replanned
+    pub version: u32,
+}
+
+impl NavGrid {
+    /// Rebuild the grid for `level` around colliders given as (center, radii)
+    pub fn rebuild(&mut self, level: &LevelData, colliders: &[(Vec2, Vec2)]) {
+        let size = level.bounds.max - level.bounds.min;
+        self.origin = level.bounds.min;
+        self.width = (size.x / NAV_CELL_SIZE).ceil().max(0.0) as i32;
+        self.height = (size.y / NAV_CELL_SIZE).ceil().max(0.0) as i32;
+        self.version = self.version.wrapping_add(1);
+
+        let inside_collider = |point: Vec2| {
+            colliders.iter().any(|(center, radii)| {
+                let d = (point - *center) / (*radii + Vec2::splat(NAV_CLEARANCE));
+                d.length_squared() <= 1.0
+            })
+        };
+        let in_pit = |point: Vec2| level.pits.iter().any(|pit| point.distance(pit.position) <= pit.edge_radius);
+
+        self.blocked = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| IVec2::new(x, y)))
+            .map(|cell| {
+                let center = self.cell_center(cell);
+                !level.is_walkable(center) || inside_collider(center) || in_pit(center)
+            })
+            .collect();
+    }
+
+    fn cell_at(&self, point: Vec2) -> IVec2 {
+        ((point - self.origin) / NAV_CELL_SIZE).floor().as_ivec2()
+    }
+
+    fn cell_center(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + Vec2::splat(0.5)) * NAV_CELL_SIZE
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let inside = cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height;
+        inside.then(|| (cell.y * self.width + cell.x) as usize)
+    }
+
+    fn is_free(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|i| !self.blocked[i])
+    }
+
+    /// Closest free cell to `point`, looking a few cells around it when its own cell is blocked
+    fn free_cell_near(&self, point: Vec2) -> Option<IVec2> {
+        let cell = self.cell_at(point);
+        (-NAV_SNAP_CELLS..=NAV_SNAP_CELLS)
+            .flat_map(|dy| (-NAV_SNAP_CELLS..=NAV_SNAP_CELLS).map(move |dx| cell + IVec2::new(dx, dy)))
+            .filter(|c| self.is_free(*c))
+            .min_by(|a, b| {
+                let da = self.cell_center(*a).distance_squared(point);
+                let db = self.cell_center(*b).distance_squared(point);
+                da.total_cmp(&db)
+            })
+    }
+
+    /// True when a straight walk from `from` to `to` crosses no blocked cells.
+    /// Always true before a level has been loaded.
+    pub fn line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        if self.blocked.is_empty() {
+            return true;
+        }
+        let steps = (from.distance(to) / (NAV_CELL_SIZE * 0.5)).ceil().max(1.0) as usize;
+        (0..=steps).all(|step| {
+            let cell = self.cell_at(from.lerp(to, step as f32 / steps as f32));
+            // Start and end may sit on a blocked cell, e.g. right next to a pillar
+            self.is_free(cell) || cell == self.cell_at(from) || cell == self.cell_at(to)
+        })
+    }
+
+    /// A* from `from` to `to`, smoothed so each waypoint is in a straight line of the previous one.
+    /// The path excludes `from`; None when `to` can't be reached.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.free_cell_near(from)?;
+        let goal = self.free_cell_near(to)?;
+
+        let heuristic = |cell: IVec2| {
+            let d = (goal - cell).abs();
+            STRAIGHT_COST * d.max_element() as u32 + (DIAGONAL_COST - STRAIGHT_COST) * d.min_element() as u32
+        };
+
+        let count = self.blocked.len();
+        let mut cost = vec![u32::MAX; count];
+        let mut came_from: Vec<Option<IVec2>> = vec![None; count];
+        let mut open = BinaryHeap::new();
+
+        cost[self.index(start)?] = 0;
+        open.push(Reverse((heuristic(start), start.x, start.y)));
+
+        while let Some(Reverse((estimate, x, y))) = open.pop() {
+            let cell = IVec2::new(x, y);
+            if cell == goal {
+                break;
+            }
+            let current_cost = cost[self.index(cell)?];
+            // Outdated entry for a cell reached more cheaply since
+            if estimate > current_cost + heuristic(cell) {
+                continue;
+            }
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let next = cell + IVec2::new(dx, dy);
+                    if !self.is_free(next) {
+                        continue;
+                    }
+                    let diagonal = dx != 0 && dy != 0;
+                    // No cutting corners past blocked cells
+                    if diagonal && !(self.is_free(cell + IVec2::new(dx, 0)) && self.is_free(cell + IVec2::new(0, dy))) {
+                        continue;
+                    }
+                    let next_cost = current_cost + if diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+                    let next_index = self.index(next)?;
+                    if next_cost < cost[next_index] {
+                        cost[next_index] = next_cost;
+                        came_from[next_index] = Some(cell);
+                        open.push(Reverse((next_cost + heuristic(next), next.x, next.y)));
+                    }
+                }
+            }
+        }
+
+        if cost[self.index(goal)?] == u32::MAX {
+            return None;
+        }
+
+        let mut cells = vec![goal];
+        while let Some(previous) = came_from[self.index(*cells.last()?)?] {
+            cells.push(previous);
+        }
+        cells.reverse();
+
+        let mut points: Vec<Vec2> = cells.into_iter().skip(1).map(|cell| self.cell_center(cell)).collect();
+        if let Some(last) = points.last_mut() {
+            *last = to;
+        }
+
+        // Skip every waypoint that can be cut straight past
+        let mut path = Vec::new();
+        let mut anchor = from;
+        let mut i = 0;
+        while i < points.len() {
+            let mut furthest = i;
+            while furthest + 1 < points.len() && self.line_clear(anchor, points[furthest + 1]) {
+                furthest += 1;
+            }
+            path.push(points[furthest]);
+            anchor = points[furthest];
+            i = furthest + 1;
+        }
+        Some(path)
+    }
+}
+
+/// Path a chasing creature follows around obstacles, in feet positions
+#[derive(Component, Default)]
+pub struct NavPath {
+    pub waypoints: Vec<Vec2>,
+    /// Where the path was planned to; None until the first search
+    pub goal: Option<Vec2>,
+    /// `NavGrid::version` the path was planned on
+    pub version: u32,
+}
+
+impl NavPath {
+    /// Next point to head for on the way from `from` to `goal`, replanning when the goal
+    /// has moved far or the grid changed. None when there is no path.
+    pub fn next_waypoint(&mut self, grid: &NavGrid, from: Vec2, goal: Vec2) -> Option<Vec2> {
+        let stale = self.version != grid.version
+            || self.goal.is_none_or(|planned| planned.distance(goal) > NAV_REPATH_DISTANCE);
+        if stale {
+            self.waypoints = grid.find_path(from, goal).unwrap_or_default();
+            self.goal = Some(goal);
+            self.version = grid.version;
+        }
+
+        while self.waypoints.first().is_some_and(|waypoint| waypoint.distance(from) <= NAV_WAYPOINT_REACH) {
+            self.waypoints.remove(0);
+        }
+        self.waypoints.first().copied()
+    }
+}
+
+/// Rebuild the navigation grid when the level or its static colliders change
+pub fn rebuild_nav_grid(
+    mut nav_grid: ResMut<NavGrid>,
+    current_level: Res<CurrentLevel>,
+    added_colliders: Query<(), Added<StaticCollider>>,
+    mut removed_colliders: RemovedComponents<StaticCollider>,
+    collider_query: Query<(&Transform, &StaticCollider)>,
+) {
+    let colliders_changed = removed_colliders.read().count() > 0 || !added_colliders.is_empty();
+    if !current_level.is_changed() && !colliders_changed {
+        return;
+    }
+    let Some(level) = current_level.data.as_ref() else { return };
+
+    nav_grid.rebuild(level, &collider_footprints(collider_query.iter()));
+}
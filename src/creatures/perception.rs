@@ -0,0 +1,131 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::core::{Dead, DeathAnimation, StaticCollider, Stunned, WalkCollider};
+use crate::levels::{CurrentLevel, LevelData};
+use crate::player::Player;
+use crate::state_machine::{StateEntered, StateMachine};
+use super::{CreatureState, CreatureSteering, Hostile, PatrolWander};
+
+/// Spacing of the points checked along a sight line
+const SIGHT_SAMPLE_STEP: f32 = 4.0;
+/// Awareness drains at this fraction of the rate it builds up
+const AWARENESS_DECAY: f32 = 0.5;
+
+type LivingPlayer = (With<Player>, Without<Dead>);
+type Watcher = (
+    &'static Transform,
+    Option<&'static WalkCollider>,
+    &'static CreatureSteering,
+    &'static StateMachine<CreatureState>,
+    &'static PatrolWander,
+    &'static mut Awareness,
+);
+type AbleWatcher = (With<Hostile>, Without<Dead>, Without<DeathAnimation>, Without<Stunned>);
+
+/// How sure a creature is that it has spotted the player, from 0 (unaware) to 1 (gives chase)
+#[derive(Component, Default)]
+pub struct Awareness(pub f32);
+
+/// Footprints of static colliders as (center, radii), for sight checks
+pub fn collider_footprints<'a>(
+    colliders: impl Iterator<Item = (&'a Transform, &'a StaticCollider)>,
+) -> Vec<(Vec2, Vec2)> {
+    colliders
+        .map(|(t, c)| {
+            let center = t.translation.truncate() + Vec2::new(c.offset_x, c.offset_y);
+            (center, Vec2::new(c.radius_x, c.radius_y))
+        })
+        .collect()
+}
+
+/// True when nothing blocks sight between two feet positions: no static collider
+/// footprint (pillars, crates, closed doors) and no non-walkable space
+pub fn has_line_of_sight(level: Option<&LevelData>, colliders: &[(Vec2, Vec2)], from: Vec2, to: Vec2) -> bool {
+    let inside = |point: Vec2, (center, radii): &(Vec2, Vec2)| ((point - *center) / *radii).length_squared() <= 1.0;
+    // Colliders around either end would hide everyone standing next to them
+    let blocking: Vec<&(Vec2, Vec2)> = colliders
+        .iter()
+        .filter(|collider| !inside(from, collider) && !inside(to, collider))
+        .collect();
+
+    let steps = (from.distance(to) / SIGHT_SAMPLE_STEP).ceil().max(1.0) as usize;
+    (1..steps).all(|step| {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        level.is_none_or(|level| level.is_walkable(point))
+            && !blocking.iter().any(|collider| inside(point, collider))
+    })
+}
+
+/// True when `to_target` lies within a cone of `view_angle` radians around `facing`.
+/// Creatures without a facing see all around.
+pub fn in_view_cone(facing: Vec2, to_target: Vec2, view_angle: f32) -> bool {
+    if view_angle >= TAU || facing == Vec2::ZERO || to_target == Vec2::ZERO {
+        return true;
+    }
+    facing.angle_to(to_target).abs() <= view_angle * 0.5
+}
+
+/// Build up awareness while patrolling or alert creatures can see the player, faster when
+/// the player is close, and let it drain once they lose sight
+pub fn update_awareness(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    player_query: Query<(&Transform, Option<&WalkCollider>), LivingPlayer>,
+    collider_query: Query<(&Transform, &StaticCollider)>,
+    mut creature_query: Query<Watcher, AbleWatcher>,
+) {
+    let player = player_query.single().ok();
+    let colliders = collider_footprints(collider_query.iter());
+    let level = current_level.data.as_ref();
+
+    for (transform, walk_collider, steering, state_machine, wander, mut awareness) in &mut creature_query {
+        let state = *state_machine.current();
+        if !matches!(state, CreatureState::Patrol | CreatureState::Alert) {
+            continue;
+        }
+
+        let config = &steering.0;
+        let creature_pos = transform.translation.truncate();
+        let rate = time.delta_secs() / config.detection_time.max(f32::EPSILON);
+
+        let sighting = player.and_then(|(player_transform, player_walk_collider)| {
+            let player_pos = player_transform.translation.truncate();
+            let distance = player_pos.distance(creature_pos);
+            if distance > config.sight_range {
+                return None;
+            }
+            // Alert creatures have already turned to face the player
+            if state == CreatureState::Patrol && !in_view_cone(wander.direction, player_pos - creature_pos, config.view_angle) {
+                return None;
+            }
+            let creature_feet = creature_pos + Vec2::new(0.0, walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+            let player_feet = player_pos + Vec2::new(0.0, player_walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+            has_line_of_sight(level, &colliders, creature_feet, player_feet).then_some(distance)
+        });
+
+        awareness.0 = match sighting {
+            Some(distance) => {
+                let closeness = 1.0 - distance / config.sight_range.max(f32::EPSILON);
+                (awareness.0 + rate * (1.0 + closeness)).min(1.0)
+            }
+            None => (awareness.0 - rate * AWARENESS_DECAY).max(0.0),
+        };
+    }
+}
+
+/// Creatures going back to patrol start out unaware again
+pub fn on_patrol_reset_awareness(
+    mut events: MessageReader<StateEntered<CreatureState>>,
+    mut creature_query: Query<&mut Awareness>,
+) {
+    for event in events.read() {
+        if event.state != CreatureState::Patrol {
+            continue;
+        }
+        if let Ok(mut awareness) = creature_query.get_mut(event.entity) {
+            awareness.0 = 0.0;
+        }
+    }
+}
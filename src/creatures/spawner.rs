@@ -9,8 +9,8 @@ use crate::effects::ResourceBall;
 use crate::player::{PlayerSpriteSheet, SpriteAnimation};
 use crate::state_machine::StateMachine;
 use crate::ui::{HeartSprite, HpText};
-use crate::levels::BoundToLevel;
-use super::{AttackOffset, CardinalAttacks, Creature, CreatureAnimation, CreatureDefinition, CreatureSteering, CreatureState, Glowing, Goblin, Hostile, PatrolOrigin, PatrolWander, ProvokedSteering, Rushing, SpriteRendering, creature_catalog};
+use crate::levels::{BoundToLevel, HazardExposure};
+use super::{AttackOffset, Awareness, CardinalAttacks, Creature, CreatureAnimation, CreatureKind, CreatureDefinition, CreatureSteering, CreatureState, Glowing, Goblin, Hostile, PatrolOrigin, PatrolWander, ProvokedSteering, Rushing, SpriteRendering, creature_catalog};
 
 /// Spawn a creature's range indicator as an independent entity
 /// This ensures consistent behavior - indicator follows creature but isn't affected by animations
@@ -30,52 +30,6 @@ pub fn spawn_creature_range_indicator(
     ));
 }
 
-pub fn spawn_creatures(
-    commands: &mut Commands,
-    config: &GameConfig,
-    assets: &CharacterAssets,
-    meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
-) {
-    let mut rng = rand::rng();
-    let blob = creature_catalog::blob();
-    let world_size = WORLD_SIZE as f32 * GRID_SPACING;
-    let min_distance = COLLISION_RADIUS * 6.0;
-    let cell_size = min_distance * 1.2;
-    let cells_per_axis = (world_size * 2.0 / cell_size) as i32;
-
-    let mut positions: Vec<Vec2> = Vec::new();
-
-    for cx in -cells_per_axis / 2..cells_per_axis / 2 {
-        for cy in -cells_per_axis / 2..cells_per_axis / 2 {
-            if rng.random_bool(1.0 - CREATURE_SPAWN_CHANCE) {
-                continue;
-            }
-
-            let base_x = cx as f32 * cell_size;
-            let base_y = cy as f32 * cell_size;
-
-            let x = base_x + rng.random_range(-cell_size * 0.4..cell_size * 0.4);
-            let y = base_y + rng.random_range(-cell_size * 0.4..cell_size * 0.4);
-
-            // Don't spawn near player start
-            if x.abs() < COLLISION_RADIUS * 4.0 && y.abs() < COLLISION_RADIUS * 4.0 {
-                continue;
-            }
-
-            let pos = Vec2::new(x, y);
-            let too_close = positions.iter().any(|p| p.distance(pos) < min_distance);
-            if too_close {
-                continue;
-            }
-
-            positions.push(pos);
-
-            spawn_creature(commands, config, assets, meshes, materials, &mut rng, &blob, x, y);
-        }
-    }
-}
-
 fn spawn_creature(
     commands: &mut Commands,
     config: &GameConfig,
@@ -128,6 +82,7 @@ fn spawn_creature(
 
     let mut entity_commands = commands.spawn((
         Creature,
+        CreatureKind(definition.name.clone()),
         YSorted { base_offset: definition.base_offset },
         WalkCollider {
             radius_x: definition.walk_collider.radius_x,
@@ -140,7 +95,7 @@ fn spawn_creature(
             definition.hit_collider.radius_y,
         ),
         anim,
-        Health(definition.health),
+        Health(definition.scaled_health(config)),
         loot,
         Mesh2d(assets.character_mesh.clone()),
         MeshMaterial2d(material),
@@ -148,9 +103,14 @@ fn spawn_creature(
     ));
 
     // Always insert steering configs (used when creature becomes hostile)
+    let mut steering = definition.steering.clone();
+    steering.detection_time = config.detection_time;
+    steering.apply_config(config);
+    let mut provoked_steering = definition.provoked_steering.clone();
+    provoked_steering.apply_config(config);
     entity_commands.insert((
-        CreatureSteering(definition.steering.clone()),
-        ProvokedSteering(definition.provoked_steering.clone()),
+        CreatureSteering(steering),
+        ProvokedSteering(provoked_steering),
     ));
 
     // Attack behavior components
@@ -172,9 +132,10 @@ fn spawn_creature(
 
     if is_hostile {
         entity_commands.insert((
-            Hostile { speed: definition.speed },
+            Hostile { speed: definition.hostile_speed(config) },
             PatrolOrigin { position: Vec2::new(x, y) },
             PatrolWander::default(),
+            Awareness::default(),
         ));
     }
     if is_glowing {
@@ -309,9 +270,13 @@ pub fn spawn_goblin(
     materials: &mut Assets<ColorMaterial>,
     position: Vec2,
     patrol_target: Option<Vec2>,
-) {
+) -> Entity {
     let mut definition = creature_catalog::goblin();
-    definition.steering.sight_range = config.goblin_sight_range;
+    definition.steering.sight_range = config.creature_sight_range();
+    definition.steering.view_angle = config.goblin_view_angle.to_radians();
+    definition.steering.detection_time = config.detection_time;
+    definition.steering.apply_config(config);
+    definition.provoked_steering.apply_config(config);
     let club = weapon_catalog::club(config, meshes, materials);
     let club_visual = club.visual.clone();
     // Thin arc (always visible)
@@ -340,9 +305,11 @@ pub fn spawn_goblin(
         Goblin,
         Creature,
         BoundToLevel,
-        Hostile { speed: definition.speed },
+        HazardExposure::default(),
+        Hostile { speed: definition.hostile_speed(config) },
         PatrolOrigin { position: patrol_origin },
         PatrolWander::default(),
+        Awareness::default(),
         StateMachine::<CreatureState>::new(CreatureState::Patrol),
         // Physics/rendering
         YSorted { base_offset: definition.base_offset },
@@ -356,9 +323,10 @@ pub fn spawn_goblin(
             definition.hit_collider.radius_x,
             definition.hit_collider.radius_y,
         ),
-        Health(definition.health),
+        Health(definition.scaled_health(config)),
         loot,
     )).insert((
+        CreatureKind(definition.name.clone()),
         // AI/steering
         CreatureSteering(definition.steering.clone()),
         ProvokedSteering(definition.provoked_steering.clone()),
@@ -448,4 +416,6 @@ pub fn spawn_goblin(
         Transform::from_xyz(position.x, position.y + definition.attack_offset_y, Z_WEAPON + 0.05),
         Visibility::Hidden,
     ));
+
+    goblin_entity
 }
@@ -2,11 +2,15 @@ use bevy::prelude::*;
 
 use crate::combat::snap_to_cardinal;
 use crate::inventory::weapons::{Fist, Weapon, WeaponSwing};
-use crate::core::{Dead, DeathAnimation, GameConfig, HitCollider, Stunned};
+use crate::core::{Dead, DeathAnimation, GameConfig, HitCollider, StaticCollider, Stunned, WalkCollider};
+use crate::levels::CurrentLevel;
 use crate::player::Player;
 use crate::state_machine::{AttackPhase, RequestTransition, StateEntered, StateExited, StateMachine};
 use crate::constants::Z_UI_WORLD;
-use super::{AlertIndicator, AttackOffset, CardinalAttacks, Creature, CreatureState, Hostile, PlayerInRange, Rushing};
+use super::{
+    collider_footprints, has_line_of_sight, AlertIndicator, AttackOffset, CardinalAttacks, Creature, CreatureState, Hostile,
+    PatrolRoute, PlayerInRange, Rushing,
+};
 
 pub fn on_attack_windup_enter(
     mut commands: Commands,
@@ -167,6 +171,21 @@ pub fn on_activated_to_chase(
     }
 }
 
+/// Creatures with a patrol route pick it up again at the nearest waypoint, e.g. after losing the player
+pub fn on_patrol_rejoin_route(
+    mut events: MessageReader<StateEntered<CreatureState>>,
+    mut creature_query: Query<(&Transform, &mut PatrolRoute)>,
+) {
+    for event in events.read() {
+        if event.state != CreatureState::Patrol {
+            continue;
+        }
+        if let Ok((transform, mut route)) = creature_query.get_mut(event.entity) {
+            route.rejoin(transform.translation.truncate());
+        }
+    }
+}
+
 pub fn on_alert_enter(
     mut commands: Commands,
     mut events: MessageReader<StateEntered<CreatureState>>,
@@ -208,23 +227,32 @@ pub fn on_alert_exit(
     }
 }
 
-/// Detects when creatures in Chase state are within weapon range of the player.
+/// Detects when creatures in Chase state are within weapon range of the player and can see them.
 /// Emits PlayerInRange event for other systems to react to.
 pub fn detect_player_proximity(
     mut events: MessageWriter<PlayerInRange>,
-    player_query: Query<(&Transform, Option<&HitCollider>), (With<Player>, Without<Creature>, Without<Dead>, Without<DeathAnimation>)>,
-    creature_query: Query<(Entity, &Transform, &StateMachine<CreatureState>, &Children, Option<&AttackOffset>), (With<Hostile>, Without<Dead>, Without<DeathAnimation>, Without<Stunned>)>,
+    current_level: Res<CurrentLevel>,
+    player_query: Query<(&Transform, Option<&HitCollider>, Option<&WalkCollider>), (With<Player>, Without<Creature>, Without<Dead>, Without<DeathAnimation>)>,
+    creature_query: Query<(Entity, &Transform, &StateMachine<CreatureState>, &Children, Option<&AttackOffset>, Option<&WalkCollider>), (With<Hostile>, Without<Dead>, Without<DeathAnimation>, Without<Stunned>)>,
+    collider_query: Query<(&Transform, &StaticCollider)>,
     fist_query: Query<&Weapon, With<Fist>>,
 ) {
-    let Ok((player_transform, player_hit_collider)) = player_query.single() else { return };
+    let Ok((player_transform, player_hit_collider, player_walk_collider)) = player_query.single() else { return };
     let player_pos = player_transform.translation.truncate();
+    let player_feet = player_pos + Vec2::new(0.0, player_walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+    let colliders = collider_footprints(collider_query.iter());
 
-    for (entity, creature_transform, state_machine, children, attack_offset) in &creature_query {
+    for (entity, creature_transform, state_machine, children, attack_offset, walk_collider) in &creature_query {
         if *state_machine.current() != CreatureState::Chase {
             continue;
         }
 
         let creature_pos = creature_transform.translation.truncate();
+        // No swinging through pillars and crates
+        let creature_feet = creature_pos + Vec2::new(0.0, walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+        if !has_line_of_sight(current_level.data.as_ref(), &colliders, creature_feet, player_feet) {
+            continue;
+        }
 
         let offset_y = attack_offset.map(|o| o.0).unwrap_or(0.0);
         let attack_origin = Vec2::new(creature_pos.x, creature_pos.y + offset_y);
@@ -318,7 +346,7 @@ pub fn advance_cooldown_state(
 ) {
     for (entity, state_machine) in &query {
         if *state_machine.current() == CreatureState::Cooldown {
-            if state_machine.time_in_state() >= config.attack_cooldown_duration {
+            if state_machine.time_in_state() >= config.creature_attack_cooldown() {
                 transitions.write(RequestTransition::new(entity, CreatureState::Chase));
             }
         }
@@ -1,7 +1,13 @@
 use bevy::prelude::*;
 use std::f32::consts::TAU;
 
+use crate::levels::WalkableShape;
+
 pub const NUM_DIRECTIONS: usize = 8;
+/// Danger weight of pits and active hazards, below solid obstacles
+pub const HAZARD_DANGER_WEIGHT: f32 = 0.6;
+/// Danger weight of closed doors and locked areas, as strong as solid obstacles
+pub const BARRIER_DANGER_WEIGHT: f32 = 1.0;
 
 /// Context map for steering decisions
 /// Each slot represents interest/danger in a direction (0 = East, rotating counter-clockwise)
@@ -242,26 +248,27 @@ pub fn player_proximity_danger(
     }
 }
 
-/// Add danger for pit hazards
-/// pits: slice of (center, edge_radius) tuples
-pub fn pit_danger(
+/// Add danger for shapes to keep away from, measured to the nearest point of each shape.
+/// `weight` scales the danger: `HAZARD_DANGER_WEIGHT` or `BARRIER_DANGER_WEIGHT`.
+pub fn shape_danger(
     map: &mut ContextMap,
     creature_pos: Vec2,
-    pits: &[(Vec2, f32)],
+    shapes: &[WalkableShape],
     look_ahead: f32,
+    weight: f32,
 ) {
-    for (pit_center, edge_radius) in pits {
-        let to_pit = *pit_center - creature_pos;
-        let dist = to_pit.length() - edge_radius;
+    for shape in shapes {
+        let to_shape = shape.clamp(creature_pos) - creature_pos;
+        let dist = to_shape.length();
 
         if dist < look_ahead && dist > 0.001 {
-            let dir_to_pit = to_pit.normalize();
+            let dir_to_shape = to_shape / dist;
             let proximity = 1.0 - (dist / look_ahead);
 
             for i in 0..NUM_DIRECTIONS {
                 let dir = ContextMap::direction(i);
-                let alignment = dir.dot(dir_to_pit).max(0.0);
-                let danger = alignment * proximity * 0.6;
+                let alignment = dir.dot(dir_to_shape).max(0.0);
+                let danger = alignment * proximity * weight;
                 map.danger[i] = map.danger[i].max(danger);
             }
         }
@@ -1,13 +1,20 @@
 use bevy::prelude::*;
 
 use crate::constants::*;
-use crate::core::{Dead, DeathAnimation, DespawnTimer, ellipse_push, ellipses_overlap, Knockback, Loot, WalkCollider};
+use crate::combat::{create_filled_half_circle, create_half_circle_arc, create_weapon_arc, CreatureRangeIndicator, GoblinAttackIndicator};
+use crate::core::{ConfigReloaded, Dead, DeathAnimation, DespawnTimer, ellipse_push, ellipses_overlap, GameConfig, Knockback, Loot, WalkCollider};
+use crate::inventory::weapons::{weapon_catalog, Fist, PlayerWeapon, Weapon};
 use crate::effects::{Hitstop, MagnetizedBall, ResourceBall};
 use crate::player::{HurtAnimation, Player, SpriteAnimation};
 use crate::player::Stats;
 use crate::core::CharacterAssets;
 use crate::state_machine::{AttackPhase, StateMachine};
-use super::{Activated, AlertIndicator, Creature, CreatureAnimation, CreatureState, Goblin, PatrolAction, PatrolOrigin, PatrolWander, Rushing, SpriteRendering};
+use super::{Activated, AlertIndicator, Awareness, Creature, CreatureAnimation, CreatureKilled, CreatureKind, CreatureState, CreatureSteering, Goblin, Hostile, PatrolAction, PatrolOrigin, PatrolWander, Provoked, Rushing, SpriteRendering, creature_catalog};
+
+type ConfiguredCreature = (Option<&'static mut Hostile>, &'static mut CreatureSteering, Has<Goblin>, Has<Provoked>);
+type CreatureWeapon = (With<Fist>, Without<PlayerWeapon>);
+type RangeIndicatorMesh = (&'static mut Mesh2d, &'static CreatureRangeIndicator);
+type AttackIndicatorMesh = (&'static mut Mesh2d, &'static GoblinAttackIndicator);
 
 pub fn animate_creatures(
     time: Res<Time>,
@@ -43,14 +50,16 @@ pub fn animate_creatures(
 pub fn animate_death(
     mut commands: Commands,
     time: Res<Time>,
+    config: Res<GameConfig>,
     assets: Res<CharacterAssets>,
     mut stats: ResMut<Stats>,
-    mut query: Query<(Entity, &Transform, &mut DeathAnimation, &Loot, &Children, Option<&SpriteRendering>, Option<&Knockback>), With<Creature>>,
+    mut killed: MessageWriter<CreatureKilled>,
+    mut query: Query<(Entity, &Transform, &mut DeathAnimation, &Loot, &Children, Option<&SpriteRendering>, Option<&Knockback>, Option<&CreatureKind>), With<Creature>>,
     ball_query: Query<&Transform, With<ResourceBall>>,
 ) {
     let mut balls_to_magnetize: Vec<(Entity, Vec3)> = Vec::new();
 
-    for (entity, transform, mut death, loot, children, sprite_rendering, knockback) in &mut query {
+    for (entity, transform, mut death, loot, children, sprite_rendering, knockback, kind) in &mut query {
         // Skip death animation while knockback is active
         if knockback.is_some() {
             continue;
@@ -103,12 +112,12 @@ pub fn animate_death(
                     if is_sprite_based {
                         commands.entity(entity).insert((
                             Dead,
-                            DespawnTimer(CORPSE_LIFETIME),
+                            DespawnTimer(config.corpse_lifetime),
                         ));
                     } else {
                         commands.entity(entity).insert((
                             Dead,
-                            DespawnTimer(CORPSE_LIFETIME),
+                            DespawnTimer(config.corpse_lifetime),
                             MeshMaterial2d(assets.dead_material.clone()),
                         ));
                     }
@@ -116,6 +125,9 @@ pub fn animate_death(
                     if loot.philosophy { stats.philosophy += 1; }
                     if loot.nature_study { stats.nature_study += 1; }
                     if loot.wisdom { stats.wisdom += 1; }
+
+                    let kind = kind.map_or_else(|| "Creature".to_string(), |k| k.0.clone());
+                    killed.write(CreatureKilled { kind });
                 }
             }
             1 => {
@@ -159,6 +171,7 @@ pub fn animate_death(
 
 pub fn apply_collision_push(
     time: Res<Time>,
+    config: Res<GameConfig>,
     player_query: Query<(&Transform, &WalkCollider), (With<Player>, Without<Dead>, Without<Creature>)>,
     mut creatures_query: Query<(Entity, &mut Transform, &WalkCollider), (With<Creature>, Without<Dead>, Without<Player>)>,
 ) {
@@ -193,7 +206,7 @@ pub fn apply_collision_push(
         if let Some((player_pos, player_radius)) = player_data {
             if ellipses_overlap(creature_pos, creature_radius, player_pos, player_radius) {
                 let raw_push = ellipse_push(creature_pos, creature_radius, player_pos, player_radius);
-                push += raw_push * PUSH_STRENGTH * dt;
+                push += raw_push * config.push_strength * dt;
             }
         }
 
@@ -205,7 +218,7 @@ pub fn apply_collision_push(
 
             if ellipses_overlap(creature_pos, creature_radius, *other_pos, *other_radius) {
                 let raw_push = ellipse_push(creature_pos, creature_radius, *other_pos, *other_radius);
-                push += raw_push * PUSH_STRENGTH * 0.5 * dt;
+                push += raw_push * config.push_strength * 0.5 * dt;
             }
         }
 
@@ -404,15 +417,74 @@ pub fn update_goblin_sprite_animation(
     }
 }
 
+/// Keeps the alert indicator above its creature, growing and brightening as awareness builds
 pub fn update_alert_indicator(
-    creature_query: Query<(&Transform, &AlertIndicator), With<Creature>>,
-    mut indicator_query: Query<&mut Transform, Without<Creature>>,
+    creature_query: Query<(&Transform, &AlertIndicator, Option<&Awareness>), With<Creature>>,
+    mut indicator_query: Query<(&mut Transform, &mut TextColor), Without<Creature>>,
 ) {
-    for (creature_transform, alert_indicator) in &creature_query {
-        let Ok(mut indicator_transform) = indicator_query.get_mut(alert_indicator.0) else {
+    for (creature_transform, alert_indicator, awareness) in &creature_query {
+        let Ok((mut indicator_transform, mut text_color)) = indicator_query.get_mut(alert_indicator.0) else {
             continue;
         };
         indicator_transform.translation.x = creature_transform.translation.x;
         indicator_transform.translation.y = creature_transform.translation.y + 35.0;
+
+        let fill = awareness.map_or(1.0, |a| a.0);
+        indicator_transform.scale = Vec3::splat(0.5 + 0.5 * fill);
+        text_color.0 = text_color.0.with_alpha(0.3 + 0.7 * fill);
+    }
+}
+
+/// Bring living creatures in line with the reloaded config: speed, perception,
+/// weapon stats and the range indicators drawn from them.
+/// Provoked creatures keep their provoked steering and only take the new provoked speed.
+pub fn apply_config_to_creatures(
+    mut reloaded: MessageReader<ConfigReloaded>,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut creature_query: Query<ConfiguredCreature, With<Creature>>,
+    goblin_query: Query<(), With<Goblin>>,
+    mut weapon_query: Query<(&mut Weapon, &ChildOf), CreatureWeapon>,
+    mut range_indicator_query: Query<RangeIndicatorMesh, Without<GoblinAttackIndicator>>,
+    mut attack_indicator_query: Query<AttackIndicatorMesh, Without<CreatureRangeIndicator>>,
+) {
+    if reloaded.read().count() == 0 {
+        return;
+    }
+
+    for (hostile, mut steering, is_goblin, provoked) in &mut creature_query {
+        if provoked {
+            if let Some(mut hostile) = hostile {
+                hostile.speed = config.creature_provoked_speed();
+            }
+            continue;
+        }
+        let definition = if is_goblin { creature_catalog::goblin() } else { creature_catalog::hostile_blob() };
+        if let Some(mut hostile) = hostile {
+            hostile.speed = definition.hostile_speed(&config);
+        }
+        steering.0.detection_time = config.detection_time;
+        steering.0.apply_config(&config);
+        if is_goblin {
+            steering.0.sight_range = config.creature_sight_range();
+            steering.0.view_angle = config.goblin_view_angle.to_radians();
+        }
+    }
+
+    let fist = weapon_catalog::fist(&config, &mut meshes, &mut materials);
+    let club = weapon_catalog::club(&config, &mut meshes, &mut materials);
+    for (mut weapon, child_of) in &mut weapon_query {
+        *weapon = if goblin_query.contains(child_of.parent()) { club.clone() } else { fist.clone() };
+    }
+
+    let fist_arc = create_weapon_arc(&mut meshes, &fist);
+    let club_arc = create_half_circle_arc(&mut meshes, club.range());
+    let club_attack_area = create_filled_half_circle(&mut meshes, club.range());
+    for (mut mesh, indicator) in &mut range_indicator_query {
+        mesh.0 = if goblin_query.contains(indicator.0) { club_arc.clone() } else { fist_arc.clone() };
+    }
+    for (mut mesh, _) in &mut attack_indicator_query {
+        mesh.0 = club_attack_area.clone();
     }
 }
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::inventory::ItemId;
+use crate::levels::PropType;
+
+pub const EDITOR_PROPS: [PropType; 4] = [PropType::Pillar, PropType::Barrel, PropType::Crate, PropType::Crate2];
+pub const EDITOR_ITEMS: [ItemId; 7] = [
+    ItemId::Mushroom,
+    ItemId::HealthPotion,
+    ItemId::RustyKnife,
+    ItemId::WoodenStick,
+    ItemId::Sword,
+    ItemId::LeatherArmor,
+    ItemId::IronKey,
+];
+/// Grid sizes cycled with the bracket keys
+pub const EDITOR_GRID_SIZES: [f32; 4] = [5.0, 10.0, 20.0, 40.0];
+
+/// Editor tools, picked with the number keys in this order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditorTool {
+    #[default]
+    Select,
+    Walkable,
+    Prop,
+    Creature,
+    Item,
+    Pit,
+    WinZone,
+    Checkpoint,
+}
+
+impl EditorTool {
+    pub const ALL: [EditorTool; 8] = [
+        EditorTool::Select,
+        EditorTool::Walkable,
+        EditorTool::Prop,
+        EditorTool::Creature,
+        EditorTool::Item,
+        EditorTool::Pit,
+        EditorTool::WinZone,
+        EditorTool::Checkpoint,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EditorTool::Select => "Select",
+            EditorTool::Walkable => "Walkable rect",
+            EditorTool::Prop => "Prop",
+            EditorTool::Creature => "Creature",
+            EditorTool::Item => "Item",
+            EditorTool::Pit => "Pit",
+            EditorTool::WinZone => "Win zone",
+            EditorTool::Checkpoint => "Checkpoint",
+        }
+    }
+}
+
+/// A level entry the editor can select. Indices point into the matching `LevelData` list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditorEntry {
+    PlayerSpawn,
+    WinZone,
+    Walkable(usize),
+    Prop(usize),
+    Creature(usize),
+    Item(usize),
+    Pit(usize),
+    Checkpoint(usize),
+}
+
+/// Mouse drag in progress
+#[derive(Clone, Copy, Debug)]
+pub enum EditorDrag {
+    /// Moving an entry; `offset` keeps the grabbed point under the cursor
+    Move { entry: EditorEntry, offset: Vec2, moved: bool },
+    /// Dragging out a new walkable rect from `start`
+    NewRect { start: Vec2 },
+    /// Resizing a walkable rect by the corner opposite `anchor`
+    Resize { index: usize, anchor: Vec2 },
+}
+
+#[derive(Resource)]
+pub struct EditorState {
+    pub tool: EditorTool,
+    /// Index into `EDITOR_PROPS` placed by the prop tool
+    pub prop: usize,
+    /// Index into `EDITOR_ITEMS` placed by the item tool
+    pub item: usize,
+    pub selected: Option<EditorEntry>,
+    pub drag: Option<EditorDrag>,
+    /// Cursor position in world space, if it is over the window
+    pub cursor: Option<Vec2>,
+    pub snap: bool,
+    pub grid_size: f32,
+    pub camera_position: Vec2,
+    pub zoom: f32,
+    /// Unsaved changes
+    pub dirty: bool,
+    /// Last save result or hint, shown in the editor HUD
+    pub status: String,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self {
+            tool: EditorTool::default(),
+            prop: 0,
+            item: 0,
+            selected: None,
+            drag: None,
+            cursor: None,
+            snap: true,
+            grid_size: 10.0,
+            camera_position: Vec2::ZERO,
+            zoom: 1.0,
+            dirty: false,
+            status: String::new(),
+        }
+    }
+}
+
+impl EditorState {
+    /// Round a world position to the grid when snapping is on
+    pub fn snap_point(&self, point: Vec2) -> Vec2 {
+        if self.snap {
+            (point / self.grid_size).round() * self.grid_size
+        } else {
+            point
+        }
+    }
+}
+
+/// Text panel listing the active tool, selection and editor keys
+#[derive(Component)]
+pub struct EditorHud;
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// Emitted when the editor changed `CurrentLevel` and the level entities need rebuilding
+#[derive(Event, Message, Debug)]
+pub struct LevelEdited;
@@ -0,0 +1,43 @@
+mod components;
+mod events;
+mod systems;
+
+pub use components::{
+    EditorDrag, EditorEntry, EditorHud, EditorState, EditorTool, EDITOR_GRID_SIZES, EDITOR_ITEMS, EDITOR_PROPS,
+};
+pub use events::LevelEdited;
+
+use bevy::prelude::*;
+
+use crate::core::GameState;
+use systems::*;
+
+/// In-game level editor. F2 switches between playing the current level and editing it.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorState>()
+            .add_message::<LevelEdited>()
+            .add_systems(Update, toggle_editor)
+            .add_systems(OnEnter(GameState::Editor), enter_editor)
+            .add_systems(OnExit(GameState::Editor), exit_editor)
+            .add_systems(
+                Update,
+                (
+                    track_editor_cursor,
+                    handle_editor_keys,
+                    (
+                        move_editor_camera,
+                        resize_with_wheel,
+                        edit_with_mouse,
+                        delete_selected_entry,
+                        save_edited_level,
+                    ),
+                    (draw_editor_gizmos, update_editor_hud),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Editor)),
+            );
+    }
+}
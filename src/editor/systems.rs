@@ -0,0 +1,766 @@
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::prelude::*;
+
+use crate::constants::CAMERA_BASE_SCALE;
+use crate::core::GameState;
+use crate::levels::{
+    CheckpointData, CreatureSpawn, CreatureType, CurrentLevel, ItemSpawn, LevelData, LevelFileWatcher, PatrolRouteMode, PitData,
+    PropSpawn, WalkableShape, WinZoneData,
+};
+use super::{
+    EditorDrag, EditorEntry, EditorHud, EditorState, EditorTool, LevelEdited, EDITOR_GRID_SIZES, EDITOR_ITEMS,
+    EDITOR_PROPS,
+};
+
+/// Camera pan speed in screen pixels per second
+const PAN_SPEED: f32 = 600.0;
+const ZOOM_STEP: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 3.0;
+const PIXELS_PER_SCROLL_LINE: f32 = 100.0;
+/// How close the cursor must be to grab an entry, in world units at the default camera zoom
+const PICK_RADIUS: f32 = 8.0;
+const MIN_RECT_SIZE: f32 = 10.0;
+const BOUNDS_MARGIN: f32 = 10.0;
+const DEFAULT_PIT_RADIUS: f32 = 18.0;
+const PIT_EDGE_WIDTH: f32 = 10.0;
+const DEFAULT_WIN_ZONE_RADIUS: f32 = 30.0;
+const DEFAULT_CHECKPOINT_RADIUS: f32 = 20.0;
+/// Shift + mouse wheel grows or shrinks the selected pit, win zone or checkpoint by this much per step
+const RADIUS_STEP: f32 = 2.0;
+const MIN_RADIUS: f32 = 4.0;
+/// Generated levels have no file of their own, so saving them writes here
+const EDITOR_SAVE_PATH: &str = "assets/levels/edited.ron";
+/// Skip drawing the grid when it would need more lines than this across the screen
+const MAX_GRID_LINES: f32 = 200.0;
+
+const TOOL_KEYS: [KeyCode; 8] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+];
+
+const GRID_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.06);
+const BOUNDS_COLOR: Color = Color::srgb(0.4, 0.4, 0.45);
+const WALKABLE_COLOR: Color = Color::srgb(0.3, 0.8, 0.4);
+const PLAYER_SPAWN_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+const WIN_ZONE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const CHECKPOINT_COLOR: Color = Color::srgb(0.3, 0.9, 0.8);
+const HAZARD_COLOR: Color = Color::srgb(0.95, 0.5, 0.2);
+const PIT_COLOR: Color = Color::srgb(0.6, 0.3, 0.8);
+const PROP_COLOR: Color = Color::srgb(0.75, 0.55, 0.35);
+const CREATURE_COLOR: Color = Color::srgb(0.95, 0.3, 0.3);
+const ITEM_COLOR: Color = Color::srgb(0.95, 0.95, 0.4);
+const SELECTED_COLOR: Color = Color::WHITE;
+
+/// F2 switches between playing the level and editing it
+pub fn toggle_editor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    current_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F2) || current_level.data.is_none() {
+        return;
+    }
+    match current_state.get() {
+        GameState::Playing => next_state.set(GameState::Editor),
+        GameState::Editor => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+/// Take over the camera where gameplay left it and show the editor HUD
+pub fn enter_editor(
+    mut commands: Commands,
+    mut state: ResMut<EditorState>,
+    camera_query: Query<&Transform, With<Camera2d>>,
+) {
+    state.selected = None;
+    state.drag = None;
+    state.status.clear();
+    if let Ok(transform) = camera_query.single() {
+        state.camera_position = transform.translation.truncate();
+        state.zoom = transform.scale.x;
+    }
+
+    commands.spawn((
+        EditorHud,
+        Text::new(""),
+        TextFont {
+            font_size: 13.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            right: Val::Px(20.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+    ));
+}
+
+pub fn exit_editor(
+    mut commands: Commands,
+    mut state: ResMut<EditorState>,
+    hud_query: Query<Entity, With<EditorHud>>,
+) {
+    for entity in &hud_query {
+        commands.entity(entity).despawn();
+    }
+    state.drag = None;
+    if state.dirty {
+        warn!("Left the editor with unsaved level changes");
+        state.dirty = false;
+    }
+}
+
+pub fn track_editor_cursor(
+    mut state: ResMut<EditorState>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.single() else { return };
+    let cursor = windows.single().ok().and_then(|window| window.cursor_position());
+    state.cursor = cursor.and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok());
+}
+
+/// Tool selection, palette cycling and grid settings
+pub fn handle_editor_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<EditorState>,
+) {
+    for (key, tool) in TOOL_KEYS.iter().zip(EditorTool::ALL) {
+        if keyboard.just_pressed(*key) {
+            state.tool = tool;
+            state.drag = None;
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyC) {
+        match state.tool {
+            EditorTool::Prop => state.prop = (state.prop + 1) % EDITOR_PROPS.len(),
+            EditorTool::Item => state.item = (state.item + 1) % EDITOR_ITEMS.len(),
+            _ => {}
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyG) {
+        state.snap = !state.snap;
+    }
+
+    let grid_index = EDITOR_GRID_SIZES.iter().position(|size| *size == state.grid_size).unwrap_or(0);
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        state.grid_size = EDITOR_GRID_SIZES[grid_index.saturating_sub(1)];
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        state.grid_size = EDITOR_GRID_SIZES[(grid_index + 1).min(EDITOR_GRID_SIZES.len() - 1)];
+    }
+}
+
+/// Pan with WASD, the arrow keys or the middle mouse button; zoom with the mouse wheel
+pub fn move_editor_camera(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut state: ResMut<EditorState>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    // Ctrl is held for saving, so Ctrl+S must not pan
+    if !keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        let mut direction = Vec2::ZERO;
+        if keyboard.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+            direction.y += 1.0;
+        }
+        if keyboard.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+            direction.y -= 1.0;
+        }
+        if keyboard.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+            direction.x -= 1.0;
+        }
+        if keyboard.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+            direction.x += 1.0;
+        }
+        let step = direction.normalize_or_zero() * PAN_SPEED * state.zoom * time.delta_secs();
+        state.camera_position += step;
+    }
+
+    if mouse.pressed(MouseButton::Middle) {
+        let drag = Vec2::new(-motion.delta.x, motion.delta.y) * state.zoom;
+        state.camera_position += drag;
+    }
+
+    // Shift + wheel resizes the selection instead
+    if !keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let steps = scroll_steps(&scroll);
+        if steps != 0.0 {
+            state.zoom = (state.zoom * (1.0 - steps * ZOOM_STEP)).clamp(MIN_ZOOM, MAX_ZOOM);
+        }
+    }
+
+    let Ok(mut camera_transform) = camera_query.single_mut() else { return };
+    camera_transform.translation.x = state.camera_position.x;
+    camera_transform.translation.y = state.camera_position.y;
+    camera_transform.scale = Vec3::splat(state.zoom);
+}
+
+/// Left click places with the active tool or grabs an entry to drag; right click deletes
+pub fn edit_with_mouse(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut edited: MessageWriter<LevelEdited>,
+) {
+    let Some(cursor) = state.cursor else { return };
+    let Some(level) = current_level.data.as_mut() else { return };
+    let pick_radius = PICK_RADIUS * state.zoom / CAMERA_BASE_SCALE;
+    let snapped = state.snap_point(cursor);
+
+    if mouse.just_pressed(MouseButton::Right) {
+        if let Some(entry) = pick_entry(level, cursor, pick_radius) {
+            if remove_entry(level, entry) {
+                state.selected = None;
+                state.drag = None;
+                mark_edited(&mut state, &mut edited);
+            }
+        }
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Left) {
+        match state.tool {
+            EditorTool::Select => {
+                let corner = state.selected.and_then(|entry| rect_corner_anchor(level, entry, cursor, pick_radius));
+                if let Some((index, anchor)) = corner {
+                    state.drag = Some(EditorDrag::Resize { index, anchor });
+                } else if let Some(entry) = pick_entry(level, cursor, pick_radius) {
+                    state.selected = Some(entry);
+                    let offset = entry_anchor(level, entry).unwrap_or(cursor) - cursor;
+                    state.drag = Some(EditorDrag::Move { entry, offset, moved: false });
+                } else {
+                    state.selected = None;
+                }
+            }
+            EditorTool::Walkable => state.drag = Some(EditorDrag::NewRect { start: snapped }),
+            tool => {
+                let Some(entry) = place_entry(level, tool, &state, snapped) else { return };
+                state.selected = Some(entry);
+                state.drag = Some(EditorDrag::Move { entry, offset: snapped - cursor, moved: false });
+                mark_edited(&mut state, &mut edited);
+            }
+        }
+        return;
+    }
+
+    // Drags only touch the level data; entities are rebuilt once the button is released
+    if mouse.pressed(MouseButton::Left) {
+        match state.drag {
+            Some(EditorDrag::Move { entry, offset, .. }) => {
+                let target = state.snap_point(cursor + offset);
+                if entry_anchor(level, entry).is_some_and(|anchor| anchor != target) {
+                    set_entry_anchor(level, entry, target);
+                    state.drag = Some(EditorDrag::Move { entry, offset, moved: true });
+                }
+            }
+            Some(EditorDrag::Resize { index, anchor }) => {
+                let rect = Rect::from_corners(anchor, snapped);
+                if rect.width() >= MIN_RECT_SIZE && rect.height() >= MIN_RECT_SIZE {
+                    if let Some(shape) = level.walkable.get_mut(index) {
+                        *shape = WalkableShape::Rect { min: rect.min, max: rect.max };
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if mouse.just_released(MouseButton::Left) {
+        match state.drag.take() {
+            Some(EditorDrag::Move { moved: true, .. }) | Some(EditorDrag::Resize { .. }) => {
+                grow_bounds(level);
+                mark_edited(&mut state, &mut edited);
+            }
+            Some(EditorDrag::NewRect { start }) => {
+                let rect = Rect::from_corners(start, snapped);
+                if rect.width() >= MIN_RECT_SIZE && rect.height() >= MIN_RECT_SIZE {
+                    level.walkable.push(WalkableShape::Rect { min: rect.min, max: rect.max });
+                    state.selected = Some(EditorEntry::Walkable(level.walkable.len() - 1));
+                    grow_bounds(level);
+                    mark_edited(&mut state, &mut edited);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Shift + mouse wheel resizes the selected pit, win zone or checkpoint
+pub fn resize_with_wheel(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut edited: MessageWriter<LevelEdited>,
+) {
+    if !keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let steps = scroll_steps(&scroll);
+    if steps == 0.0 {
+        return;
+    }
+    let Some(level) = current_level.data.as_mut() else { return };
+    let delta = steps * RADIUS_STEP;
+
+    match state.selected {
+        Some(EditorEntry::Pit(index)) => {
+            let Some(pit) = level.pits.get_mut(index) else { return };
+            let radius = (pit.radius + delta).max(MIN_RADIUS);
+            pit.edge_radius += radius - pit.radius;
+            pit.radius = radius;
+        }
+        Some(EditorEntry::WinZone) => {
+            let Some(win_zone) = level.win_zone.as_mut() else { return };
+            win_zone.radius = (win_zone.radius + delta).max(MIN_RADIUS);
+        }
+        Some(EditorEntry::Checkpoint(index)) => {
+            let Some(checkpoint) = level.checkpoints.get_mut(index) else { return };
+            checkpoint.radius = (checkpoint.radius + delta).max(MIN_RADIUS);
+        }
+        _ => return,
+    }
+    mark_edited(&mut state, &mut edited);
+}
+
+pub fn delete_selected_entry(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut edited: MessageWriter<LevelEdited>,
+) {
+    if !keyboard.any_just_pressed([KeyCode::Delete, KeyCode::Backspace]) {
+        return;
+    }
+    let Some(entry) = state.selected else { return };
+    let Some(level) = current_level.data.as_mut() else { return };
+
+    if remove_entry(level, entry) {
+        state.selected = None;
+        state.drag = None;
+        mark_edited(&mut state, &mut edited);
+    } else {
+        state.status = "The player spawn can't be deleted".to_string();
+    }
+}
+
+/// Ctrl+S writes the edited level back to its file
+pub fn save_edited_level(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut watcher: ResMut<LevelFileWatcher>,
+) {
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keyboard.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+    let Some(level) = current_level.data.as_ref() else { return };
+    let path = current_level.path.clone().unwrap_or_else(|| EDITOR_SAVE_PATH.to_string());
+
+    if let Err(error) = level.save_to_file(&path) {
+        error!("{}", error);
+        state.status = error.to_string();
+        return;
+    }
+
+    // Unfinished levels are saved anyway; the problems are listed so they can be fixed
+    let issues = level.validate();
+    for issue in &issues {
+        warn!("{}", issue);
+    }
+    state.status = if issues.is_empty() {
+        format!("Saved {}", path)
+    } else {
+        format!("Saved {} with {} problem(s), see log", path, issues.len())
+    };
+    info!("{}", state.status);
+    state.dirty = false;
+
+    // The saved file is now the level's source; don't hot-reload our own write
+    watcher.last_modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    watcher.path = Some(path.clone());
+    current_level.path = Some(path);
+}
+
+pub fn draw_editor_gizmos(
+    mut gizmos: Gizmos,
+    state: Res<EditorState>,
+    current_level: Res<CurrentLevel>,
+    windows: Query<&Window>,
+) {
+    let Some(level) = current_level.data.as_ref() else { return };
+    let color = |entry: EditorEntry, base: Color| {
+        if state.selected == Some(entry) { SELECTED_COLOR } else { base }
+    };
+
+    if state.snap {
+        if let Ok(window) = windows.single() {
+            draw_grid(&mut gizmos, &state, window.size());
+        }
+    }
+
+    let bounds = Rect::from_corners(level.bounds.min, level.bounds.max);
+    gizmos.rect_2d(bounds.center(), bounds.size(), BOUNDS_COLOR);
+
+    for (index, shape) in level.walkable.iter().enumerate() {
+        draw_shape(&mut gizmos, shape, color(EditorEntry::Walkable(index), WALKABLE_COLOR));
+    }
+
+    // Corner handles on the selected rect can be dragged to resize it
+    if let Some(EditorEntry::Walkable(index)) = state.selected {
+        if let Some(WalkableShape::Rect { .. }) = level.walkable.get(index) {
+            for corner in level.walkable[index].vertices() {
+                gizmos.rect_2d(corner, Vec2::splat(4.0), SELECTED_COLOR);
+            }
+        }
+    }
+
+    // Hazards are edited in the level file; they are drawn for reference only
+    for hazard in &level.hazards {
+        draw_shape(&mut gizmos, &hazard.area, HAZARD_COLOR);
+    }
+
+    for (index, pit) in level.pits.iter().enumerate() {
+        let pit_color = color(EditorEntry::Pit(index), PIT_COLOR);
+        gizmos.circle_2d(pit.position, pit.radius, pit_color);
+        gizmos.circle_2d(pit.position, pit.edge_radius, pit_color.with_alpha(0.4));
+    }
+
+    if let Some(win_zone) = &level.win_zone {
+        gizmos.circle_2d(win_zone.position, win_zone.radius, color(EditorEntry::WinZone, WIN_ZONE_COLOR));
+    }
+
+    for (index, checkpoint) in level.checkpoints.iter().enumerate() {
+        let checkpoint_color = color(EditorEntry::Checkpoint(index), CHECKPOINT_COLOR);
+        gizmos.circle_2d(checkpoint.position, checkpoint.radius, checkpoint_color);
+        gizmos.line_2d(checkpoint.position, checkpoint.position + Vec2::new(0.0, 12.0), checkpoint_color);
+    }
+
+    for (index, prop) in level.props.iter().enumerate() {
+        gizmos.rect_2d(prop.position, Vec2::splat(12.0), color(EditorEntry::Prop(index), PROP_COLOR));
+    }
+
+    for (index, creature) in level.creatures.iter().enumerate() {
+        let creature_color = color(EditorEntry::Creature(index), CREATURE_COLOR);
+        gizmos.circle_2d(creature.position, 7.0, creature_color);
+
+        // Patrol routes are edited in the level file; they are drawn for reference only
+        if let Some(route) = &creature.route {
+            let route_color = creature_color.with_alpha(0.4);
+            for waypoint in &route.waypoints {
+                gizmos.circle_2d(*waypoint, 3.0, route_color);
+            }
+            for pair in route.waypoints.windows(2) {
+                gizmos.line_2d(pair[0], pair[1], route_color);
+            }
+            if let (PatrolRouteMode::Loop, [first, .., last]) = (route.mode, route.waypoints.as_slice()) {
+                gizmos.line_2d(*last, *first, route_color);
+            }
+        }
+    }
+
+    for (index, item) in level.items.iter().enumerate() {
+        let diamond = Isometry2d::new(item.position, Rot2::degrees(45.0));
+        gizmos.rect_2d(diamond, Vec2::splat(7.0), color(EditorEntry::Item(index), ITEM_COLOR));
+    }
+
+    let spawn_color = color(EditorEntry::PlayerSpawn, PLAYER_SPAWN_COLOR);
+    gizmos.cross_2d(level.player_spawn, 6.0, spawn_color);
+    gizmos.circle_2d(level.player_spawn, 8.0, spawn_color);
+
+    // Preview the rect being dragged out
+    if let (Some(EditorDrag::NewRect { start }), Some(cursor)) = (state.drag, state.cursor) {
+        let rect = Rect::from_corners(start, state.snap_point(cursor));
+        gizmos.rect_2d(rect.center(), rect.size(), SELECTED_COLOR);
+    }
+}
+
+pub fn update_editor_hud(
+    state: Res<EditorState>,
+    current_level: Res<CurrentLevel>,
+    mut hud_query: Query<&mut Text, With<EditorHud>>,
+) {
+    let Ok(mut text) = hud_query.single_mut() else { return };
+    let Some(level) = current_level.data.as_ref() else { return };
+
+    let tool_key = EditorTool::ALL.iter().position(|tool| *tool == state.tool).unwrap_or(0) + 1;
+    let tool = match state.tool {
+        EditorTool::Prop => format!("{} ({:?})", state.tool.label(), EDITOR_PROPS[state.prop]),
+        EditorTool::Item => format!("{} ({:?})", state.tool.label(), EDITOR_ITEMS[state.item]),
+        tool => tool.label().to_string(),
+    };
+    let snap = if state.snap { format!("on ({})", state.grid_size) } else { "off".to_string() };
+    let selected = state.selected.map(|entry| describe_entry(level, entry)).unwrap_or_else(|| "none".to_string());
+
+    let hud = format!(
+        "EDITOR - {}{}\nTool: [{}] {}\nSnap: {}\nSelected: {}\n{}\n\n\
+         1-8 tools  C variant  G snap  [ ] grid size\n\
+         LMB place/drag  RMB/Del delete  Shift+wheel radius\n\
+         WASD/MMB pan  Wheel zoom  Ctrl+S save  F2 play",
+        level.name,
+        if state.dirty { " *" } else { "" },
+        tool_key,
+        tool,
+        snap,
+        selected,
+        state.status,
+    );
+    if text.0 != hud {
+        text.0 = hud;
+    }
+}
+
+fn mark_edited(state: &mut EditorState, edited: &mut MessageWriter<LevelEdited>) {
+    state.dirty = true;
+    edited.write(LevelEdited);
+}
+
+fn scroll_steps(scroll: &AccumulatedMouseScroll) -> f32 {
+    match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_SCROLL_LINE,
+    }
+}
+
+/// Closest entry under the cursor. Spawns, pits, checkpoints and the win zone win over the walkable shapes
+/// beneath them; overlapping shapes pick the one drawn last.
+fn pick_entry(level: &LevelData, cursor: Vec2, radius: f32) -> Option<EditorEntry> {
+    let mut best: Option<(EditorEntry, f32)> = None;
+    let mut consider = |entry: EditorEntry, position: Vec2, reach: f32| {
+        let distance = position.distance(cursor);
+        if distance <= reach && best.is_none_or(|(_, closest)| distance < closest) {
+            best = Some((entry, distance));
+        }
+    };
+
+    consider(EditorEntry::PlayerSpawn, level.player_spawn, radius);
+    for (index, prop) in level.props.iter().enumerate() {
+        consider(EditorEntry::Prop(index), prop.position, radius);
+    }
+    for (index, creature) in level.creatures.iter().enumerate() {
+        consider(EditorEntry::Creature(index), creature.position, radius);
+    }
+    for (index, item) in level.items.iter().enumerate() {
+        consider(EditorEntry::Item(index), item.position, radius);
+    }
+    for (index, pit) in level.pits.iter().enumerate() {
+        consider(EditorEntry::Pit(index), pit.position, pit.radius.max(radius));
+    }
+    if let Some(win_zone) = &level.win_zone {
+        consider(EditorEntry::WinZone, win_zone.position, win_zone.radius.max(radius));
+    }
+    for (index, checkpoint) in level.checkpoints.iter().enumerate() {
+        consider(EditorEntry::Checkpoint(index), checkpoint.position, checkpoint.radius.max(radius));
+    }
+
+    best.map(|(entry, _)| entry).or_else(|| {
+        level.walkable.iter().rposition(|shape| shape.contains(cursor)).map(EditorEntry::Walkable)
+    })
+}
+
+/// When the cursor is on a corner of the selected walkable rect, the rect index and the opposite corner
+fn rect_corner_anchor(level: &LevelData, entry: EditorEntry, cursor: Vec2, radius: f32) -> Option<(usize, Vec2)> {
+    let EditorEntry::Walkable(index) = entry else { return None };
+    let shape @ WalkableShape::Rect { .. } = level.walkable.get(index)? else { return None };
+    let corners = shape.vertices();
+    let corner = corners.iter().position(|corner| corner.distance(cursor) <= radius)?;
+    Some((index, corners[(corner + 2) % corners.len()]))
+}
+
+/// The point an entry is dragged by: its position, or the min corner of walkable shapes so they stay on the grid
+fn entry_anchor(level: &LevelData, entry: EditorEntry) -> Option<Vec2> {
+    match entry {
+        EditorEntry::PlayerSpawn => Some(level.player_spawn),
+        EditorEntry::WinZone => level.win_zone.as_ref().map(|w| w.position),
+        EditorEntry::Walkable(index) => level.walkable.get(index).map(|shape| shape.bounding_rect().min),
+        EditorEntry::Prop(index) => level.props.get(index).map(|p| p.position),
+        EditorEntry::Creature(index) => level.creatures.get(index).map(|c| c.position),
+        EditorEntry::Item(index) => level.items.get(index).map(|i| i.position),
+        EditorEntry::Pit(index) => level.pits.get(index).map(|p| p.position),
+        EditorEntry::Checkpoint(index) => level.checkpoints.get(index).map(|c| c.position),
+    }
+}
+
+fn set_entry_anchor(level: &mut LevelData, entry: EditorEntry, anchor: Vec2) {
+    match entry {
+        EditorEntry::PlayerSpawn => level.player_spawn = anchor,
+        EditorEntry::WinZone => {
+            if let Some(win_zone) = level.win_zone.as_mut() {
+                win_zone.position = anchor;
+            }
+        }
+        EditorEntry::Walkable(index) => {
+            if let Some(shape) = level.walkable.get_mut(index) {
+                *shape = shape.translated(anchor - shape.bounding_rect().min);
+            }
+        }
+        EditorEntry::Prop(index) => {
+            if let Some(prop) = level.props.get_mut(index) {
+                prop.position = anchor;
+            }
+        }
+        EditorEntry::Creature(index) => {
+            if let Some(creature) = level.creatures.get_mut(index) {
+                creature.position = anchor;
+            }
+        }
+        EditorEntry::Item(index) => {
+            if let Some(item) = level.items.get_mut(index) {
+                item.position = anchor;
+            }
+        }
+        EditorEntry::Pit(index) => {
+            if let Some(pit) = level.pits.get_mut(index) {
+                pit.position = anchor;
+            }
+        }
+        EditorEntry::Checkpoint(index) => {
+            if let Some(checkpoint) = level.checkpoints.get_mut(index) {
+                checkpoint.position = anchor;
+            }
+        }
+    }
+}
+
+/// Remove an entry from the level. The player spawn can only be moved.
+fn remove_entry(level: &mut LevelData, entry: EditorEntry) -> bool {
+    fn remove_at<T>(list: &mut Vec<T>, index: usize) -> bool {
+        if index < list.len() {
+            list.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    match entry {
+        EditorEntry::PlayerSpawn => false,
+        EditorEntry::WinZone => level.win_zone.take().is_some(),
+        EditorEntry::Walkable(index) => remove_at(&mut level.walkable, index),
+        EditorEntry::Prop(index) => remove_at(&mut level.props, index),
+        EditorEntry::Creature(index) => remove_at(&mut level.creatures, index),
+        EditorEntry::Item(index) => remove_at(&mut level.items, index),
+        EditorEntry::Pit(index) => remove_at(&mut level.pits, index),
+        EditorEntry::Checkpoint(index) => remove_at(&mut level.checkpoints, index),
+    }
+}
+
+/// Add a new entry for a placement tool. A level has one win zone, so placing it again moves it.
+fn place_entry(level: &mut LevelData, tool: EditorTool, state: &EditorState, position: Vec2) -> Option<EditorEntry> {
+    let entry = match tool {
+        EditorTool::Select | EditorTool::Walkable => return None,
+        EditorTool::Prop => {
+            level.props.push(PropSpawn { prop: EDITOR_PROPS[state.prop].clone(), position, name: None });
+            EditorEntry::Prop(level.props.len() - 1)
+        }
+        EditorTool::Creature => {
+            level.creatures.push(CreatureSpawn { creature: CreatureType::Goblin, position, name: None, route: None });
+            EditorEntry::Creature(level.creatures.len() - 1)
+        }
+        EditorTool::Item => {
+            level.items.push(ItemSpawn { item_id: EDITOR_ITEMS[state.item], quantity: 1, position });
+            EditorEntry::Item(level.items.len() - 1)
+        }
+        EditorTool::Pit => {
+            level.pits.push(PitData {
+                position,
+                radius: DEFAULT_PIT_RADIUS,
+                edge_radius: DEFAULT_PIT_RADIUS + PIT_EDGE_WIDTH,
+            });
+            EditorEntry::Pit(level.pits.len() - 1)
+        }
+        EditorTool::WinZone => {
+            let radius = level.win_zone.as_ref().map_or(DEFAULT_WIN_ZONE_RADIUS, |w| w.radius);
+            level.win_zone = Some(WinZoneData { position, radius });
+            EditorEntry::WinZone
+        }
+        EditorTool::Checkpoint => {
+            level.checkpoints.push(CheckpointData { position, radius: DEFAULT_CHECKPOINT_RADIUS });
+            EditorEntry::Checkpoint(level.checkpoints.len() - 1)
+        }
+    };
+    Some(entry)
+}
+
+/// Grow the level bounds so every walkable shape stays inside them
+fn grow_bounds(level: &mut LevelData) {
+    let mut bounds = Rect::from_corners(level.bounds.min, level.bounds.max);
+    for shape in &level.walkable {
+        bounds = bounds.union(shape.bounding_rect().inflate(BOUNDS_MARGIN));
+    }
+    level.bounds.min = bounds.min;
+    level.bounds.max = bounds.max;
+}
+
+fn describe_entry(level: &LevelData, entry: EditorEntry) -> String {
+    let details = match entry {
+        EditorEntry::PlayerSpawn => Some("Player spawn".to_string()),
+        EditorEntry::WinZone => level.win_zone.as_ref().map(|w| format!("Win zone (radius {})", w.radius)),
+        EditorEntry::Walkable(index) => Some(format!("Walkable shape #{}", index)),
+        EditorEntry::Prop(index) => level.props.get(index).map(|p| format!("Prop #{} {:?}", index, p.prop)),
+        EditorEntry::Creature(index) => level.creatures.get(index).map(|c| format!("Creature #{} {:?}", index, c.creature)),
+        EditorEntry::Item(index) => level.items.get(index).map(|i| format!("Item #{} {:?} x{}", index, i.item_id, i.quantity)),
+        EditorEntry::Pit(index) => level.pits.get(index).map(|p| format!("Pit #{} (radius {})", index, p.radius)),
+        EditorEntry::Checkpoint(index) => {
+            level.checkpoints.get(index).map(|c| format!("Checkpoint #{} (radius {})", index, c.radius))
+        }
+    };
+    let position = entry_anchor(level, entry).map(|p| format!(" at ({}, {})", p.x, p.y)).unwrap_or_default();
+    details.map(|d| d + &position).unwrap_or_else(|| "none".to_string())
+}
+
+fn draw_shape(gizmos: &mut Gizmos, shape: &WalkableShape, color: Color) {
+    match shape {
+        WalkableShape::Rect { min, max } => {
+            let rect = Rect::from_corners(*min, *max);
+            gizmos.rect_2d(rect.center(), rect.size(), color);
+        }
+        WalkableShape::Polygon { points } => {
+            gizmos.linestrip_2d(points.iter().copied().chain(points.first().copied()), color);
+        }
+        WalkableShape::Circle { center, radius } => {
+            gizmos.circle_2d(*center, *radius, color);
+        }
+    }
+}
+
+fn draw_grid(gizmos: &mut Gizmos, state: &EditorState, window_size: Vec2) {
+    let half_view = window_size / 2.0 * state.zoom;
+    let size = state.grid_size;
+    if half_view.max_element() * 2.0 / size > MAX_GRID_LINES {
+        return;
+    }
+
+    let min = ((state.camera_position - half_view) / size).floor() * size;
+    let max = state.camera_position + half_view;
+    let mut x = min.x;
+    while x <= max.x {
+        gizmos.line_2d(Vec2::new(x, min.y), Vec2::new(x, max.y), GRID_COLOR);
+        x += size;
+    }
+    let mut y = min.y;
+    while y <= max.y {
+        gizmos.line_2d(Vec2::new(min.x, y), Vec2::new(max.x, y), GRID_COLOR);
+        y += size;
+    }
+}
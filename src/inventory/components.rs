@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::data::{get_item_data, InventorySlot, ItemId, INVENTORY_SIZE, HOTBAR_SIZE};
 
 #[derive(Component)]
 pub struct EquippedWeaponId(pub ItemId);
 
-#[derive(Component)]
+#[derive(Component, Clone, Deserialize, Serialize)]
 pub struct Inventory {
     pub slots: [Option<InventorySlot>; INVENTORY_SIZE],
 }
@@ -63,6 +64,35 @@ impl Inventory {
         self.slots[index].as_ref()
     }
 
+    /// Total quantity of an item across all slots
+    pub fn count(&self, item_id: ItemId) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|slot| slot.item_id == item_id)
+            .map(|slot| slot.quantity)
+            .sum()
+    }
+
+    /// Remove `quantity` of an item across slots, only if that many are held
+    pub fn take(&mut self, item_id: ItemId, quantity: u32) -> bool {
+        if self.count(item_id) < quantity {
+            return false;
+        }
+        let mut remaining = quantity;
+        for index in 0..self.slots.len() {
+            if remaining == 0 {
+                break;
+            }
+            if self.slots[index].as_ref().is_some_and(|slot| slot.item_id == item_id) {
+                if let Some((_, removed)) = self.remove(index, remaining) {
+                    remaining -= removed;
+                }
+            }
+        }
+        true
+    }
+
     #[allow(dead_code)]
     pub fn hotbar_slots(&self) -> &[Option<InventorySlot>] {
         &self.slots[..HOTBAR_SIZE]
@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 
 use bevy::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::core::GameConfig;
 use super::items::ConsumableEffect;
@@ -19,7 +19,7 @@ pub enum Rarity {
     Legendary,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub enum ItemId {
     WoodenStick,
     RustyKnife,
@@ -28,6 +28,7 @@ pub enum ItemId {
     HealthPotion,
     Mushroom,
     LeatherArmor,
+    IronKey,
 }
 
 /// Resource holding item icon textures for UI and ground display
@@ -42,6 +43,7 @@ pub enum ItemCategory {
     Weapon,
     Armor,
     Consumable,
+    Key,
 }
 
 /// Visual representation of an item when on the ground
@@ -76,7 +78,7 @@ pub struct ItemData {
     pub stack_max: u32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct InventorySlot {
     pub item_id: ItemId,
     pub quantity: u32,
@@ -129,6 +131,12 @@ pub fn get_item_data(id: ItemId) -> ItemData {
             category: ItemCategory::Armor,
             stack_max: 1,
         },
+        ItemId::IronKey => ItemData {
+            id,
+            name: "Iron Key".to_string(),
+            category: ItemCategory::Key,
+            stack_max: 5,
+        },
     }
 }
 
@@ -297,5 +305,37 @@ pub fn build_item_registry(
         },
     );
 
+    // Iron Key
+    let key_material = materials.add(Color::srgb(0.55, 0.55, 0.6));
+    items.insert(
+        ItemId::IronKey,
+        ItemDefinition {
+            name: "Iron Key".to_string(),
+            category: ItemCategory::Key,
+            stack_max: 5,
+            ground_visual: GroundItemVisual {
+                meshes: vec![
+                    (
+                        meshes.add(Annulus::new(2.0, 4.0)),
+                        key_material.clone(),
+                        Vec3::new(-5.0, 0.0, 0.0),
+                    ),
+                    (
+                        meshes.add(Rectangle::new(8.0, 2.0)),
+                        key_material.clone(),
+                        Vec3::new(1.0, 0.0, 0.0),
+                    ),
+                    (
+                        meshes.add(Rectangle::new(2.0, 3.0)),
+                        key_material,
+                        Vec3::new(4.0, -2.0, 0.0),
+                    ),
+                ],
+            },
+            weapon: None,
+            consumable_effect: None,
+        },
+    );
+
     ItemRegistry { items }
 }
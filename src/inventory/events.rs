@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+use super::ItemId;
+
+/// Emitted when the player picks up a ground item
+#[derive(Event, Message, Debug)]
+pub struct ItemPickedUp {
+    pub item_id: ItemId,
+    pub quantity: u32,
+}
+
+/// Emitted when the player uses a consumable
+#[derive(Event, Message, Debug)]
+pub struct ItemUsed {
+    pub item_id: ItemId,
+}
@@ -1,11 +1,13 @@
 pub mod components;
 pub mod data;
+pub mod events;
 pub mod items;
 pub mod systems;
 pub mod weapons;
 
 pub use components::*;
 pub use data::*;
+pub use events::*;
 #[allow(unused_imports)]
 pub use items::{Armor, Consumable, ConsumableEffect, item_catalog};
 pub use systems::*;
@@ -28,6 +30,8 @@ impl Plugin for InventoryPlugin {
             .init_resource::<CursorOverUI>()
             .init_resource::<DragState>()
             .init_resource::<SelectedHotbarSlot>()
+            .add_message::<ItemPickedUp>()
+            .add_message::<ItemUsed>()
             .add_systems(
                 Update,
                 (
@@ -52,6 +56,7 @@ impl Plugin for InventoryPlugin {
                 pickup_ground_items
                     .run_if(in_state(GameState::Playing))
                     .run_if(cursor_not_over_ui),
-            );
+            )
+            .add_systems(Update, refresh_weapons_from_config);
     }
 }
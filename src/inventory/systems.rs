@@ -1,9 +1,9 @@
 use bevy::prelude::*;
 
 use super::weapons::{PlayerWeapon, Weapon};
-use crate::core::{GameAction, GameConfig, Health, InputBindings};
+use crate::core::{ConfigReloaded, GameAction, GameConfig, Health, InputBindings, MaxHealth};
 use crate::player::Player;
-use super::{get_weapon_stats, ConsumableEffect, EquippedWeaponId, GroundItem, GroundItemBob, Inventory, InventorySlot, ItemCategory, ItemIcons, ItemId, ItemRegistry, Pickupable};
+use super::{get_weapon_stats, ConsumableEffect, EquippedWeaponId, GroundItem, GroundItemBob, Inventory, InventorySlot, ItemCategory, ItemIcons, ItemId, ItemPickedUp, ItemRegistry, ItemUsed, Pickupable};
 use crate::ui::{
     HotbarSlot, HotbarSlotCount, HotbarSlotIcon,
     InventoryPanel, InventorySlotCount, InventorySlotIcon, InventorySlotUI,
@@ -194,6 +194,7 @@ fn get_item_color(registry: &ItemRegistry, id: ItemId) -> Color {
         ItemCategory::Weapon => Color::srgba(0.6, 0.4, 0.3, 1.0),
         ItemCategory::Armor => Color::srgba(0.4, 0.5, 0.6, 1.0),
         ItemCategory::Consumable => Color::srgba(0.4, 0.6, 0.4, 1.0),
+        ItemCategory::Key => Color::srgba(0.7, 0.65, 0.35, 1.0),
     }
 }
 
@@ -265,6 +266,7 @@ pub fn pickup_ground_items(
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
     ground_items: Query<(Entity, &Transform, &GroundItem), (With<Pickupable>, Without<Player>)>,
+    mut picked_up: MessageWriter<ItemPickedUp>,
 ) {
     if !bindings.just_pressed(GameAction::InventoryPickup, &keyboard, &mouse) {
         return;
@@ -291,6 +293,7 @@ pub fn pickup_ground_items(
         if world_pos.distance(item_pos) < pickup_radius {
             if inventory.try_add(ground_item.item_id, ground_item.quantity) {
                 commands.entity(entity).despawn();
+                picked_up.write(ItemPickedUp { item_id: ground_item.item_id, quantity: ground_item.quantity });
             }
             return;
         }
@@ -306,9 +309,10 @@ pub fn handle_inventory_right_click(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     registry: Res<ItemRegistry>,
-    mut player_query: Query<(&mut Inventory, &mut Health, &mut EquippedWeaponId), With<Player>>,
+    mut player_query: Query<(&mut Inventory, &mut Health, &MaxHealth, &mut EquippedWeaponId), With<Player>>,
     mut weapon_query: Query<&mut Weapon, With<PlayerWeapon>>,
     slot_query: Query<(&Interaction, &InventorySlotUI)>,
+    mut used: MessageWriter<ItemUsed>,
 ) {
     if !ui_state.open {
         return;
@@ -318,7 +322,7 @@ pub fn handle_inventory_right_click(
         return;
     }
 
-    let Ok((mut inventory, mut health, mut equipped)) = player_query.single_mut() else { return };
+    let Ok((mut inventory, mut health, max_health, mut equipped)) = player_query.single_mut() else { return };
 
     for (interaction, slot_ui) in &slot_query {
         if *interaction != Interaction::Hovered && *interaction != Interaction::Pressed {
@@ -332,7 +336,8 @@ pub fn handle_inventory_right_click(
 
             match item.category {
                 ItemCategory::Consumable => {
-                    if use_consumable(&registry, slot.item_id, &mut health) {
+                    if use_consumable(&registry, slot.item_id, &mut health, max_health) {
+                        used.write(ItemUsed { item_id: slot.item_id });
                         inventory.remove(slot_index, 1);
                     }
                 }
@@ -367,9 +372,10 @@ pub fn use_hotbar_keys(
     bindings: Res<InputBindings>,
     registry: Res<ItemRegistry>,
     mut selected_slot: ResMut<SelectedHotbarSlot>,
-    mut player_query: Query<(&mut Inventory, &mut Health), With<Player>>,
+    mut player_query: Query<(&mut Inventory, &mut Health, &MaxHealth), With<Player>>,
+    mut used: MessageWriter<ItemUsed>,
 ) {
-    let Ok((mut inventory, mut health)) = player_query.single_mut() else { return };
+    let Ok((mut inventory, mut health, max_health)) = player_query.single_mut() else { return };
 
     let actions = [
         GameAction::Hotbar1,
@@ -385,7 +391,8 @@ pub fn use_hotbar_keys(
                 let Some(item) = registry.items.get(&slot.item_id) else { continue };
                 match item.category {
                     ItemCategory::Consumable => {
-                        if use_consumable(&registry, slot.item_id, &mut health) {
+                        if use_consumable(&registry, slot.item_id, &mut health, max_health) {
+                            used.write(ItemUsed { item_id: slot.item_id });
                             inventory.remove(slot_index, 1);
                         }
                     }
@@ -440,15 +447,43 @@ pub fn sync_selected_weapon(
     }
 }
 
-fn use_consumable(registry: &ItemRegistry, item_id: ItemId, health: &mut Health) -> bool {
+/// Rebuild weapon stats from the reloaded config, in the registry and on the equipped weapon
+pub fn refresh_weapons_from_config(
+    mut reloaded: MessageReader<ConfigReloaded>,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut registry: ResMut<ItemRegistry>,
+    equipped_query: Query<&EquippedWeaponId, With<Player>>,
+    mut weapon_query: Query<&mut Weapon, With<PlayerWeapon>>,
+) {
+    if reloaded.read().count() == 0 {
+        return;
+    }
+
+    for (id, item) in registry.items.iter_mut() {
+        if item.weapon.is_some() {
+            item.weapon = get_weapon_stats(*id, &config, &mut meshes, &mut materials);
+        }
+    }
+
+    // Replacing the weapon also rebuilds its range indicator in update_weapon_visual
+    let Ok(equipped) = equipped_query.single() else { return };
+    let Ok(mut weapon) = weapon_query.single_mut() else { return };
+    if let Some(new_weapon) = get_weapon_stats(equipped.0, &config, &mut meshes, &mut materials) {
+        *weapon = new_weapon;
+    }
+}
+
+fn use_consumable(registry: &ItemRegistry, item_id: ItemId, health: &mut Health, max_health: &MaxHealth) -> bool {
     let Some(item) = registry.items.get(&item_id) else { return false };
 
     match &item.consumable_effect {
         Some(ConsumableEffect::Heal(amount)) => {
-            if health.0 >= 10 {
+            if health.0 >= max_health.0 {
                 return false; // Already at full HP
             }
-            health.0 = (health.0 + *amount).min(10);
+            health.0 = (health.0 + *amount).min(max_health.0);
             true
         }
         None => false,
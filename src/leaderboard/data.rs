@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::Stats;
+
+/// Leaderboard file with the best clears of every level
+pub const LEADERBOARD_FILE: &str = "leaderboard.ron";
+/// Clears kept per level
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: ron::error::SpannedError },
+    Serialize { path: String, error: ron::Error },
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderboardError::Io { path, error } => write!(f, "Failed to access leaderboard file {}: {}", path, error),
+            LeaderboardError::Parse { path, error } => write!(f, "Failed to parse leaderboard file {}: {}", path, error),
+            LeaderboardError::Serialize { path, error } => write!(f, "Failed to serialize leaderboard for {}: {}", path, error),
+        }
+    }
+}
+
+/// One clear of a level
+#[derive(Clone, Deserialize, Serialize)]
+pub struct LeaderboardEntry {
+    /// Seconds from entering the level to clearing it, pauses excluded
+    pub time: f32,
+    pub damage_taken: i32,
+    pub kills: u32,
+    /// Resources collected in the level
+    pub resources: Stats,
+    /// Unix time of the clear
+    #[serde(default)]
+    pub recorded_at: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn total_resources(&self) -> u32 {
+        self.resources.philosophy + self.resources.nature_study + self.resources.wisdom
+    }
+
+    /// Faster clears rank higher; equal times go to the one that took less damage
+    fn ranks_above(&self, other: &LeaderboardEntry) -> bool {
+        match self.time.total_cmp(&other.time) {
+            std::cmp::Ordering::Equal => self.damage_taken < other.damage_taken,
+            ordering => ordering.is_lt(),
+        }
+    }
+}
+
+/// Best clears per level, keyed by `LevelData::name`
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
+pub struct Leaderboard {
+    #[serde(default)]
+    pub levels: BTreeMap<String, Vec<LeaderboardEntry>>,
+}
+
+impl Leaderboard {
+    /// Best clears of a level, best first
+    pub fn entries(&self, level: &str) -> &[LeaderboardEntry] {
+        self.levels.get(level).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a clear to a level's table. Returns its rank (0 is best) when it made the table.
+    pub fn insert(&mut self, level: &str, entry: LeaderboardEntry) -> Option<usize> {
+        let entries = self.levels.entry(level.to_string()).or_default();
+        let rank = entries.iter().position(|other| entry.ranks_above(other)).unwrap_or(entries.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        entries.insert(rank, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, LeaderboardError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| LeaderboardError::Io { path: path.to_string(), error })?;
+        ron::from_str(&contents)
+            .map_err(|error| LeaderboardError::Parse { path: path.to_string(), error })
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), LeaderboardError> {
+        let config = ron::ser::PrettyConfig::new().indentor("    ".to_string());
+        let contents = ron::ser::to_string_pretty(self, config)
+            .map_err(|error| LeaderboardError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| LeaderboardError::Io { path: path.to_string(), error })
+    }
+}
@@ -0,0 +1,22 @@
+mod data;
+pub mod systems;
+
+pub use data::{Leaderboard, LeaderboardEntry, LeaderboardError, LEADERBOARD_FILE, LEADERBOARD_SIZE};
+pub use systems::LatestClear;
+
+use bevy::prelude::*;
+
+use crate::core::GameState;
+use crate::run_stats::systems::start_next_level;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Leaderboard>()
+            .init_resource::<LatestClear>()
+            .add_systems(Startup, systems::load_leaderboard)
+            .add_systems(OnEnter(GameState::LevelComplete), systems::record_level_clear.before(start_next_level))
+            .add_systems(OnEnter(GameState::Victory), systems::record_level_clear);
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::ErrorKind;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+use crate::levels::CurrentLevel;
+use crate::player::Stats;
+use crate::run_stats::RunStats;
+use super::{Leaderboard, LeaderboardEntry, LeaderboardError, LEADERBOARD_FILE};
+
+/// The level cleared last and where the clear landed on its table
+#[derive(Resource, Default)]
+pub struct LatestClear {
+    pub level: Option<String>,
+    /// Rank on the level's table, None when it missed the top entries
+    pub rank: Option<usize>,
+}
+
+/// Read the leaderboard at startup. Without a leaderboard file every table starts empty.
+pub fn load_leaderboard(mut leaderboard: ResMut<Leaderboard>) {
+    match Leaderboard::load_from_file(LEADERBOARD_FILE) {
+        Ok(loaded) => *leaderboard = loaded,
+        Err(LeaderboardError::Io { error, .. }) if error.kind() == ErrorKind::NotFound => {}
+        Err(error) => error!("{}", error),
+    }
+}
+
+/// Enter the cleared level on its table, measured from when the level started
+pub fn record_level_clear(
+    current_level: Res<CurrentLevel>,
+    run_stats: Res<RunStats>,
+    stats: Res<Stats>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut latest: ResMut<LatestClear>,
+) {
+    let Some(level) = current_level.data.as_ref() else { return };
+    let start = &run_stats.level_start;
+    let entry = LeaderboardEntry {
+        time: run_stats.time - start.time,
+        damage_taken: run_stats.damage_taken - start.damage_taken,
+        kills: run_stats.total_kills().saturating_sub(start.kills),
+        resources: Stats {
+            philosophy: stats.philosophy.saturating_sub(start.resources.philosophy),
+            nature_study: stats.nature_study.saturating_sub(start.resources.nature_study),
+            wisdom: stats.wisdom.saturating_sub(start.resources.wisdom),
+        },
+        recorded_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+    };
+
+    let rank = leaderboard.insert(&level.name, entry);
+    *latest = LatestClear { level: Some(level.name.clone()), rank };
+    if let Some(rank) = rank {
+        info!("Clear of {} ranked #{}", level.name, rank + 1);
+        if let Err(error) = leaderboard.save_to_file(LEADERBOARD_FILE) {
+            error!("{}", error);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Dead, DeathAnimation, GameState, Health};
+use crate::creatures::Creature;
+use crate::inventory::{GroundItem, Inventory, ItemId};
+use crate::player::Player;
+use crate::props::Prop;
+use super::spawner::checkpoint_color;
+use super::{
+    Checkpoint, CheckpointReached, CurrentLevel, LevelData, LevelProgress, LevelSpawnIndex, TriggerVolumeState,
+    WaveSpawnState,
+};
+
+/// Seconds the player lies dead before respawning at a checkpoint
+const RESPAWN_DELAY: f32 = 1.5;
+
+type LivingPlayer = (With<Player>, Without<Dead>);
+type LivingCreature = (With<Creature>, Without<Dead>, Without<DeathAnimation>);
+
+/// Everything needed to rebuild the level as it was when a checkpoint was reached
+#[derive(Clone, Deserialize, Serialize)]
+pub struct CheckpointSnapshot {
+    /// Index into `LevelData::checkpoints`
+    pub checkpoint: usize,
+    pub position: Vec2,
+    pub health: Health,
+    pub inventory: Inventory,
+    /// Indices into `LevelData::creatures` of creatures already killed
+    pub killed_creatures: Vec<usize>,
+    /// Indices into `LevelData::props` of props already destroyed
+    pub destroyed_props: Vec<usize>,
+    /// Positions of living creatures spawned by waves or triggers. Those are all goblins for now.
+    pub extra_creatures: Vec<Vec2>,
+    pub ground_items: Vec<(ItemId, u32, Vec2)>,
+    /// Level data as it was, including which doors were open and which areas locked
+    pub level: LevelData,
+    pub waves: WaveSpawnState,
+    pub triggers: TriggerVolumeState,
+}
+
+/// Last checkpoint reached in the current level. Reset when a level starts.
+#[derive(Resource, Default)]
+pub struct CheckpointState {
+    pub snapshot: Option<CheckpointSnapshot>,
+    /// Times the player has respawned in this level
+    pub respawns: u32,
+    /// Seconds since the player died, while waiting to respawn
+    pub respawn_timer: f32,
+}
+
+impl CheckpointState {
+    /// Respawns left in this level, or None when the level doesn't limit them
+    pub fn lives_left(&self, level: &LevelData) -> Option<u32> {
+        level.lives.map(|lives| lives.saturating_sub(self.respawns))
+    }
+
+    /// True when dying now respawns the player at a checkpoint instead of ending the run
+    pub fn can_respawn(&self, level: Option<&LevelData>) -> bool {
+        self.snapshot.is_some() && level.is_some_and(|level| self.lives_left(level) != Some(0))
+    }
+}
+
+/// Record a snapshot when the living player steps onto a checkpoint other than the active one
+pub fn activate_checkpoints(
+    mut progress: LevelProgress,
+    mut reached: MessageWriter<CheckpointReached>,
+    player_query: Query<(&Transform, &Health, &Inventory), LivingPlayer>,
+    checkpoint_query: Query<(&Transform, &Checkpoint)>,
+    creature_query: Query<(&Transform, Option<&LevelSpawnIndex>), LivingCreature>,
+    prop_query: Query<&LevelSpawnIndex, With<Prop>>,
+    ground_item_query: Query<(&Transform, &GroundItem)>,
+) {
+    let Some(level) = progress.current_level.data.as_ref() else { return };
+    let Ok((player_transform, health, inventory)) = player_query.single() else { return };
+    let player_pos = player_transform.translation.truncate();
+    let active = progress.checkpoint.snapshot.as_ref().map(|snapshot| snapshot.checkpoint);
+
+    let touched = checkpoint_query.iter().find(|(transform, checkpoint)| {
+        Some(checkpoint.index) != active
+            && transform.translation.truncate().distance(player_pos) <= checkpoint.radius
+    });
+    let Some((checkpoint_transform, checkpoint)) = touched else { return };
+
+    let mut alive_creatures = Vec::new();
+    let mut extra_creatures = Vec::new();
+    for (transform, spawn_index) in &creature_query {
+        match spawn_index {
+            Some(LevelSpawnIndex(index)) => alive_creatures.push(*index),
+            None => extra_creatures.push(transform.translation.truncate()),
+        }
+    }
+    let standing_props: Vec<usize> = prop_query.iter().map(|LevelSpawnIndex(index)| *index).collect();
+
+    let snapshot = CheckpointSnapshot {
+        checkpoint: checkpoint.index,
+        position: checkpoint_transform.translation.truncate(),
+        health: *health,
+        inventory: inventory.clone(),
+        killed_creatures: (0..level.creatures.len()).filter(|i| !alive_creatures.contains(i)).collect(),
+        destroyed_props: (0..level.props.len()).filter(|i| !standing_props.contains(i)).collect(),
+        extra_creatures,
+        ground_items: ground_item_query
+            .iter()
+            .map(|(transform, item)| (item.item_id, item.quantity, transform.translation.truncate()))
+            .collect(),
+        level: level.clone(),
+        waves: progress.waves.clone(),
+        triggers: progress.triggers.clone(),
+    };
+    progress.checkpoint.snapshot = Some(snapshot);
+    reached.write(CheckpointReached);
+    info!("Reached checkpoint #{}", checkpoint.index);
+}
+
+/// Light up the active checkpoint
+pub fn update_checkpoint_visuals(
+    state: Res<CheckpointState>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    checkpoint_query: Query<(&Checkpoint, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    let active = state.snapshot.as_ref().map(|snapshot| snapshot.checkpoint);
+    for (checkpoint, material) in &checkpoint_query {
+        let color = checkpoint_color(Some(checkpoint.index) == active);
+        if materials.get(&material.0).is_some_and(|m| m.color != color) {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.color = color;
+            }
+        }
+    }
+}
+
+/// After the player has been dead for a moment, respawn at the last checkpoint while lives remain.
+/// Without a checkpoint or lives the death screen takes over as before.
+pub fn respawn_after_death(
+    time: Res<Time>,
+    mut state: ResMut<CheckpointState>,
+    current_level: Res<CurrentLevel>,
+    dead_player_query: Query<(), (With<Player>, With<Dead>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if dead_player_query.is_empty() {
+        state.respawn_timer = 0.0;
+        return;
+    }
+    if !state.can_respawn(current_level.data.as_ref()) {
+        return;
+    }
+
+    state.respawn_timer += time.delta_secs();
+    if state.respawn_timer >= RESPAWN_DELAY {
+        state.respawn_timer = 0.0;
+        next_state.set(GameState::Respawning);
+    }
+}
@@ -1,9 +1,14 @@
+use std::borrow::Cow;
+use std::fmt;
+
 use bevy::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::inventory::ItemId;
+use crate::core::Health;
+use crate::inventory::{Inventory, ItemId};
+use super::{generate_level, GeneratorSettings};
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LevelBounds {
     pub min: Vec2,
     pub max: Vec2,
@@ -40,34 +45,358 @@ impl LevelBounds {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
-pub struct WalkableRect {
-    pub min: Vec2,
-    pub max: Vec2,
+/// Distance under which a point counts as lying on a shape's outline
+const EDGE_EPSILON: f32 = 0.01;
+
+/// One piece of the walkable floor. Polygons may be concave; points are listed in order.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "WalkableShapeDef")]
+pub enum WalkableShape {
+    Rect { min: Vec2, max: Vec2 },
+    Polygon { points: Vec<Vec2> },
+    Circle { center: Vec2, radius: f32 },
 }
 
-impl WalkableRect {
+/// Accepts tagged shapes and, because RON drops variant names here, tells RON shapes apart
+/// by their fields. This keeps the plain `(min: .., max: ..)` rects of older level files loading.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WalkableShapeDef {
+    Tagged(TaggedWalkableShape),
+    Rect { min: Vec2, max: Vec2 },
+    Polygon { points: Vec<Vec2> },
+    Circle { center: Vec2, radius: f32 },
+}
+
+/// `WalkableShape` as the JSON save format writes it
+#[derive(Deserialize)]
+enum TaggedWalkableShape {
+    Rect { min: Vec2, max: Vec2 },
+    Polygon { points: Vec<Vec2> },
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl From<WalkableShapeDef> for WalkableShape {
+    fn from(def: WalkableShapeDef) -> Self {
+        match def {
+            WalkableShapeDef::Tagged(TaggedWalkableShape::Rect { min, max }) | WalkableShapeDef::Rect { min, max } => {
+                WalkableShape::Rect { min, max }
+            }
+            WalkableShapeDef::Tagged(TaggedWalkableShape::Polygon { points }) | WalkableShapeDef::Polygon { points } => {
+                WalkableShape::Polygon { points }
+            }
+            WalkableShapeDef::Tagged(TaggedWalkableShape::Circle { center, radius })
+            | WalkableShapeDef::Circle { center, radius } => WalkableShape::Circle { center, radius },
+        }
+    }
+}
+
+impl WalkableShape {
+    /// True for shapes without area: inverted rects, polygons with under 3 points, circles without radius
+    pub fn is_degenerate(&self) -> bool {
+        match self {
+            WalkableShape::Rect { min, max } => min.x >= max.x || min.y >= max.y,
+            WalkableShape::Polygon { points } => points.len() < 3,
+            WalkableShape::Circle { radius, .. } => *radius <= 0.0,
+        }
+    }
+
     pub fn contains(&self, point: Vec2) -> bool {
-        point.x >= self.min.x
-            && point.x <= self.max.x
-            && point.y >= self.min.y
-            && point.y <= self.max.y
+        match self {
+            WalkableShape::Rect { min, max } => {
+                point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+            }
+            WalkableShape::Circle { center, radius } => point.distance_squared(*center) <= radius * radius,
+            WalkableShape::Polygon { points } => {
+                polygon_contains(points, point)
+                    || polygon_edges(points).any(|(a, b)| closest_point_on_segment(a, b, point).distance(point) <= EDGE_EPSILON)
+            }
+        }
     }
 
+    /// Nearest point inside the shape. Points already inside are returned unchanged.
     pub fn clamp(&self, point: Vec2) -> Vec2 {
+        if self.contains(point) {
+            return point;
+        }
+        match self {
+            WalkableShape::Rect { min, max } => point.clamp(*min, *max),
+            WalkableShape::Circle { center, radius } => *center + (point - *center).normalize_or_zero() * *radius,
+            WalkableShape::Polygon { points } => {
+                let mut best_point = point;
+                let mut best_dist = f32::MAX;
+                for (a, b) in polygon_edges(points) {
+                    let candidate = closest_point_on_segment(a, b, point);
+                    let dist = candidate.distance_squared(point);
+                    if dist < best_dist {
+                        best_dist = dist;
+                        best_point = candidate;
+                    }
+                }
+                best_point
+            }
+        }
+    }
+
+    /// Nearest point just outside the shape, for pushing things out of a blocked area
+    pub fn closest_outside(&self, point: Vec2) -> Vec2 {
+        let boundary = match self {
+            WalkableShape::Rect { min, max } => [
+                Vec2::new(min.x, point.y),
+                Vec2::new(max.x, point.y),
+                Vec2::new(point.x, min.y),
+                Vec2::new(point.x, max.y),
+            ]
+            .into_iter()
+            .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+            .unwrap_or(point),
+            WalkableShape::Circle { center, radius } => {
+                *center + (point - *center).normalize_or(Vec2::X) * *radius
+            }
+            WalkableShape::Polygon { points } => polygon_edges(points)
+                .map(|(a, b)| closest_point_on_segment(a, b, point))
+                .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+                .unwrap_or(point),
+        };
+        boundary + (boundary - point).normalize_or_zero() * EDGE_EPSILON * 2.0
+    }
+
+    /// Outline vertices. Circles have none and are handled separately by `touches`.
+    pub fn vertices(&self) -> Vec<Vec2> {
+        match self {
+            WalkableShape::Rect { min, max } => vec![
+                *min,
+                Vec2::new(max.x, min.y),
+                *max,
+                Vec2::new(min.x, max.y),
+            ],
+            WalkableShape::Polygon { points } => points.clone(),
+            WalkableShape::Circle { .. } => Vec::new(),
+        }
+    }
+
+    /// Smallest axis-aligned rect around the shape
+    pub fn bounding_rect(&self) -> Rect {
+        match self {
+            WalkableShape::Rect { min, max } => Rect::from_corners(*min, *max),
+            WalkableShape::Polygon { points } => points
+                .iter()
+                .fold(Rect::from_center_size(points.first().copied().unwrap_or_default(), Vec2::ZERO), |acc, p| {
+                    acc.union_point(*p)
+                }),
+            WalkableShape::Circle { center, radius } => Rect::from_center_half_size(*center, Vec2::splat(*radius)),
+        }
+    }
+
+    /// The same shape moved by `offset`
+    pub fn translated(&self, offset: Vec2) -> WalkableShape {
+        match self {
+            WalkableShape::Rect { min, max } => WalkableShape::Rect { min: *min + offset, max: *max + offset },
+            WalkableShape::Polygon { points } => WalkableShape::Polygon {
+                points: points.iter().map(|p| *p + offset).collect(),
+            },
+            WalkableShape::Circle { center, radius } => WalkableShape::Circle { center: *center + offset, radius: *radius },
+        }
+    }
+
+    /// True if the two shapes overlap or share an edge
+    pub fn touches(&self, other: &WalkableShape) -> bool {
+        if let WalkableShape::Circle { center, radius } = self {
+            return other.clamp(*center).distance(*center) <= radius + EDGE_EPSILON;
+        }
+        if let WalkableShape::Circle { center, radius } = other {
+            return self.clamp(*center).distance(*center) <= radius + EDGE_EPSILON;
+        }
+
+        let self_vertices = self.vertices();
+        let other_vertices = other.vertices();
+
+        self_vertices.iter().any(|v| other.contains(*v))
+            || other_vertices.iter().any(|v| self.contains(*v))
+            || polygon_edges(&self_vertices).any(|(a, b)| {
+                polygon_edges(&other_vertices).any(|(c, d)| segments_intersect(a, b, c, d))
+            })
+    }
+}
+
+fn polygon_edges(points: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    (0..points.len()).map(move |i| (points[i], points[(i + 1) % points.len()]))
+}
+
+/// Even-odd ray cast, works for concave polygons
+fn polygon_contains(points: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in polygon_edges(points) {
+        if (a.y > point.y) != (b.y > point.y) {
+            let x_at_y = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x_at_y {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn closest_point_on_segment(a: Vec2, b: Vec2, point: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length_sq = ab.length_squared();
+    if length_sq <= f32::EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+fn segments_intersect(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let d1 = (b - a).perp_dot(c - a);
+    let d2 = (b - a).perp_dot(d - a);
+    let d3 = (d - c).perp_dot(a - c);
+    let d4 = (d - c).perp_dot(b - c);
+    (d1 * d2 < 0.0) && (d3 * d4 < 0.0)
+}
+
+/// Cell character meaning "no tile here"; walkability falls back to the walkable shapes
+pub const EMPTY_TILE: char = ' ';
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TerrainDef {
+    pub id: char,
+    pub walkable: bool,
+    /// pixel-gen autotile sheet (4x4 tiles, corner bit flags NW=1, NE=2, SW=4, SE=8)
+    #[serde(default)]
+    pub sheet: Option<String>,
+    /// Pixel size of one tile in the sheet, independent of the layer's world `tile_size`
+    #[serde(default = "default_sheet_tile_size")]
+    pub sheet_tile_size: u32,
+}
+
+/// pixel-gen's default autotile size
+fn default_sheet_tile_size() -> u32 {
+    16
+}
+
+/// Grid of terrain ids laid over the level. Row 0 is the top row; `origin` is its top-left corner.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TileLayer {
+    pub origin: Vec2,
+    pub tile_size: f32,
+    pub terrains: Vec<TerrainDef>,
+    /// Written as one string per row, kept as chars so cell lookups are O(1)
+    #[serde(with = "tile_rows")]
+    pub rows: Vec<Vec<char>>,
+}
+
+mod tile_rows {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(rows: &[Vec<char>], serializer: S) -> Result<S::Ok, S::Error> {
+        let lines: Vec<String> = rows.iter().map(|row| row.iter().collect()).collect();
+        lines.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<char>>, D::Error> {
+        let lines = Vec::<String>::deserialize(deserializer)?;
+        Ok(lines.iter().map(|line| line.chars().collect()).collect())
+    }
+}
+
+impl TileLayer {
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn terrain(&self, id: char) -> Option<&TerrainDef> {
+        self.terrains.iter().find(|t| t.id == id)
+    }
+
+    /// Terrain id at a grid cell, or `EMPTY_TILE` outside the grid
+    pub fn id_at(&self, col: i32, row: i32) -> char {
+        if col < 0 || row < 0 {
+            return EMPTY_TILE;
+        }
+        self.rows
+            .get(row as usize)
+            .and_then(|r| r.get(col as usize).copied())
+            .unwrap_or(EMPTY_TILE)
+    }
+
+    pub fn cell_at(&self, point: Vec2) -> (i32, i32) {
+        let col = ((point.x - self.origin.x) / self.tile_size).floor() as i32;
+        let row = ((self.origin.y - point.y) / self.tile_size).floor() as i32;
+        (col, row)
+    }
+
+    pub fn cell_center(&self, col: i32, row: i32) -> Vec2 {
         Vec2::new(
-            point.x.clamp(self.min.x, self.max.x),
-            point.y.clamp(self.min.y, self.max.y),
+            self.origin.x + (col as f32 + 0.5) * self.tile_size,
+            self.origin.y - (row as f32 + 0.5) * self.tile_size,
         )
     }
+
+    /// Terrain under a world point, `None` for empty cells and points outside the grid
+    pub fn terrain_at(&self, point: Vec2) -> Option<&TerrainDef> {
+        let (col, row) = self.cell_at(point);
+        self.terrain(self.id_at(col, row))
+    }
+
+    /// Blob autotile index for a cell, matching pixel-gen's `generate_autotile_preview`
+    pub fn autotile_index(&self, col: i32, row: i32) -> usize {
+        let id = self.id_at(col, row);
+        let get = |c: i32, r: i32| self.id_at(c, r) == id;
+
+        let nw = get(col, row) && get(col - 1, row) && get(col, row - 1) && get(col - 1, row - 1);
+        let ne = get(col, row) && get(col + 1, row) && get(col, row - 1) && get(col + 1, row - 1);
+        let sw = get(col, row) && get(col - 1, row) && get(col, row + 1) && get(col - 1, row + 1);
+        let se = get(col, row) && get(col + 1, row) && get(col, row + 1) && get(col + 1, row + 1);
+
+        (nw as usize) | ((ne as usize) << 1) | ((sw as usize) << 2) | ((se as usize) << 3)
+    }
+
+    /// True for cells whose terrain blocks movement
+    pub fn is_cell_blocking(&self, col: i32, row: i32) -> bool {
+        self.terrain(self.id_at(col, row)).is_some_and(|t| !t.walkable)
+    }
+
+    /// Candidate points for leaving blocking terrain: the nearest point of every non-blocking
+    /// cell and the nearest point just outside the grid, each nudged off the cell border
+    fn escape_candidates(&self, point: Vec2) -> Vec<Vec2> {
+        let inset = EDGE_EPSILON;
+        let half = self.tile_size / 2.0 - inset;
+        let mut candidates = Vec::new();
+
+        for row in 0..self.height() as i32 {
+            for col in 0..self.width() as i32 {
+                if self.is_cell_blocking(col, row) {
+                    continue;
+                }
+                let center = self.cell_center(col, row);
+                candidates.push(point.clamp(center - Vec2::splat(half), center + Vec2::splat(half)));
+            }
+        }
+
+        let left = self.origin.x;
+        let right = self.origin.x + self.width() as f32 * self.tile_size;
+        let top = self.origin.y;
+        let bottom = self.origin.y - self.height() as f32 * self.tile_size;
+        candidates.push(Vec2::new(left - inset, point.y));
+        candidates.push(Vec2::new(right + inset, point.y));
+        candidates.push(Vec2::new(point.x, top + inset));
+        candidates.push(Vec2::new(point.x, bottom - inset));
+
+        candidates
+    }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum CreatureType {
     Goblin,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum PropType {
     Pillar,
     Barrel,
@@ -75,19 +404,48 @@ pub enum PropType {
     Crate2,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CreatureSpawn {
     pub creature: CreatureType,
     pub position: Vec2,
+    /// Lets objectives refer to this creature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Waypoints to walk instead of wandering around the spawn point
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<PatrolRouteData>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// What a patrolling creature does after its last waypoint
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PatrolRouteMode {
+    /// Walk from the last waypoint back to the first
+    #[default]
+    Loop,
+    /// Walk the waypoints back in reverse order
+    PingPong,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PatrolRouteData {
+    pub waypoints: Vec<Vec2>,
+    #[serde(default)]
+    pub mode: PatrolRouteMode,
+    /// Seconds to stand at each waypoint
+    #[serde(default)]
+    pub wait: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PropSpawn {
     pub prop: PropType,
     pub position: Vec2,
+    /// Lets objectives refer to this prop
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ItemSpawn {
     pub item_id: ItemId,
     #[serde(default = "default_quantity")]
@@ -99,47 +457,803 @@ fn default_quantity() -> u32 {
     1
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct WinZoneData {
     pub position: Vec2,
     pub radius: f32,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Respawn point; touching it records where and how the player respawns after dying
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CheckpointData {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PitData {
     pub position: Vec2,
     pub radius: f32,
     pub edge_radius: f32,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum DoorKind {
+    #[default]
+    Door,
+    Gate,
+}
+
+/// Door or gate that blocks movement while closed. `position` is the center of its footprint.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DoorData {
+    pub name: String,
+    #[serde(default)]
+    pub kind: DoorKind,
+    pub position: Vec2,
+    pub size: Vec2,
+    #[serde(default)]
+    pub open: bool,
+    /// Key item that opens the door when the player interacts with it
+    #[serde(default)]
+    pub key: Option<ItemId>,
+}
+
+impl DoorData {
+    pub fn footprint(&self) -> WalkableShape {
+        WalkableShape::Rect {
+            min: self.position - self.size / 2.0,
+            max: self.position + self.size / 2.0,
+        }
+    }
+}
+
+/// Lever that toggles a door when the player interacts with it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LeverData {
+    pub door: String,
+    pub position: Vec2,
+}
+
+/// Condition that starts a wave. A wave starts once all of its triggers hold.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum WaveTrigger {
+    /// Seconds since the level started
+    Timer { seconds: f32 },
+    /// Player inside a circle
+    RegionEntered { center: Vec2, radius: f32 },
+    /// No hostile creature left alive
+    AllEnemiesDead,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WaveCreature {
+    pub creature: CreatureType,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WaveData {
+    pub triggers: Vec<WaveTrigger>,
+    /// Seconds between trigger and first spawn
+    #[serde(default)]
+    pub delay: f32,
+    /// Seconds between spawn rounds
+    pub interval: f32,
+    /// Spawns per round; spawn points are used in order and wrap around
+    #[serde(default = "default_per_round")]
+    pub per_round: u32,
+    pub spawn_points: Vec<Vec2>,
+    /// Creatures are spawned in list order until every count is used up
+    pub creatures: Vec<WaveCreature>,
+    /// Spawned creatures rush to this point before patrolling around it
+    #[serde(default)]
+    pub target: Option<Vec2>,
+}
+
+fn default_per_round() -> u32 {
+    1
+}
+
+impl WaveData {
+    pub fn total_count(&self) -> u32 {
+        self.creatures.iter().map(|c| c.count).sum()
+    }
+
+    /// Creature type of the n-th spawn in this wave
+    pub fn creature_for(&self, index: u32) -> Option<&CreatureType> {
+        let mut remaining = index;
+        for entry in &self.creatures {
+            if remaining < entry.count {
+                return Some(&entry.creature);
+            }
+            remaining -= entry.count;
+        }
+        None
+    }
+}
+
+/// What a hazard does to anyone standing in it while it is active
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum HazardEffect {
+    /// Continuous damage, in health per second
+    Damage { per_second: f32 },
+    /// A hit of `damage` on stepping in, then again every `interval` seconds while staying in
+    Spikes { damage: i32, interval: f32 },
+    /// Scales movement speed; below 1 slows, above 1 speeds up
+    Speed { multiplier: f32 },
+    /// Shoves away from the middle of the hazard on stepping in
+    Knockback { force: f32 },
+}
+
+/// When a hazard is active
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum HazardTiming {
+    #[default]
+    Always,
+    /// On for `on` seconds, then off for `off` seconds. `offset` shifts the cycle so hazards can take turns.
+    Cycle {
+        on: f32,
+        off: f32,
+        #[serde(default)]
+        offset: f32,
+    },
+}
+
+impl HazardTiming {
+    pub fn is_active(&self, time: f32) -> bool {
+        match self {
+            HazardTiming::Always => true,
+            HazardTiming::Cycle { on, off, offset } => (time + offset).rem_euclid(on + off) < *on,
+        }
+    }
+}
+
+/// Area that hurts, slows or shoves the player and creatures standing in it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HazardData {
+    pub area: WalkableShape,
+    pub effects: Vec<HazardEffect>,
+    #[serde(default)]
+    pub timing: HazardTiming,
+}
+
+impl HazardData {
+    /// Product of the speed multipliers of this hazard
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| match effect {
+                HazardEffect::Speed { multiplier } => *multiplier,
+                _ => 1.0,
+            })
+            .product()
+    }
+}
+
+/// Named area that blocks movement while locked. Trigger actions lock and unlock it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LockableArea {
+    pub name: String,
+    pub area: WalkableShape,
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// When a trigger volume fires relative to the player
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum TriggerFiring {
+    Enter,
+    Exit,
+    /// Fires every `interval` seconds while the player stays inside
+    Stay { interval: f32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum TriggerCondition {
+    AllHostilesDead,
+    ItemHeld {
+        item: ItemId,
+        #[serde(default = "default_quantity")]
+        count: u32,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum TriggerAction {
+    SpawnCreatures {
+        creature: CreatureType,
+        positions: Vec<Vec2>,
+        #[serde(default)]
+        target: Option<Vec2>,
+    },
+    ShowText {
+        text: String,
+        #[serde(default = "default_prompt_duration")]
+        duration: f32,
+    },
+    LockArea { area: String },
+    UnlockArea { area: String },
+    OpenDoor { door: String },
+    CloseDoor { door: String },
+    PlayMusic { track: String },
+    StopMusic,
+    /// Multiply the camera zoom; 1.0 is the normal view
+    CameraZoom { scale: f32 },
+    CameraReset,
+}
+
+fn default_prompt_duration() -> f32 {
+    3.0
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TriggerVolumeData {
+    #[serde(default)]
+    pub name: String,
+    pub area: WalkableShape,
+    pub firing: TriggerFiring,
+    /// Fire every time the firing rule matches instead of only once
+    #[serde(default)]
+    pub repeat: bool,
+    #[serde(default)]
+    pub conditions: Vec<TriggerCondition>,
+    pub actions: Vec<TriggerAction>,
+}
+
+/// What the player has to do to finish the level. `All` and `Any` combine objectives.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Objective {
+    /// Hold the win zone for `time` seconds once everything else is done.
+    /// Without a time the game's default applies.
+    ReachZone {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        time: Option<f32>,
+    },
+    /// Defeat every hostile, including waves that are still spawning
+    KillAll,
+    Survive { seconds: f32 },
+    /// Hold this many of an item in the inventory
+    Collect {
+        item: ItemId,
+        #[serde(default = "default_quantity")]
+        count: u32,
+    },
+    /// Destroy the props with these names
+    DestroyProps { props: Vec<String> },
+    /// Defeat the creature with this name
+    DefeatCreature { name: String },
+    All(Vec<Objective>),
+    Any(Vec<Objective>),
+}
+
+impl Default for Objective {
+    /// Levels without an objective are won by clearing them and then holding the win zone
+    fn default() -> Self {
+        Objective::All(vec![Objective::KillAll, Objective::ReachZone { time: None }])
+    }
+}
+
+impl Objective {
+    /// Hold time of the first `ReachZone` in the objective, using `default` when it sets none
+    pub fn zone_time(&self, default: f32) -> Option<f32> {
+        match self {
+            Objective::ReachZone { time } => Some(time.unwrap_or(default)),
+            Objective::All(objectives) | Objective::Any(objectives) => {
+                objectives.iter().find_map(|objective| objective.zone_time(default))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LevelData {
     pub name: String,
     pub bounds: LevelBounds,
-    pub walkable: Vec<WalkableRect>,
+    pub walkable: Vec<WalkableShape>,
     pub player_spawn: Vec2,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ItemSpawn>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub creatures: Vec<CreatureSpawn>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub props: Vec<PropSpawn>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub win_zone: Option<WinZoneData>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub pits: Vec<PitData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hazards: Vec<HazardData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tiles: Option<TileLayer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waves: Vec<WaveData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub areas: Vec<LockableArea>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<TriggerVolumeData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doors: Vec<DoorData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub levers: Vec<LeverData>,
+    /// Defaults to clearing the level and holding the win zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<Objective>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<CheckpointData>,
+    /// Respawns allowed at checkpoints before dying ends the run; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lives: Option<u32>,
+}
+
+#[derive(Debug)]
+pub enum LevelLoadError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: ron::error::SpannedError },
+    Invalid { path: String, issues: Vec<LevelIssue> },
+    /// The campaign has no level to play, usually because its file could not be loaded
+    NoCampaignLevel { reason: String },
+}
+
+impl fmt::Display for LevelLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelLoadError::Io { path, error } => write!(f, "Failed to read level file {}: {}", path, error),
+            LevelLoadError::Parse { path, error } => write!(f, "Failed to parse level file {}: {}", path, error),
+            LevelLoadError::Invalid { path, issues } => {
+                write!(f, "Level file {} has {} problem(s):", path, issues.len())?;
+                for issue in issues {
+                    write!(f, "\n  - {}", issue)?;
+                }
+                Ok(())
+            }
+            LevelLoadError::NoCampaignLevel { reason } => write!(f, "No level to play: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CampaignLoadError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: ron::error::SpannedError },
+    Empty { path: String },
+}
+
+impl fmt::Display for CampaignLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CampaignLoadError::Io { path, error } => write!(f, "Failed to read campaign file {}: {}", path, error),
+            CampaignLoadError::Parse { path, error } => write!(f, "Failed to parse campaign file {}: {}", path, error),
+            CampaignLoadError::Empty { path } => write!(f, "Campaign file {} lists no levels", path),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelSaveError {
+    Io { path: String, error: std::io::Error },
+    Serialize { path: String, error: ron::Error },
+}
+
+impl fmt::Display for LevelSaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelSaveError::Io { path, error } => write!(f, "Failed to write level file {}: {}", path, error),
+            LevelSaveError::Serialize { path, error } => write!(f, "Failed to serialize level for {}: {}", path, error),
+        }
+    }
+}
+
+/// A single problem found by `LevelData::validate`
+#[derive(Clone, Debug)]
+pub enum LevelIssue {
+    NoWalkableArea,
+    DegenerateShape { index: usize },
+    ShapeOutsideBounds { index: usize },
+    InvalidTileSize { tile_size: f32 },
+    InvalidWave { index: usize, reason: &'static str },
+    InvalidHazard { index: usize, reason: &'static str },
+    InvalidRoute { creature: usize, reason: &'static str },
+    UnknownArea { trigger: usize, area: String },
+    UnknownDoor { what: String, door: String },
+    UnknownTerrain { id: char, row: usize, col: usize },
+    SpawnInVoid { what: String, position: Vec2 },
+    OutsideBounds { what: String, position: Vec2 },
+    PropOverlapsPit { prop: PropType, position: Vec2, pit_position: Vec2 },
+    WinZoneUnreachable { position: Vec2 },
+    ObjectiveNeedsWinZone,
+    UnknownObjectiveTarget { what: &'static str, name: String },
+    InvalidObjective { reason: &'static str },
+}
+
+impl fmt::Display for LevelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelIssue::NoWalkableArea => write!(f, "walkable area is empty"),
+            LevelIssue::DegenerateShape { index } => {
+                write!(f, "walkable shape #{} has no area (polygon needs 3+ points, circle a positive radius)", index)
+            }
+            LevelIssue::ShapeOutsideBounds { index } => {
+                write!(f, "walkable shape #{} reaches outside the level bounds", index)
+            }
+            LevelIssue::InvalidWave { index, reason } => write!(f, "wave #{} {}", index, reason),
+            LevelIssue::InvalidHazard { index, reason } => write!(f, "hazard #{} {}", index, reason),
+            LevelIssue::InvalidRoute { creature, reason } => write!(f, "patrol route of creature #{} {}", creature, reason),
+            LevelIssue::UnknownArea { trigger, area } => {
+                write!(f, "trigger #{} refers to unknown area \"{}\"", trigger, area)
+            }
+            LevelIssue::UnknownDoor { what, door } => write!(f, "{} refers to unknown door \"{}\"", what, door),
+            LevelIssue::InvalidTileSize { tile_size } => write!(f, "tile size {} must be positive", tile_size),
+            LevelIssue::UnknownTerrain { id, row, col } => {
+                write!(f, "tile '{}' at row {}, column {} has no terrain definition", id, row, col)
+            }
+            LevelIssue::SpawnInVoid { what, position } => {
+                write!(f, "{} at ({}, {}) is outside the walkable area", what, position.x, position.y)
+            }
+            LevelIssue::OutsideBounds { what, position } => {
+                write!(f, "{} at ({}, {}) is outside the level bounds", what, position.x, position.y)
+            }
+            LevelIssue::PropOverlapsPit { prop, position, pit_position } => write!(
+                f,
+                "{:?} at ({}, {}) overlaps pit at ({}, {})",
+                prop, position.x, position.y, pit_position.x, pit_position.y
+            ),
+            LevelIssue::WinZoneUnreachable { position } => write!(
+                f,
+                "win zone at ({}, {}) cannot be reached from the player spawn",
+                position.x, position.y
+            ),
+            LevelIssue::ObjectiveNeedsWinZone => write!(f, "objective asks to reach the win zone but the level has none"),
+            LevelIssue::UnknownObjectiveTarget { what, name } => {
+                write!(f, "objective refers to unknown {} \"{}\"", what, name)
+            }
+            LevelIssue::InvalidObjective { reason } => write!(f, "objective {}", reason),
+        }
+    }
 }
 
 impl LevelData {
-    pub fn load_from_file(path: &str) -> Self {
+    pub fn load_from_file(path: &str) -> Result<Self, LevelLoadError> {
         let contents = std::fs::read_to_string(path)
-            .unwrap_or_else(|_| panic!("Failed to read level file: {}", path));
+            .map_err(|error| LevelLoadError::Io { path: path.to_string(), error })?;
         ron::from_str(&contents)
-            .unwrap_or_else(|e| panic!("Failed to parse level file {}: {}", path, e))
+            .map_err(|error| LevelLoadError::Parse { path: path.to_string(), error })
+    }
+
+    /// Write the level as pretty-printed RON that `load_from_file` reads back unchanged
+    pub fn save_to_file(&self, path: &str) -> Result<(), LevelSaveError> {
+        let config = ron::ser::PrettyConfig::new().indentor("    ".to_string());
+        let contents = ron::ser::to_string_pretty(self, config)
+            .map_err(|error| LevelSaveError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| LevelSaveError::Io { path: path.to_string(), error })
+    }
+
+    /// Load a level and reject it if validation finds any issues
+    pub fn load_validated(path: &str) -> Result<Self, LevelLoadError> {
+        let data = Self::load_from_file(path)?;
+        let issues = data.validate();
+        if issues.is_empty() {
+            Ok(data)
+        } else {
+            Err(LevelLoadError::Invalid { path: path.to_string(), issues })
+        }
+    }
+
+    /// Check the level for content that would leave it broken or unwinnable
+    pub fn validate(&self) -> Vec<LevelIssue> {
+        let mut issues = Vec::new();
+
+        if let Some(tiles) = &self.tiles {
+            if tiles.tile_size <= 0.0 {
+                issues.push(LevelIssue::InvalidTileSize { tile_size: tiles.tile_size });
+                return issues;
+            }
+            for (row, line) in tiles.rows.iter().enumerate() {
+                for (col, &id) in line.iter().enumerate() {
+                    if id != EMPTY_TILE && tiles.terrain(id).is_none() {
+                        issues.push(LevelIssue::UnknownTerrain { id, row, col });
+                    }
+                }
+            }
+        }
+
+        let has_walkable_tiles = self.tiles.as_ref().is_some_and(|tiles| {
+            tiles.rows.iter().flatten().any(|&id| tiles.terrain(id).is_some_and(|t| t.walkable))
+        });
+        if self.walkable.is_empty() && !has_walkable_tiles {
+            issues.push(LevelIssue::NoWalkableArea);
+            return issues;
+        }
+
+        for (index, shape) in self.walkable.iter().enumerate() {
+            if shape.is_degenerate() {
+                issues.push(LevelIssue::DegenerateShape { index });
+            }
+            let rect = shape.bounding_rect();
+            if !self.bounds.contains(rect.min) || !self.bounds.contains(rect.max) {
+                issues.push(LevelIssue::ShapeOutsideBounds { index });
+            }
+        }
+
+        // Spawns outside the bounds or in the void
+        let mut check_spawn = |what: String, position: Vec2| {
+            if !self.bounds.contains(position) {
+                issues.push(LevelIssue::OutsideBounds { what, position });
+            } else if !self.is_walkable(position) {
+                issues.push(LevelIssue::SpawnInVoid { what, position });
+            }
+        };
+        check_spawn("player spawn".to_string(), self.player_spawn);
+        for (index, spawn) in self.creatures.iter().enumerate() {
+            check_spawn(format!("{:?}", spawn.creature), spawn.position);
+            for (waypoint, position) in spawn.route.iter().flat_map(|route| route.waypoints.iter().enumerate()) {
+                check_spawn(format!("waypoint #{} of creature #{}", waypoint, index), *position);
+            }
+        }
+        for spawn in &self.items {
+            check_spawn(format!("{:?}", spawn.item_id), spawn.position);
+        }
+        for spawn in &self.props {
+            check_spawn(format!("{:?}", spawn.prop), spawn.position);
+        }
+        if let Some(win_zone) = &self.win_zone {
+            check_spawn("win zone".to_string(), win_zone.position);
+        }
+        for (index, checkpoint) in self.checkpoints.iter().enumerate() {
+            check_spawn(format!("checkpoint #{}", index), checkpoint.position);
+        }
+        for lever in &self.levers {
+            check_spawn(format!("lever for \"{}\"", lever.door), lever.position);
+        }
+        // A closed door blocks its own footprint, so only the ground beneath it counts
+        for door in &self.doors {
+            let what = format!("door \"{}\"", door.name);
+            if !self.bounds.contains(door.position) {
+                issues.push(LevelIssue::OutsideBounds { what, position: door.position });
+            } else if !self.is_walkable_ignoring_barriers(door.position) {
+                issues.push(LevelIssue::SpawnInVoid { what, position: door.position });
+            }
+        }
+
+        for (index, wave) in self.waves.iter().enumerate() {
+            if wave.spawn_points.is_empty() {
+                issues.push(LevelIssue::InvalidWave { index, reason: "has no spawn points" });
+            }
+            for (point, position) in wave.spawn_points.iter().enumerate() {
+                if !self.bounds.contains(*position) {
+                    issues.push(LevelIssue::OutsideBounds {
+                        what: format!("spawn point #{} of wave #{}", point, index),
+                        position: *position,
+                    });
+                }
+            }
+            if wave.interval <= 0.0 {
+                issues.push(LevelIssue::InvalidWave { index, reason: "needs a positive interval" });
+            }
+            if wave.per_round == 0 {
+                issues.push(LevelIssue::InvalidWave { index, reason: "spawns nothing per round" });
+            }
+        }
+
+        for (index, hazard) in self.hazards.iter().enumerate() {
+            if hazard.area.is_degenerate() {
+                issues.push(LevelIssue::InvalidHazard { index, reason: "has no area" });
+            }
+            if hazard.effects.is_empty() {
+                issues.push(LevelIssue::InvalidHazard { index, reason: "has no effects" });
+            }
+            for effect in &hazard.effects {
+                match effect {
+                    HazardEffect::Spikes { interval, .. } if *interval <= 0.0 => {
+                        issues.push(LevelIssue::InvalidHazard { index, reason: "needs a positive spike interval" });
+                    }
+                    HazardEffect::Speed { multiplier } if *multiplier < 0.0 => {
+                        issues.push(LevelIssue::InvalidHazard { index, reason: "has a negative speed multiplier" });
+                    }
+                    _ => {}
+                }
+            }
+            if let HazardTiming::Cycle { on, off, .. } = hazard.timing {
+                if on <= 0.0 || off < 0.0 {
+                    issues.push(LevelIssue::InvalidHazard { index, reason: "needs a positive on time and no negative off time" });
+                }
+            }
+        }
+
+        for (creature, route) in self.creatures.iter().enumerate().filter_map(|(i, c)| Some((i, c.route.as_ref()?))) {
+            if route.waypoints.is_empty() {
+                issues.push(LevelIssue::InvalidRoute { creature, reason: "has no waypoints" });
+            }
+            if route.wait < 0.0 {
+                issues.push(LevelIssue::InvalidRoute { creature, reason: "has a negative wait" });
+            }
+        }
+
+        let door_exists = |name: &str| self.doors.iter().any(|d| d.name == name);
+        for (trigger, volume) in self.triggers.iter().enumerate() {
+            for action in &volume.actions {
+                match action {
+                    TriggerAction::LockArea { area } | TriggerAction::UnlockArea { area }
+                        if !self.areas.iter().any(|a| &a.name == area) =>
+                    {
+                        issues.push(LevelIssue::UnknownArea { trigger, area: area.clone() });
+                    }
+                    TriggerAction::OpenDoor { door } | TriggerAction::CloseDoor { door } if !door_exists(door) => {
+                        issues.push(LevelIssue::UnknownDoor { what: format!("trigger #{}", trigger), door: door.clone() });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for (index, lever) in self.levers.iter().enumerate() {
+            if !door_exists(&lever.door) {
+                issues.push(LevelIssue::UnknownDoor { what: format!("lever #{}", index), door: lever.door.clone() });
+            }
+        }
+
+        // Props sitting on a pit or its slippery edge
+        for spawn in &self.props {
+            for pit in &self.pits {
+                if spawn.position.distance(pit.position) < pit.edge_radius {
+                    issues.push(LevelIssue::PropOverlapsPit {
+                        prop: spawn.prop.clone(),
+                        position: spawn.position,
+                        pit_position: pit.position,
+                    });
+                }
+            }
+        }
+
+        // Win zone must share a connected walkable region with the player spawn
+        if let Some(win_zone) = &self.win_zone {
+            let zone_reachable = match &self.tiles {
+                Some(tiles) => self.sampled_path_exists(self.player_spawn, win_zone.position, tiles.tile_size / 2.0),
+                None => {
+                    let reachable = self.connected_shapes(self.player_spawn);
+                    self.walkable.iter().enumerate()
+                        .any(|(i, shape)| reachable[i] && shape.contains(win_zone.position))
+                }
+            };
+            if !zone_reachable && self.is_walkable(win_zone.position) {
+                issues.push(LevelIssue::WinZoneUnreachable { position: win_zone.position });
+            }
+        }
+
+        self.validate_objective(&self.objective(), &mut issues);
+
+        issues
+    }
+
+    fn validate_objective(&self, objective: &Objective, issues: &mut Vec<LevelIssue>) {
+        match objective {
+            Objective::ReachZone { time } => {
+                if self.win_zone.is_none() {
+                    issues.push(LevelIssue::ObjectiveNeedsWinZone);
+                }
+                if time.is_some_and(|t| t < 0.0) {
+                    issues.push(LevelIssue::InvalidObjective { reason: "has a negative win zone time" });
+                }
+            }
+            Objective::KillAll => {}
+            Objective::Survive { seconds } if *seconds <= 0.0 => {
+                issues.push(LevelIssue::InvalidObjective { reason: "must survive for a positive time" });
+            }
+            Objective::Survive { .. } => {}
+            Objective::Collect { count, .. } if *count == 0 => {
+                issues.push(LevelIssue::InvalidObjective { reason: "must collect at least one item" });
+            }
+            Objective::Collect { .. } => {}
+            Objective::DestroyProps { props } => {
+                if props.is_empty() {
+                    issues.push(LevelIssue::InvalidObjective { reason: "must name at least one prop to destroy" });
+                }
+                for name in props {
+                    if !self.props.iter().any(|p| p.name.as_ref() == Some(name)) {
+                        issues.push(LevelIssue::UnknownObjectiveTarget { what: "prop", name: name.clone() });
+                    }
+                }
+            }
+            Objective::DefeatCreature { name } => {
+                if !self.creatures.iter().any(|c| c.name.as_ref() == Some(name)) {
+                    issues.push(LevelIssue::UnknownObjectiveTarget { what: "creature", name: name.clone() });
+                }
+            }
+            Objective::All(objectives) | Objective::Any(objectives) => {
+                if objectives.is_empty() {
+                    issues.push(LevelIssue::InvalidObjective { reason: "has an empty All/Any group" });
+                }
+                for objective in objectives {
+                    self.validate_objective(objective, issues);
+                }
+            }
+        }
+    }
+
+    /// The level's objective, or the default one when the file doesn't set it
+    pub fn objective(&self) -> Cow<'_, Objective> {
+        match &self.objective {
+            Some(objective) => Cow::Borrowed(objective),
+            None => Cow::Owned(Objective::default()),
+        }
+    }
+
+    /// Flood fill over a regular sample grid covering the level bounds. Used when tiles can
+    /// carve blocking terrain out of the walkable shapes, which the shape graph cannot see.
+    /// Locked areas and closed doors are ignored since the level may open them.
+    fn sampled_path_exists(&self, from: Vec2, to: Vec2, step: f32) -> bool {
+        let min = self.bounds.min;
+        let cols = (self.bounds.width() / step).ceil() as i32 + 1;
+        let rows = (self.bounds.height() / step).ceil() as i32 + 1;
+        let to_cell = |p: Vec2| (((p.x - min.x) / step).round() as i32, ((p.y - min.y) / step).round() as i32);
+        let to_point = |(c, r): (i32, i32)| min + Vec2::new(c as f32, r as f32) * step;
+        let in_grid = |(c, r): (i32, i32)| c >= 0 && r >= 0 && c < cols && r < rows;
+
+        let start = to_cell(from);
+        let goal = to_cell(to);
+        if !in_grid(start) || !in_grid(goal) {
+            return false;
+        }
+
+        let mut visited = vec![false; (cols * rows) as usize];
+        let mut stack = vec![start];
+        visited[(start.1 * cols + start.0) as usize] = true;
+
+        while let Some((c, r)) = stack.pop() {
+            if (c, r) == goal {
+                return true;
+            }
+            for next in [(c + 1, r), (c - 1, r), (c, r + 1), (c, r - 1)] {
+                if !in_grid(next) {
+                    continue;
+                }
+                let index = (next.1 * cols + next.0) as usize;
+                if !visited[index] && (next == goal || self.is_walkable_ignoring_barriers(to_point(next))) {
+                    visited[index] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Flood fill over touching walkable shapes, starting from those containing `start`
+    fn connected_shapes(&self, start: Vec2) -> Vec<bool> {
+        let mut visited: Vec<bool> = self.walkable.iter().map(|shape| shape.contains(start)).collect();
+        let mut stack: Vec<usize> = (0..self.walkable.len()).filter(|&i| visited[i]).collect();
+
+        while let Some(current) = stack.pop() {
+            for (i, shape) in self.walkable.iter().enumerate() {
+                if !visited[i] && shape.touches(&self.walkable[current]) {
+                    visited[i] = true;
+                    stack.push(i);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Locked areas and closed doors, which block movement until the level opens them
+    pub fn barriers(&self) -> impl Iterator<Item = WalkableShape> + '_ {
+        let areas = self.areas.iter().filter(|a| a.locked).map(|a| a.area.clone());
+        let doors = self.doors.iter().filter(|d| !d.open).map(|d| d.footprint());
+        areas.chain(doors)
     }
 
+    /// Barriers block movement; otherwise see `is_walkable_ignoring_barriers`
     pub fn is_walkable(&self, point: Vec2) -> bool {
-        self.walkable.iter().any(|rect| rect.contains(point))
+        !self.barriers().any(|barrier| barrier.contains(point)) && self.is_walkable_ignoring_barriers(point)
+    }
+
+    /// Tiles decide walkability inside the tile grid; elsewhere the walkable shapes do
+    fn is_walkable_ignoring_barriers(&self, point: Vec2) -> bool {
+        if let Some(terrain) = self.tiles.as_ref().and_then(|tiles| tiles.terrain_at(point)) {
+            return terrain.walkable;
+        }
+        self.walkable.iter().any(|shape| shape.contains(point))
     }
 
     pub fn clamp_to_walkable(&self, point: Vec2) -> Vec2 {
@@ -147,30 +1261,106 @@ impl LevelData {
             return point;
         }
 
-        let mut best_point = point;
-        let mut best_dist = f32::MAX;
-
-        for rect in &self.walkable {
-            let clamped = rect.clamp(point);
-            let dist = (clamped - point).length_squared();
-            if dist < best_dist {
-                best_dist = dist;
-                best_point = clamped;
+        let mut candidates: Vec<Vec2> = self.walkable.iter().map(|shape| shape.clamp(point)).collect();
+        if let Some(tiles) = &self.tiles {
+            // Escape points may sit outside every shape, so also try pulling them back in
+            for escape in tiles.escape_candidates(point) {
+                candidates.push(escape);
+                candidates.extend(self.walkable.iter().map(|shape| shape.clamp(escape)));
             }
         }
 
-        best_point
+        // Step out of any barrier holding the point or one of the candidates
+        let barriers: Vec<WalkableShape> = self.barriers().collect();
+        let escapes: Vec<Vec2> = std::iter::once(point)
+            .chain(candidates.iter().copied())
+            .flat_map(|p| {
+                barriers
+                    .iter()
+                    .filter(move |barrier| barrier.contains(p))
+                    .map(move |barrier| barrier.closest_outside(p))
+            })
+            .collect();
+        candidates.extend(escapes);
+
+        // A shape point may land on a blocking tile, so only keep truly walkable candidates
+        candidates
+            .into_iter()
+            .filter(|candidate| self.is_walkable(*candidate))
+            .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+            .unwrap_or(point)
     }
 }
 
 #[derive(Resource, Default)]
 pub struct CurrentLevel {
+    pub path: Option<String>,
     pub data: Option<LevelData>,
+    pub error: Option<String>,
 }
 
 impl CurrentLevel {
-    pub fn load(&mut self, path: &str) {
-        self.data = Some(LevelData::load_from_file(path));
+    /// Load and validate a level. On failure the error message is kept for display.
+    pub fn load(&mut self, path: &str) -> Result<(), LevelLoadError> {
+        self.path = Some(path.to_string());
+        self.data = None;
+        self.error = None;
+
+        match LevelData::load_validated(path) {
+            Ok(data) => {
+                self.data = Some(data);
+                Ok(())
+            }
+            Err(error) => {
+                self.error = Some(error.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    /// Generate and validate a level. Generated levels have no file to hot-reload.
+    pub fn generate(&mut self, settings: &GeneratorSettings) -> Result<(), LevelLoadError> {
+        self.path = None;
+        self.data = None;
+        self.error = None;
+
+        let data = generate_level(settings);
+        info!("Generated level \"{}\" from seed {}", data.name, settings.seed);
+        let issues = data.validate();
+        if issues.is_empty() {
+            self.data = Some(data);
+            Ok(())
+        } else {
+            let error = LevelLoadError::Invalid { path: format!("generated from seed {}", settings.seed), issues };
+            self.error = Some(error.to_string());
+            Err(error)
+        }
+    }
+
+    /// Load or generate the campaign's current level
+    pub fn load_campaign_level(&mut self, campaign: &Campaign) -> Result<(), LevelLoadError> {
+        match campaign.current_level() {
+            Some(CampaignLevel::File(path)) => self.load(path),
+            Some(CampaignLevel::Generated(settings)) => self.generate(settings),
+            None => {
+                self.path = None;
+                self.data = None;
+                let reason = campaign
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| format!("the campaign has no level {}", campaign.current + 1));
+                let error = LevelLoadError::NoCampaignLevel { reason };
+                self.error = Some(error.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    /// Re-read the current level file. On failure the previously loaded data is kept.
+    pub fn reload(&mut self) -> Result<(), LevelLoadError> {
+        let Some(path) = self.path.as_deref() else { return Ok(()) };
+        self.data = Some(LevelData::load_validated(path)?);
+        Ok(())
     }
 
     pub fn bounds(&self) -> Option<&LevelBounds> {
@@ -195,4 +1385,161 @@ impl CurrentLevel {
     pub fn pits(&self) -> &[PitData] {
         self.data.as_ref().map(|d| d.pits.as_slice()).unwrap_or(&[])
     }
+
+    pub fn hazards(&self) -> &[HazardData] {
+        self.data.as_ref().map(|d| d.hazards.as_slice()).unwrap_or(&[])
+    }
+
+    /// Shapes creatures steer around: pit edges and the hazards active at `time`
+    pub fn hazard_areas(&self, time: f32) -> Vec<WalkableShape> {
+        let pits = self.pits().iter().map(|p| WalkableShape::Circle { center: p.position, radius: p.edge_radius });
+        let hazards = self.hazards().iter().filter(|h| h.timing.is_active(time)).map(|h| h.area.clone());
+        pits.chain(hazards).collect()
+    }
+
+    pub fn waves(&self) -> &[WaveData] {
+        self.data.as_ref().map(|d| d.waves.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn triggers(&self) -> &[TriggerVolumeData] {
+        self.data.as_ref().map(|d| d.triggers.as_slice()).unwrap_or(&[])
+    }
+
+    /// Shapes of locked areas and closed doors
+    pub fn barriers(&self) -> Vec<WalkableShape> {
+        self.data.as_ref().map(|d| d.barriers().collect()).unwrap_or_default()
+    }
+
+    pub fn doors(&self) -> &[DoorData] {
+        self.data.as_ref().map(|d| d.doors.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns false if the door is missing or already in that state
+    pub fn set_door_open(&mut self, name: &str, open: bool) -> bool {
+        let Some(data) = self.data.as_mut() else { return false };
+        let Some(door) = data.doors.iter_mut().find(|d| d.name == name) else { return false };
+        let changed = door.open != open;
+        door.open = open;
+        changed
+    }
+
+    pub fn set_area_locked(&mut self, name: &str, locked: bool) {
+        let Some(data) = self.data.as_mut() else { return };
+        for area in data.areas.iter_mut().filter(|a| a.name == name) {
+            area.locked = locked;
+        }
+    }
+}
+
+/// A campaign entry: a level file, or a level generated from a seed
+#[derive(Clone, Debug, Deserialize)]
+pub enum CampaignLevel {
+    File(String),
+    Generated(GeneratorSettings),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CampaignData {
+    pub levels: Vec<CampaignLevel>,
+}
+
+impl CampaignData {
+    pub fn load_from_file(path: &str) -> Result<Self, CampaignLoadError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| CampaignLoadError::Io { path: path.to_string(), error })?;
+        ron::from_str(&contents).map_err(|error| CampaignLoadError::Parse { path: path.to_string(), error })
+    }
+}
+
+/// Ordered list of levels and the index of the level being played
+#[derive(Resource)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+    pub current: usize,
+    /// Why the campaign file could not be loaded; the campaign has no levels then
+    pub error: Option<String>,
+}
+
+impl Campaign {
+    pub fn load_from_file(path: &str) -> Result<Self, CampaignLoadError> {
+        let data = CampaignData::load_from_file(path)?;
+        if data.levels.is_empty() {
+            return Err(CampaignLoadError::Empty { path: path.to_string() });
+        }
+        Ok(Self {
+            levels: data.levels,
+            current: 0,
+            error: None,
+        })
+    }
+
+    /// A campaign without levels, shown as a level error when a game starts
+    pub fn failed(error: &CampaignLoadError) -> Self {
+        Self {
+            levels: Vec::new(),
+            current: 0,
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn current_level(&self) -> Option<&CampaignLevel> {
+        self.levels.get(self.current)
+    }
+
+    pub fn is_last_level(&self) -> bool {
+        self.current + 1 >= self.levels.len()
+    }
+
+    pub fn advance(&mut self) {
+        if !self.is_last_level() {
+            self.current += 1;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.current = 0;
+    }
+}
+
+/// Player state carried from a completed level into the next one
+#[derive(Resource, Default)]
+pub struct LevelCarryOver {
+    pub health: Option<Health>,
+    pub inventory: Option<Inventory>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walkable_accepts_plain_and_tagged_shapes() {
+        // Walkable list of the original corridor.ron, written before shapes were tagged
+        let plain = "[
+            (
+                min: (-130.0, -90.0),
+                max: (130.0, 800.0),
+            ),
+            (
+                min: (-200.0, 800.0),
+                max: (200.0, 1100.0),
+            ),
+        ]";
+        let shapes: Vec<WalkableShape> = ron::from_str(plain).unwrap();
+        assert_eq!(shapes.len(), 2);
+        assert!(matches!(
+            shapes[1],
+            WalkableShape::Rect { min, max } if min == Vec2::new(-200.0, 800.0) && max == Vec2::new(200.0, 1100.0)
+        ));
+
+        let tagged = "[
+            Rect(min: (0.0, 0.0), max: (10.0, 10.0)),
+            Polygon(points: [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)]),
+            Circle(center: (5.0, 5.0), radius: 3.0),
+        ]";
+        let shapes: Vec<WalkableShape> = ron::from_str(tagged).unwrap();
+        assert!(matches!(shapes[0], WalkableShape::Rect { .. }));
+        assert!(matches!(&shapes[1], WalkableShape::Polygon { points } if points.len() == 3));
+        assert!(matches!(shapes[2], WalkableShape::Circle { radius, .. } if radius == 3.0));
+    }
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+/// Emitted when the level file changed on disk and was reloaded into `CurrentLevel`
+#[derive(Event, Message, Debug)]
+pub struct LevelReloaded;
+
+/// Emitted when a trigger volume fires; `trigger` indexes `LevelData::triggers`.
+/// Each plugin reads the actions it cares about from the level data.
+#[derive(Event, Message, Debug)]
+pub struct TriggerFired {
+    pub trigger: usize,
+}
+
+/// Request to open or close a named door, from keys, levers or level triggers
+#[derive(Event, Message, Debug)]
+pub struct SetDoorState {
+    pub door: String,
+    pub open: bool,
+}
+
+/// Emitted when the player activates a checkpoint; its snapshot is in `CheckpointState`
+#[derive(Event, Message, Debug)]
+pub struct CheckpointReached;
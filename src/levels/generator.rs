@@ -0,0 +1,316 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use crate::inventory::ItemId;
+use super::data::{CheckpointData, CreatureSpawn, ItemSpawn, LevelBounds, PitData, PropSpawn, WinZoneData};
+use super::{CreatureType, LevelData, PropType, WalkableShape};
+
+/// Corridors reach this far into the rooms they join so the shapes touch
+const CORRIDOR_OVERLAP: f32 = 1.0;
+/// Empty space kept between unrelated rooms and corridors
+const ROOM_MARGIN: f32 = 40.0;
+const PLACEMENT_ATTEMPTS: u32 = 12;
+const BOUNDS_MARGIN: f32 = 10.0;
+/// Keep spawns this far from room walls
+const WALL_INSET: f32 = 20.0;
+const MIN_SPAWN_SPACING: f32 = 32.0;
+const SPAWN_CLEARANCE: f32 = 60.0;
+const WIN_ZONE_RADIUS: f32 = 30.0;
+const CHECKPOINT_RADIUS: f32 = 20.0;
+const PIT_EDGE_WIDTH: f32 = 10.0;
+/// Density values count spawns per this much room floor (100 x 100 units)
+const DENSITY_AREA: f32 = 10_000.0;
+
+const PROP_TYPES: [PropType; 4] = [PropType::Pillar, PropType::Barrel, PropType::Crate, PropType::Crate2];
+const ITEM_TYPES: [ItemId; 4] = [ItemId::Mushroom, ItemId::Mushroom, ItemId::HealthPotion, ItemId::RustyKnife];
+
+/// Settings for a generated level. The same settings always produce the same level.
+#[derive(Clone, Debug, Deserialize)]
+pub struct GeneratorSettings {
+    pub seed: u64,
+    #[serde(default = "default_rooms")]
+    pub rooms: u32,
+    #[serde(default = "default_room_min")]
+    pub room_min: Vec2,
+    #[serde(default = "default_room_max")]
+    pub room_max: Vec2,
+    #[serde(default = "default_corridor_width")]
+    pub corridor_width: f32,
+    /// Shortest and longest corridor between two rooms
+    #[serde(default = "default_corridor_length")]
+    pub corridor_length: (f32, f32),
+    /// Creatures per 100 x 100 units of room floor. The first room never has any.
+    #[serde(default = "default_creature_density")]
+    pub creature_density: f32,
+    #[serde(default = "default_prop_density")]
+    pub prop_density: f32,
+    #[serde(default = "default_item_density")]
+    pub item_density: f32,
+    /// Chance for each room between the first and the last to get a pit
+    #[serde(default = "default_pit_chance")]
+    pub pit_chance: f32,
+}
+
+fn default_rooms() -> u32 {
+    6
+}
+
+fn default_room_min() -> Vec2 {
+    Vec2::new(140.0, 120.0)
+}
+
+fn default_room_max() -> Vec2 {
+    Vec2::new(280.0, 220.0)
+}
+
+fn default_corridor_width() -> f32 {
+    60.0
+}
+
+fn default_corridor_length() -> (f32, f32) {
+    (60.0, 160.0)
+}
+
+fn default_creature_density() -> f32 {
+    0.6
+}
+
+fn default_prop_density() -> f32 {
+    0.5
+}
+
+fn default_item_density() -> f32 {
+    0.15
+}
+
+fn default_pit_chance() -> f32 {
+    0.35
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            rooms: default_rooms(),
+            room_min: default_room_min(),
+            room_max: default_room_max(),
+            corridor_width: default_corridor_width(),
+            corridor_length: default_corridor_length(),
+            creature_density: default_creature_density(),
+            prop_density: default_prop_density(),
+            item_density: default_item_density(),
+            pit_chance: default_pit_chance(),
+        }
+    }
+}
+
+/// Room spawns are kept clear of its doorways
+struct Room {
+    rect: Rect,
+    entrances: Vec<Vec2>,
+}
+
+/// Build a chain of rooms joined by straight corridors, heading away from the first room.
+/// The player starts in the first room and the win zone sits in the last one.
+pub fn generate_level(settings: &GeneratorSettings) -> LevelData {
+    // ChaCha8 keeps a seed's layout the same across platforms and rand releases
+    let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+
+    // Rooms must fit a corridor plus the wall inset on both sides
+    let corridor_width = settings.corridor_width.max(1.0);
+    let room_min = settings.room_min.max(Vec2::splat(corridor_width + WALL_INSET * 2.0));
+    let room_max = settings.room_max.max(room_min);
+    let (length_min, length_max) = settings.corridor_length;
+    let length_min = length_min.max(0.0);
+    let length_max = length_max.max(length_min);
+
+    let random_size = |rng: &mut ChaCha8Rng| {
+        Vec2::new(
+            rng.random_range(room_min.x..=room_max.x),
+            rng.random_range(room_min.y..=room_max.y),
+        )
+    };
+
+    let mut rooms = vec![Room {
+        rect: Rect::from_center_size(Vec2::ZERO, random_size(&mut rng)),
+        entrances: Vec::new(),
+    }];
+    let mut corridors: Vec<Rect> = Vec::new();
+
+    while rooms.len() < settings.rooms.max(1) as usize {
+        let current = rooms.len() - 1;
+        let from = rooms[current].rect;
+        let mut placed = None;
+
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let size = random_size(&mut rng);
+            let length = rng.random_range(length_min..=length_max);
+            let half = corridor_width / 2.0;
+            // Up is twice as likely as either side so levels keep moving forward
+            let direction = match rng.random_range(0..4) {
+                0 | 1 => Vec2::Y,
+                2 => Vec2::NEG_X,
+                _ => Vec2::X,
+            };
+
+            let (corridor, room, entrance, exit) = if direction == Vec2::Y {
+                let x = rng.random_range(from.min.x + half..=from.max.x - half);
+                let corridor = Rect::new(x - half, from.max.y - CORRIDOR_OVERLAP, x + half, from.max.y + length + CORRIDOR_OVERLAP);
+                let slack = size.x / 2.0 - half - WALL_INSET;
+                let center = Vec2::new(x + rng.random_range(-slack..=slack), from.max.y + length + size.y / 2.0);
+                let room = Rect::from_center_size(center, size);
+                (corridor, room, Vec2::new(x, from.max.y), Vec2::new(x, room.min.y))
+            } else {
+                let y = rng.random_range(from.min.y + half..=from.max.y - half);
+                let edge = if direction.x > 0.0 { from.max.x } else { from.min.x };
+                let far = edge + direction.x * length;
+                let corridor = Rect::from_corners(
+                    Vec2::new(edge - direction.x * CORRIDOR_OVERLAP, y - half),
+                    Vec2::new(far + direction.x * CORRIDOR_OVERLAP, y + half),
+                );
+                let slack = size.y / 2.0 - half - WALL_INSET;
+                let center = Vec2::new(far + direction.x * size.x / 2.0, y + rng.random_range(-slack..=slack));
+                (corridor, Rect::from_center_size(center, size), Vec2::new(edge, y), Vec2::new(far, y))
+            };
+
+            let overlaps = |other: &Rect| !room.inflate(ROOM_MARGIN).intersect(*other).is_empty();
+            let corridor_blocked = rooms
+                .iter()
+                .enumerate()
+                .any(|(i, r)| i != current && !corridor.inflate(ROOM_MARGIN / 2.0).intersect(r.rect).is_empty());
+            if rooms.iter().any(|r| overlaps(&r.rect)) || corridors.iter().any(overlaps) || corridor_blocked {
+                continue;
+            }
+
+            placed = Some((corridor, room, entrance, exit));
+            break;
+        }
+
+        // Boxed in: end the level at the current room
+        let Some((corridor, room, entrance, exit)) = placed else { break };
+        rooms[current].entrances.push(entrance);
+        rooms.push(Room { rect: room, entrances: vec![exit] });
+        corridors.push(corridor);
+    }
+
+    let player_spawn = rooms[0].rect.center();
+    let win_zone_position = rooms[rooms.len() - 1].rect.center();
+    let last = rooms.len() - 1;
+    // One checkpoint halfway along the run, once there are rooms between the start and the exit
+    let checkpoint_room = (last >= 2).then_some(last / 2);
+    let checkpoints: Vec<CheckpointData> = checkpoint_room
+        .map(|index| CheckpointData { position: rooms[index].rect.center(), radius: CHECKPOINT_RADIUS })
+        .into_iter()
+        .collect();
+
+    let mut pits = Vec::new();
+    let mut props = Vec::new();
+    let mut items = Vec::new();
+    let mut creatures = Vec::new();
+
+    for (index, room) in rooms.iter().enumerate() {
+        let units = room.rect.width() * room.rect.height() / DENSITY_AREA;
+        let inner = room.rect.inflate(-WALL_INSET);
+
+        // Spawns keep clear of each other, the doorways, pits, the player, the checkpoint and the win zone
+        let mut occupied: Vec<(Vec2, f32)> = room.entrances.iter().map(|e| (*e, corridor_width)).collect();
+        if index == 0 {
+            occupied.push((player_spawn, SPAWN_CLEARANCE));
+        }
+        if checkpoint_room == Some(index) {
+            occupied.push((room.rect.center(), CHECKPOINT_RADIUS + MIN_SPAWN_SPACING));
+        }
+        if index == last {
+            occupied.push((win_zone_position, WIN_ZONE_RADIUS + MIN_SPAWN_SPACING));
+        }
+
+        if index != 0 && index != last && rng.random_bool(settings.pit_chance.clamp(0.0, 1.0) as f64) {
+            let radius = rng.random_range(14.0..=22.0);
+            let edge_radius = radius + PIT_EDGE_WIDTH;
+            let jitter = (inner.half_size() - Vec2::splat(edge_radius)).max(Vec2::ZERO) * 0.5;
+            let position = room.rect.center()
+                + Vec2::new(rng.random_range(-jitter.x..=jitter.x), rng.random_range(-jitter.y..=jitter.y));
+            if occupied.iter().all(|(p, r)| p.distance(position) > r + edge_radius) {
+                occupied.push((position, edge_radius + MIN_SPAWN_SPACING));
+                pits.push(PitData { position, radius, edge_radius });
+            }
+        }
+
+        for _ in 0..density_count(&mut rng, units * settings.prop_density) {
+            if let Some(position) = pick_spot(&mut rng, inner, &mut occupied) {
+                let prop = PROP_TYPES[rng.random_range(0..PROP_TYPES.len())].clone();
+                props.push(PropSpawn { prop, position, name: None });
+            }
+        }
+
+        for _ in 0..density_count(&mut rng, units * settings.item_density) {
+            if let Some(position) = pick_spot(&mut rng, inner, &mut occupied) {
+                let item_id = ITEM_TYPES[rng.random_range(0..ITEM_TYPES.len())];
+                let quantity = if item_id == ItemId::Mushroom { rng.random_range(1..=3) } else { 1 };
+                items.push(ItemSpawn { item_id, quantity, position });
+            }
+        }
+
+        if index == 0 {
+            continue;
+        }
+        for _ in 0..density_count(&mut rng, units * settings.creature_density) {
+            if let Some(position) = pick_spot(&mut rng, inner, &mut occupied) {
+                creatures.push(CreatureSpawn { creature: CreatureType::Goblin, position, name: None, route: None });
+            }
+        }
+    }
+
+    let walkable: Vec<Rect> = rooms.iter().map(|r| r.rect).chain(corridors).collect();
+    let extent = walkable.iter().fold(walkable[0], |acc, r| acc.union(*r)).inflate(BOUNDS_MARGIN);
+
+    LevelData {
+        name: format!("Generated #{}", settings.seed),
+        bounds: LevelBounds { min: extent.min, max: extent.max },
+        walkable: walkable.iter().map(|r| WalkableShape::Rect { min: r.min, max: r.max }).collect(),
+        player_spawn,
+        items,
+        creatures,
+        props,
+        win_zone: Some(WinZoneData { position: win_zone_position, radius: WIN_ZONE_RADIUS }),
+        pits,
+        hazards: Vec::new(),
+        tiles: None,
+        waves: Vec::new(),
+        areas: Vec::new(),
+        triggers: Vec::new(),
+        doors: Vec::new(),
+        levers: Vec::new(),
+        objective: None,
+        checkpoints,
+        lives: None,
+    }
+}
+
+/// Round a fractional spawn count up or down at random, so low densities still spawn sometimes
+fn density_count(rng: &mut ChaCha8Rng, expected: f32) -> u32 {
+    let expected = expected.max(0.0);
+    let whole = expected.floor();
+    let extra = rng.random_bool((expected - whole) as f64);
+    whole as u32 + extra as u32
+}
+
+/// Random free spot inside `area`, recorded in `occupied` when found
+fn pick_spot(rng: &mut ChaCha8Rng, area: Rect, occupied: &mut Vec<(Vec2, f32)>) -> Option<Vec2> {
+    if area.is_empty() {
+        return None;
+    }
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let position = Vec2::new(
+            rng.random_range(area.min.x..=area.max.x),
+            rng.random_range(area.min.y..=area.max.y),
+        );
+        if occupied.iter().all(|(p, r)| p.distance(position) > *r) {
+            occupied.push((position, MIN_SPAWN_SPACING));
+            return Some(position);
+        }
+    }
+    None
+}
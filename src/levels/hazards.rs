@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+
+use crate::combat::PlayerDamaged;
+use crate::core::{Dead, DeathAnimation, GameConfig, Health, Knockback, WalkCollider};
+use crate::effects::{spawn_damage_number, HitHighlight};
+use crate::player::Player;
+use super::spawner::hazard_color;
+use super::{BoundToLevel, CurrentLevel, FallingIntoPit, HazardEffect};
+
+/// Opacity of a hazard's floor while it is switched off
+const INACTIVE_HAZARD_ALPHA: f32 = 0.15;
+
+/// Spawned hazard; `index` points into `LevelData::hazards`
+#[derive(Component)]
+pub struct Hazard {
+    pub index: usize,
+}
+
+/// Hazard bookkeeping for an entity that hazards affect
+#[derive(Component, Default)]
+pub struct HazardExposure {
+    /// Active hazards the entity stands in, with seconds until their next spike hit
+    pub inside: Vec<(usize, f32)>,
+    /// Damage over time not dealt yet, since health only drops in whole points
+    pub pending_damage: f32,
+    /// Position after last frame's movement, for slowing hazards
+    pub last_position: Option<Vec2>,
+}
+
+type HazardTarget = (With<BoundToLevel>, Without<Dead>, Without<FallingIntoPit>);
+type HazardVictim = (
+    Entity,
+    &'static Transform,
+    Option<&'static WalkCollider>,
+    &'static mut Health,
+    &'static mut HazardExposure,
+    Has<Knockback>,
+    Has<DeathAnimation>,
+    Has<Player>,
+);
+
+/// Deal hazard damage and knockback to the player and creatures standing in active hazards
+pub fn apply_hazard_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    current_level: Res<CurrentLevel>,
+    mut player_damaged: MessageWriter<PlayerDamaged>,
+    mut query: Query<HazardVictim, HazardTarget>,
+) {
+    let hazards = current_level.hazards();
+    let now = time.elapsed_secs();
+    let dt = time.delta_secs();
+
+    for (entity, transform, walk_collider, mut health, mut exposure, has_knockback, dying, is_player) in &mut query {
+        let position = transform.translation.truncate();
+        let feet_pos = position + Vec2::new(0.0, walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+
+        let damage_scale = if is_player { config.player_damage_taken_scale } else { 1.0 };
+        let mut inside = Vec::new();
+        let mut damage_over_time = false;
+        let mut spike_damage = 0;
+        let mut knockback = None;
+
+        for (index, hazard) in hazards.iter().enumerate() {
+            if !hazard.timing.is_active(now) || !hazard.area.contains(feet_pos) {
+                continue;
+            }
+            // Stepping in, or the hazard switching on underneath, counts as entering
+            let previous = exposure.inside.iter().find(|(i, _)| *i == index).map(|(_, timer)| *timer);
+            let entered = previous.is_none();
+            let mut spike_timer = previous.map_or(0.0, |timer| timer - dt);
+            let spike_due = spike_timer <= 0.0;
+
+            for effect in &hazard.effects {
+                match effect {
+                    HazardEffect::Damage { per_second } => {
+                        exposure.pending_damage += per_second * damage_scale * dt;
+                        damage_over_time = true;
+                    }
+                    HazardEffect::Spikes { damage, interval } if spike_due => {
+                        spike_damage += (*damage as f32 * damage_scale).round() as i32;
+                        spike_timer = spike_timer.max(0.0) + interval;
+                    }
+                    HazardEffect::Knockback { force } if entered => {
+                        let away = (feet_pos - hazard.area.bounding_rect().center()).normalize_or(Vec2::Y);
+                        knockback = Some(away * force);
+                    }
+                    _ => {}
+                }
+            }
+            inside.push((index, spike_timer));
+        }
+        exposure.inside = inside;
+        if !damage_over_time {
+            exposure.pending_damage = 0.0;
+        }
+
+        let whole_damage = exposure.pending_damage.floor();
+        exposure.pending_damage -= whole_damage;
+        let damage = whole_damage as i32 + spike_damage;
+
+        if let Some(velocity) = knockback.filter(|_| !has_knockback && !dying) {
+            commands.entity(entity).insert(Knockback { velocity, timer: 0.0 });
+        }
+        if damage <= 0 || dying {
+            continue;
+        }
+
+        health.0 -= damage;
+        spawn_damage_number(&mut commands, position, damage);
+        if is_player {
+            player_damaged.write(PlayerDamaged { amount: damage, blocked: false });
+        }
+        commands.entity(entity).insert(HitHighlight {
+            timer: 0.0,
+            duration: config.hit_highlight_duration,
+            original_material: None,
+        });
+        // Knockback starts the death animation itself once it ends
+        if health.0 <= 0 && !has_knockback && knockback.is_none() {
+            commands.entity(entity).insert(DeathAnimation { timer: 0.0, stage: 0 });
+        }
+    }
+}
+
+/// Scale this frame's movement of anything standing in an active speed hazard.
+/// Runs after all movement so it covers walking, dashing and knockback alike.
+pub fn apply_hazard_speed(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    mut query: Query<(&mut Transform, Option<&WalkCollider>, &mut HazardExposure), HazardTarget>,
+) {
+    let hazards = current_level.hazards();
+    let now = time.elapsed_secs();
+
+    for (mut transform, walk_collider, mut exposure) in &mut query {
+        let position = transform.translation.truncate();
+        let feet_pos = position + Vec2::new(0.0, walk_collider.map(|c| c.offset_y).unwrap_or(0.0));
+
+        let multiplier: f32 = hazards
+            .iter()
+            .filter(|hazard| hazard.timing.is_active(now) && hazard.area.contains(feet_pos))
+            .map(|hazard| hazard.speed_multiplier())
+            .product();
+
+        if let Some(last_position) = exposure.last_position {
+            if multiplier != 1.0 {
+                let scaled = last_position + (position - last_position) * multiplier;
+                transform.translation.x = scaled.x;
+                transform.translation.y = scaled.y;
+            }
+        }
+        exposure.last_position = Some(transform.translation.truncate());
+    }
+}
+
+/// Fade hazards out while they are switched off
+pub fn update_hazard_visuals(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    hazard_query: Query<(&Hazard, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    let now = time.elapsed_secs();
+    for (hazard, material) in &hazard_query {
+        let Some(data) = current_level.hazards().get(hazard.index) else { continue };
+        let mut color = hazard_color(data);
+        if !data.timing.is_active(now) {
+            color = color.with_alpha(INACTIVE_HAZARD_ALPHA);
+        }
+        if materials.get(&material.0).is_some_and(|m| m.color != color) {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.color = color;
+            }
+        }
+    }
+}
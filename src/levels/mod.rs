@@ -1,10 +1,20 @@
+mod checkpoints;
 mod data;
+mod events;
+mod generator;
+mod hazards;
+mod objectives;
 mod spawner;
 pub mod systems;
 
-pub use data::{CreatureType, CurrentLevel, LevelData, PitData, PropType};
-pub use spawner::{spawn_level_background, spawn_pit, spawn_win_zone, LevelBackground, Pit, VoidBackground, WinZone, WinZoneTimer, WinZoneTimerText};
-pub use systems::{BoundToLevel, FallingIntoPit, WaveSpawnState};
+pub use checkpoints::{CheckpointSnapshot, CheckpointState};
+pub use data::{Campaign, CheckpointData, CreatureSpawn, CreatureType, CurrentLevel, DoorData, DoorKind, HazardData, HazardEffect, ItemSpawn, LevelCarryOver, LevelData, LeverData, Objective, PatrolRouteData, PatrolRouteMode, PitData, PropSpawn, PropType, TileLayer, TriggerAction, TriggerCondition, TriggerFiring, WalkableShape, WaveData, WaveTrigger, WinZoneData};
+pub use events::{CheckpointReached, LevelReloaded, SetDoorState, TriggerFired};
+pub use generator::{generate_level, GeneratorSettings};
+pub use hazards::{Hazard, HazardExposure};
+pub use objectives::{check_objectives, ObjectiveState};
+pub use spawner::{spawn_checkpoint, spawn_door, spawn_hazard, spawn_level_background, spawn_lever, spawn_locked_areas, spawn_pit, spawn_tile_layer, spawn_win_zone, Door, DoorLeaf, Checkpoint, LevelBackground, LevelSpawnIndex, Lever, LeverHandle, LockedAreaVisual, Pit, VoidBackground, WinZone, WinZoneTimerText};
+pub use systems::{BoundToLevel, FallingIntoPit, LevelFileWatcher, LevelMusic, LevelProgress, TriggerVolumeState, WaveSpawnState};
 
 use bevy::prelude::*;
 
@@ -15,17 +25,47 @@ pub struct LevelsPlugin;
 impl Plugin for LevelsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CurrentLevel>()
+            .init_resource::<LevelCarryOver>()
+            .init_resource::<LevelFileWatcher>()
+            .init_resource::<TriggerVolumeState>()
+            .init_resource::<CheckpointState>()
+            .add_message::<LevelReloaded>()
+            .add_message::<CheckpointReached>()
+            .add_message::<TriggerFired>()
+            .add_message::<SetDoorState>()
             .add_systems(
                 Update,
                 (
+                    systems::watch_level_file,
                     systems::apply_pit_edge_resistance,
                     systems::check_pit_fall.after(systems::apply_pit_edge_resistance),
                     systems::animate_pit_fall.after(systems::check_pit_fall),
+                    systems::detect_trigger_volumes,
+                    (
+                        systems::spawn_trigger_creatures,
+                        systems::apply_trigger_area_locks,
+                        systems::play_trigger_music,
+                        systems::apply_trigger_doors,
+                    ).after(systems::detect_trigger_volumes),
+                    systems::use_keys_on_doors,
+                    systems::pull_levers,
+                    systems::apply_door_state
+                        .after(systems::apply_trigger_doors)
+                        .after(systems::use_keys_on_doors)
+                        .after(systems::pull_levers),
+                    checkpoints::activate_checkpoints,
+                    checkpoints::update_checkpoint_visuals.after(checkpoints::activate_checkpoints),
+                    checkpoints::respawn_after_death,
+                    hazards::apply_hazard_effects,
+                    hazards::update_hazard_visuals,
                 ).run_if(in_state(GameState::Playing)),
             )
             .add_systems(
                 PostUpdate,
-                systems::enforce_level_bounds.run_if(in_state(GameState::Playing)),
+                (
+                    hazards::apply_hazard_speed,
+                    systems::enforce_level_bounds.after(hazards::apply_hazard_speed),
+                ).run_if(in_state(GameState::Playing)),
             );
     }
 }
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::core::{Dead, GameConfig, GameState};
+use crate::creatures::{Creature, Hostile};
+use crate::inventory::{get_item_data, Inventory};
+use crate::player::Player;
+use crate::props::Prop;
+use super::{Campaign, CurrentLevel, Objective, WaveSpawnState, WinZone, WinZoneTimerText};
+
+/// One line of the objective HUD; nested objectives are indented by `depth`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectiveLine {
+    pub depth: usize,
+    pub text: String,
+    pub done: bool,
+}
+
+/// Progress toward the current level's objective. Reset when a level starts.
+#[derive(Resource, Default)]
+pub struct ObjectiveState {
+    /// Seconds since the level started
+    pub elapsed: f32,
+    /// Seconds the player has held the win zone
+    pub zone_time: f32,
+    /// Objective progress as of the last check, for the HUD
+    pub lines: Vec<ObjectiveLine>,
+}
+
+type LivingPlayer = (With<Player>, Without<Dead>);
+
+/// What the objective checks can see this frame
+struct ObjectiveContext<'a> {
+    hostiles_left: usize,
+    wave_spawning: bool,
+    elapsed: f32,
+    zone_time: f32,
+    /// Seconds to hold the win zone when the objective doesn't set a time
+    default_zone_time: f32,
+    /// Count every `ReachZone` as held, to find out whether the zone is all that's left
+    assume_zone_held: bool,
+    inventory: Option<&'a Inventory>,
+    /// Names of named props still standing
+    props_standing: Vec<&'a str>,
+    /// Names of named creatures still alive
+    creatures_alive: Vec<&'a str>,
+}
+
+/// Evaluate the level objective, finishing the level once it is met.
+/// The win zone timer only runs while holding the zone is the last step left.
+pub fn check_objectives(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    mut state: ResMut<ObjectiveState>,
+    wave_state: Res<WaveSpawnState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    hostiles_query: Query<(), (With<Hostile>, Without<Dead>)>,
+    player_query: Query<(&Transform, &Inventory), LivingPlayer>,
+    props_query: Query<&Name, With<Prop>>,
+    creatures_query: Query<&Name, (With<Creature>, Without<Dead>)>,
+    win_zone_query: Query<(&Transform, &WinZone)>,
+    mut timer_text_query: Query<&mut Text2d, With<WinZoneTimerText>>,
+) {
+    let Some(level) = current_level.data.as_ref() else { return };
+    let objective = level.objective();
+    let dt = time.delta_secs();
+    state.elapsed += dt;
+
+    let player = player_query.single().ok();
+    let in_zone = match (player, win_zone_query.single()) {
+        (Some((player_transform, _)), Ok((zone_transform, win_zone))) => {
+            let player_pos = player_transform.translation.truncate();
+            player_pos.distance(zone_transform.translation.truncate()) <= win_zone.radius
+        }
+        _ => false,
+    };
+
+    let mut context = ObjectiveContext {
+        hostiles_left: hostiles_query.iter().count(),
+        wave_spawning: wave_state.is_spawning(current_level.waves()),
+        elapsed: state.elapsed,
+        zone_time: state.zone_time,
+        default_zone_time: config.win_zone_time,
+        assume_zone_held: true,
+        inventory: player.map(|(_, inventory)| inventory),
+        props_standing: props_query.iter().map(|name| name.as_str()).collect(),
+        creatures_alive: creatures_query.iter().map(|name| name.as_str()).collect(),
+    };
+
+    let zone_time = objective.zone_time(config.win_zone_time);
+    let zone_is_last_step = evaluate(&objective, &context, 0, &mut Vec::new());
+    if zone_time.is_some() && in_zone && zone_is_last_step {
+        state.zone_time += dt;
+    } else {
+        state.zone_time = 0.0;
+    }
+
+    context.zone_time = state.zone_time;
+    context.assume_zone_held = false;
+    let mut lines = Vec::new();
+    let complete = evaluate(&objective, &context, 0, &mut lines);
+    state.lines = lines;
+
+    // Countdown inside the win zone
+    if let Ok(mut text) = timer_text_query.single_mut() {
+        **text = match zone_time {
+            Some(hold_time) if state.zone_time > 0.0 => {
+                let remaining = (hold_time - state.zone_time).ceil() as i32;
+                format!("{}", remaining.max(1))
+            }
+            _ => "".to_string(),
+        };
+    }
+
+    if complete && player.is_some() {
+        if campaign.is_last_level() {
+            next_state.set(GameState::Victory);
+        } else {
+            next_state.set(GameState::LevelComplete);
+        }
+    }
+}
+
+/// Check an objective and append its HUD lines. Groups check every child so all of them get a line.
+fn evaluate(objective: &Objective, context: &ObjectiveContext, depth: usize, lines: &mut Vec<ObjectiveLine>) -> bool {
+    let (text, done) = match objective {
+        Objective::ReachZone { time } => {
+            let hold_time = time.unwrap_or(context.default_zone_time);
+            let done = context.assume_zone_held || context.zone_time >= hold_time;
+            let text = if context.zone_time > 0.0 && !done {
+                format!("Hold the exit ({}s)", (hold_time - context.zone_time).ceil() as i32)
+            } else {
+                "Reach the exit".to_string()
+            };
+            (text, done)
+        }
+        Objective::KillAll => {
+            let done = context.hostiles_left == 0 && !context.wave_spawning;
+            let text = if context.wave_spawning {
+                "Defeat all enemies (more coming)".to_string()
+            } else if !done {
+                format!("Defeat all enemies ({} left)", context.hostiles_left)
+            } else {
+                "Defeat all enemies".to_string()
+            };
+            (text, done)
+        }
+        Objective::Survive { seconds } => {
+            let done = context.elapsed >= *seconds;
+            let text = if done {
+                format!("Survive {}s", seconds)
+            } else {
+                format!("Survive {}s ({}s left)", seconds, (seconds - context.elapsed).ceil() as i32)
+            };
+            (text, done)
+        }
+        Objective::Collect { item, count } => {
+            let held = context.inventory.map_or(0, |inventory| inventory.count(*item));
+            let text = format!("Collect {} ({}/{})", get_item_data(*item).name, held.min(*count), count);
+            (text, held >= *count)
+        }
+        Objective::DestroyProps { props } => {
+            let standing = props.iter().filter(|name| context.props_standing.contains(&name.as_str())).count();
+            let text = format!("Destroy the marked props ({}/{})", props.len() - standing, props.len());
+            (text, standing == 0)
+        }
+        Objective::DefeatCreature { name } => {
+            let done = !context.creatures_alive.contains(&name.as_str());
+            (format!("Defeat {}", name), done)
+        }
+        Objective::All(objectives) | Objective::Any(objectives) => {
+            let is_all = matches!(objective, Objective::All(_));
+            // A top-level All is just the list of objectives, without a header
+            let header = (!is_all || depth > 0).then(|| {
+                lines.push(ObjectiveLine {
+                    depth,
+                    text: if is_all { "All of:" } else { "One of:" }.to_string(),
+                    done: false,
+                });
+                lines.len() - 1
+            });
+            let child_depth = if header.is_some() { depth + 1 } else { depth };
+
+            let results: Vec<bool> = objectives.iter().map(|o| evaluate(o, context, child_depth, lines)).collect();
+            let done = if is_all { results.iter().all(|d| *d) } else { results.iter().any(|d| *d) };
+            if let Some(header) = header {
+                lines[header].done = done;
+            }
+            return done;
+        }
+    };
+
+    lines.push(ObjectiveLine { depth, text, done });
+    done
+}
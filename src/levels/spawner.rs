@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::asset::RenderAssetUsages;
 
-use super::LevelData;
+use crate::core::{StaticCollider, YSorted};
+use super::hazards::Hazard;
+use super::{CheckpointData, DoorData, DoorKind, HazardData, HazardEffect, LevelData, LeverData, TileLayer, WalkableShape};
 
 #[derive(Component)]
 pub struct LevelBackground;
@@ -24,13 +26,51 @@ pub struct Pit {
 #[derive(Component)]
 pub struct WinZoneTimerText;
 
-#[derive(Resource, Default)]
-pub struct WinZoneTimer(pub f32);
+/// `index` points into `LevelData::checkpoints`
+#[derive(Component)]
+pub struct Checkpoint {
+    pub index: usize,
+    pub radius: f32,
+}
+
+/// Index of the `LevelData` creature or prop entry an entity was spawned from
+#[derive(Component, Clone, Copy)]
+pub struct LevelSpawnIndex(pub usize);
+
+#[derive(Component)]
+pub struct Door {
+    pub name: String,
+    pub open: bool,
+}
+
+/// Moving part of a door, hidden while the door is open
+#[derive(Component)]
+pub struct DoorLeaf;
+
+#[derive(Component)]
+pub struct Lever {
+    pub door: String,
+    pub pulled: bool,
+}
+
+/// Pivot of a lever's handle, rotated to show whether it is pulled
+#[derive(Component)]
+pub struct LeverHandle;
+
+/// Barrier drawn over a lockable area, visible while the area is locked
+#[derive(Component)]
+pub struct LockedAreaVisual {
+    pub name: String,
+}
 
 const Z_VOID: f32 = -10.0;
 const Z_CORRIDOR: f32 = -9.0;
+const Z_TILES: f32 = -8.9;
+const Z_LOCKED_AREA: f32 = -8.8;
 const Z_PIT: f32 = -8.5;
+const Z_HAZARD: f32 = -8.7;
 const Z_WIN_ZONE: f32 = -8.5;
+const Z_CHECKPOINT: f32 = -8.5;
 
 /// Create a pentagram (5-pointed star) inside a circle, mirrored horizontally (point down)
 fn create_pentagram_in_circle_mesh(radius: f32, line_thickness: f32) -> Mesh {
@@ -99,6 +139,80 @@ fn create_pentagram_in_circle_mesh(radius: f32, line_thickness: f32) -> Mesh {
         .with_inserted_indices(Indices::U32(indices))
 }
 
+/// Triangulate a simple polygon (convex or concave) by ear clipping
+fn create_polygon_mesh(points: &[Vec2]) -> Mesh {
+    let positions: Vec<[f32; 3]> = points.iter().map(|p| [p.x, p.y, 0.0]).collect();
+
+    // Work in counter-clockwise order so convex corners have a positive cross product
+    let signed_area: f32 = (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum();
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    if signed_area < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut indices = Vec::new();
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let prev = points[remaining[(i + count - 1) % count]];
+            let curr = points[remaining[i]];
+            let next = points[remaining[(i + 1) % count]];
+            if (curr - prev).perp_dot(next - curr) <= 0.0 {
+                return false;
+            }
+            // No other vertex may sit inside the candidate ear
+            remaining.iter().all(|&j| {
+                let p = points[j];
+                p == prev || p == curr || p == next || !point_in_triangle(p, prev, curr, next)
+            })
+        });
+
+        // Degenerate input (self-intersecting or collinear): fall back to clipping the first vertex
+        let i = ear.unwrap_or(0);
+        indices.extend_from_slice(&[
+            remaining[(i + count - 1) % count] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % count] as u32,
+        ]);
+        remaining.remove(i);
+    }
+    if remaining.len() == 3 {
+        indices.extend(remaining.iter().map(|&i| i as u32));
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+    d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0
+}
+
+/// Mesh for a level shape and the translation to place it at
+fn shape_mesh(shape: &WalkableShape, meshes: &mut Assets<Mesh>) -> (Handle<Mesh>, Vec2) {
+    match shape {
+        WalkableShape::Rect { min, max } => (
+            meshes.add(Rectangle::new(max.x - min.x, max.y - min.y)),
+            (*min + *max) / 2.0,
+        ),
+        WalkableShape::Circle { center, radius } => (
+            meshes.add(Circle::new(*radius)),
+            *center,
+        ),
+        // Polygon vertices are already in world space
+        WalkableShape::Polygon { points } => (
+            meshes.add(create_polygon_mesh(points)),
+            Vec2::ZERO,
+        ),
+    }
+}
+
 pub fn spawn_level_background(
     commands: &mut Commands,
     level: &LevelData,
@@ -122,23 +236,81 @@ pub fn spawn_level_background(
     ));
 
     for walkable in &level.walkable {
-        let width = walkable.max.x - walkable.min.x;
-        let height = walkable.max.y - walkable.min.y;
-        let center_x = (walkable.min.x + walkable.max.x) / 2.0;
-        let center_y = (walkable.min.y + walkable.max.y) / 2.0;
-
-        let corridor_mesh = meshes.add(Rectangle::new(width, height));
+        let (corridor_mesh, center) = shape_mesh(walkable, meshes);
         let corridor_material = materials.add(corridor_color);
 
         commands.spawn((
             LevelBackground,
             Mesh2d(corridor_mesh),
             MeshMaterial2d(corridor_material),
-            Transform::from_xyz(center_x, center_y, Z_CORRIDOR),
+            Transform::from_xyz(center.x, center.y, Z_CORRIDOR),
+        ));
+    }
+}
+
+pub fn spawn_locked_areas(
+    commands: &mut Commands,
+    level: &LevelData,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let barrier_material = materials.add(Color::srgba(0.45, 0.3, 0.2, 0.85));
+
+    for area in &level.areas {
+        let (mesh, center) = shape_mesh(&area.area, meshes);
+        let visibility = if area.locked { Visibility::Inherited } else { Visibility::Hidden };
+
+        commands.spawn((
+            LevelBackground,
+            LockedAreaVisual { name: area.name.clone() },
+            Mesh2d(mesh),
+            MeshMaterial2d(barrier_material.clone()),
+            Transform::from_xyz(center.x, center.y, Z_LOCKED_AREA),
+            visibility,
         ));
     }
 }
 
+/// Spawn one autotiled sprite per non-empty cell whose terrain has a sheet.
+/// Terrains are layered in the order they are listed.
+pub fn spawn_tile_layer(
+    commands: &mut Commands,
+    tiles: &TileLayer,
+    asset_server: &AssetServer,
+    atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) {
+    for (layer, terrain) in tiles.terrains.iter().enumerate() {
+        let Some(sheet) = &terrain.sheet else { continue };
+        let texture: Handle<Image> = asset_server.load(sheet.clone());
+        let tile_px = UVec2::splat(terrain.sheet_tile_size.max(1));
+        let layout = atlas_layouts.add(TextureAtlasLayout::from_grid(tile_px, 4, 4, None, None));
+        let z = Z_TILES + layer as f32 * 0.01;
+
+        for row in 0..tiles.height() as i32 {
+            for col in 0..tiles.width() as i32 {
+                if tiles.id_at(col, row) != terrain.id {
+                    continue;
+                }
+                let center = tiles.cell_center(col, row);
+                let mut sprite = Sprite::from_atlas_image(
+                    texture.clone(),
+                    TextureAtlas {
+                        layout: layout.clone(),
+                        index: tiles.autotile_index(col, row),
+                    },
+                );
+                sprite.custom_size = Some(Vec2::splat(tiles.tile_size));
+
+                commands.spawn((
+                    LevelBackground,
+                    sprite,
+                    Transform::from_xyz(center.x, center.y, z),
+                ));
+            }
+        }
+    }
+}
+
 pub fn spawn_win_zone(
     commands: &mut Commands,
     position: Vec2,
@@ -167,6 +339,199 @@ pub fn spawn_win_zone(
     });
 }
 
+/// Floor color of an active hazard, from its first effect
+pub fn hazard_color(hazard: &HazardData) -> Color {
+    match hazard.effects.first() {
+        Some(HazardEffect::Damage { .. }) => Color::srgba(0.95, 0.45, 0.1, 0.55),
+        Some(HazardEffect::Spikes { .. }) => Color::srgba(0.6, 0.6, 0.65, 0.6),
+        Some(HazardEffect::Speed { multiplier }) if *multiplier < 1.0 => Color::srgba(0.4, 0.28, 0.15, 0.65),
+        Some(HazardEffect::Speed { .. }) => Color::srgba(0.3, 0.6, 0.9, 0.5),
+        Some(HazardEffect::Knockback { .. }) | None => Color::srgba(0.7, 0.3, 0.8, 0.5),
+    }
+}
+
+pub fn spawn_hazard(
+    commands: &mut Commands,
+    index: usize,
+    hazard: &HazardData,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let (mesh, center) = shape_mesh(&hazard.area, meshes);
+    let material = materials.add(hazard_color(hazard));
+
+    commands.spawn((
+        Hazard { index },
+        Mesh2d(mesh),
+        MeshMaterial2d(material),
+        Transform::from_xyz(center.x, center.y, Z_HAZARD),
+    ));
+}
+
+pub fn checkpoint_color(active: bool) -> Color {
+    if active {
+        Color::srgba(0.3, 0.95, 0.8, 0.8)
+    } else {
+        Color::srgba(0.5, 0.55, 0.6, 0.5)
+    }
+}
+
+pub fn spawn_checkpoint(
+    commands: &mut Commands,
+    index: usize,
+    checkpoint: &CheckpointData,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    // Ring, post and pennant share one material so activating the checkpoint recolors all of them
+    let ring_mesh = meshes.add(create_ring_mesh((checkpoint.radius - 2.0).max(0.0), checkpoint.radius, 24));
+    let post_mesh = meshes.add(Rectangle::new(2.0, 16.0));
+    let pennant_mesh = meshes.add(Triangle2d::new(Vec2::new(0.0, 3.0), Vec2::new(0.0, -3.0), Vec2::new(8.0, 0.0)));
+    let material = materials.add(checkpoint_color(false));
+
+    commands.spawn((
+        Checkpoint { index, radius: checkpoint.radius },
+        Mesh2d(ring_mesh),
+        MeshMaterial2d(material.clone()),
+        Transform::from_xyz(checkpoint.position.x, checkpoint.position.y, Z_CHECKPOINT),
+    )).with_children(|parent| {
+        parent.spawn((
+            Mesh2d(post_mesh),
+            MeshMaterial2d(material.clone()),
+            Transform::from_xyz(0.0, 8.0, 0.01),
+        ));
+        parent.spawn((
+            Mesh2d(pennant_mesh),
+            MeshMaterial2d(material),
+            Transform::from_xyz(1.0, 13.0, 0.01),
+        ));
+    });
+}
+
+pub fn door_collider(size: Vec2) -> StaticCollider {
+    StaticCollider {
+        radius_x: size.x / 2.0,
+        radius_y: size.y / 2.0,
+        offset_x: 0.0,
+        offset_y: 0.0,
+    }
+}
+
+pub fn spawn_door(
+    commands: &mut Commands,
+    door: &DoorData,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    // Posts stand at both ends of the door's long axis
+    let vertical = door.size.y > door.size.x;
+    let post_size = if vertical {
+        Vec2::new(door.size.x + 6.0, 6.0)
+    } else {
+        Vec2::new(6.0, door.size.y + 6.0)
+    };
+    let post_mesh = meshes.add(Rectangle::new(post_size.x, post_size.y));
+    let post_material = materials.add(Color::srgb(0.3, 0.3, 0.32));
+    let leaf_color = match door.kind {
+        DoorKind::Door => Color::srgb(0.45, 0.3, 0.18),
+        DoorKind::Gate => Color::srgb(0.35, 0.35, 0.4),
+    };
+    let leaf_mesh = meshes.add(Rectangle::new(door.size.x, door.size.y));
+    let leaf_material = materials.add(leaf_color);
+    let detail_material = materials.add(Color::srgba(0.0, 0.0, 0.0, 0.35));
+    let leaf_visibility = if door.open { Visibility::Hidden } else { Visibility::Inherited };
+
+    let mut entity = commands.spawn((
+        Door { name: door.name.clone(), open: door.open },
+        YSorted { base_offset: 0.0 },
+        Transform::from_xyz(door.position.x, door.position.y, 0.0),
+        Visibility::default(),
+    ));
+    if !door.open {
+        entity.insert(door_collider(door.size));
+    }
+
+    entity.with_children(|parent| {
+        for side in [-1.0, 1.0] {
+            let offset = if vertical {
+                Vec2::new(0.0, side * (door.size.y + post_size.y) / 2.0)
+            } else {
+                Vec2::new(side * (door.size.x + post_size.x) / 2.0, 0.0)
+            };
+            parent.spawn((
+                Mesh2d(post_mesh.clone()),
+                MeshMaterial2d(post_material.clone()),
+                Transform::from_xyz(offset.x, offset.y, 0.002),
+            ));
+        }
+
+        parent.spawn((
+            DoorLeaf,
+            Mesh2d(leaf_mesh),
+            MeshMaterial2d(leaf_material),
+            Transform::from_xyz(0.0, 0.0, 0.001),
+            leaf_visibility,
+        )).with_children(|leaf| {
+            // Planks for doors, bars for gates, running across the long axis
+            let (length, depth) = if vertical { (door.size.y, door.size.x) } else { (door.size.x, door.size.y) };
+            let (count, width, extra) = match door.kind {
+                DoorKind::Door => (((length / 12.0) as i32).max(1), 1.0, 0.0),
+                DoorKind::Gate => (((length / 8.0) as i32).max(1), 2.0, 2.0),
+            };
+            let detail_size = if vertical { Vec2::new(depth + extra, width) } else { Vec2::new(width, depth + extra) };
+            let detail_mesh = meshes.add(Rectangle::new(detail_size.x, detail_size.y));
+            let spacing = length / (count + 1) as f32;
+            for i in 1..=count {
+                let along = -length / 2.0 + spacing * i as f32;
+                let offset = if vertical { Vec2::new(0.0, along) } else { Vec2::new(along, 0.0) };
+                leaf.spawn((
+                    Mesh2d(detail_mesh.clone()),
+                    MeshMaterial2d(detail_material.clone()),
+                    Transform::from_xyz(offset.x, offset.y, 0.001),
+                ));
+            }
+        });
+    });
+}
+
+pub fn lever_handle_rotation(pulled: bool) -> Quat {
+    Quat::from_rotation_z(if pulled { -0.6 } else { 0.6 })
+}
+
+pub fn spawn_lever(
+    commands: &mut Commands,
+    lever: &LeverData,
+    pulled: bool,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let base_mesh = meshes.add(Rectangle::new(10.0, 5.0));
+    let base_material = materials.add(Color::srgb(0.3, 0.3, 0.32));
+    let handle_mesh = meshes.add(Rectangle::new(2.0, 12.0));
+    let handle_material = materials.add(Color::srgb(0.55, 0.4, 0.2));
+
+    commands.spawn((
+        Lever { door: lever.door.clone(), pulled },
+        YSorted { base_offset: -2.5 },
+        Mesh2d(base_mesh),
+        MeshMaterial2d(base_material),
+        Transform::from_xyz(lever.position.x, lever.position.y, 0.0),
+    )).with_children(|parent| {
+        // Handle pivots at its bottom end, on the base
+        parent.spawn((
+            LeverHandle,
+            Transform::from_xyz(0.0, 2.0, -0.001).with_rotation(lever_handle_rotation(pulled)),
+            Visibility::default(),
+        )).with_children(|pivot| {
+            pivot.spawn((
+                Mesh2d(handle_mesh),
+                MeshMaterial2d(handle_material),
+                Transform::from_xyz(0.0, 6.0, 0.0),
+            ));
+        });
+    });
+}
+
 fn create_pit_mesh(radius: f32, segments: u32) -> Mesh {
     use std::f32::consts::PI;
 
@@ -1,28 +1,96 @@
-use bevy::prelude::*;
+use std::time::SystemTime;
 
-use crate::core::{CharacterAssets, Dead, DeathAnimation, GameConfig, GameState, Knockback, WalkCollider};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{CharacterAssets, Dead, DeathAnimation, GameAction, GameConfig, Health, InputBindings, Knockback, StaticCollider, WalkCollider};
 use crate::creatures::Hostile;
+use crate::inventory::{Inventory, SelectedHotbarSlot};
 use crate::player::{Player, PlayerSpriteSheet};
-use super::{CurrentLevel, Pit, WinZone, WinZoneTimer, WinZoneTimerText};
-
-const WIN_ZONE_TIME: f32 = 5.0;
-const WAVE_TRIGGER_TIME: f32 = 2.0;
-const WAVE_SPAWN_INTERVAL: f32 = 3.0;
-const WAVE_GOBLINS_PER_SIDE: u32 = 4;
-
-#[derive(Resource, Default)]
-pub struct WaveSpawnState {
+use super::spawner::{door_collider, lever_handle_rotation};
+use super::{
+    Campaign, CheckpointState, CreatureType, CurrentLevel, ObjectiveState, Door, DoorLeaf, LevelCarryOver, LevelReloaded, Lever, LeverHandle,
+    LockedAreaVisual, Pit, SetDoorState, TriggerAction, TriggerCondition, TriggerFired, TriggerFiring, WaveData,
+    WaveTrigger,
+};
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct WaveProgress {
     pub triggered: bool,
     pub spawn_timer: f32,
-    pub spawned_left: u32,
-    pub spawned_right: u32,
+    pub spawned: u32,
+}
+
+/// Progress of each wave in `LevelData::waves`, by index
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
+pub struct WaveSpawnState {
+    pub elapsed: f32,
+    pub waves: Vec<WaveProgress>,
+}
+
+impl WaveSpawnState {
+    /// True while a started wave still has creatures left to spawn
+    pub fn is_spawning(&self, waves: &[WaveData]) -> bool {
+        self.waves
+            .iter()
+            .zip(waves)
+            .any(|(progress, wave)| progress.triggered && progress.spawned < wave.total_count())
+    }
+}
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct TriggerProgress {
+    pub inside: bool,
+    pub stay_timer: f32,
+    pub fired: bool,
+}
+
+/// Progress of each trigger volume in `LevelData::triggers`, by index
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
+pub struct TriggerVolumeState {
+    pub triggers: Vec<TriggerProgress>,
+}
+
+/// Campaign position plus the objective, wave, trigger and checkpoint state of the current level
+#[derive(SystemParam)]
+pub struct LevelProgress<'w> {
+    pub campaign: ResMut<'w, Campaign>,
+    pub current_level: ResMut<'w, CurrentLevel>,
+    pub carry_over: ResMut<'w, LevelCarryOver>,
+    pub objective: ResMut<'w, ObjectiveState>,
+    pub waves: ResMut<'w, WaveSpawnState>,
+    pub triggers: ResMut<'w, TriggerVolumeState>,
+    pub checkpoint: ResMut<'w, CheckpointState>,
+}
+
+impl LevelProgress<'_> {
+    /// Forget the objective, wave, trigger and checkpoint progress of the current level
+    pub fn reset_level(&mut self) {
+        *self.objective = ObjectiveState::default();
+        *self.waves = WaveSpawnState::default();
+        *self.triggers = TriggerVolumeState::default();
+        *self.checkpoint = CheckpointState::default();
+    }
 }
-const PIT_EDGE_RESISTANCE: f32 = 80.0;
+
+/// Background music started by a level trigger
+#[derive(Component)]
+pub struct LevelMusic;
+
+const INTERACT_RANGE: f32 = 24.0;
 const PIT_FALL_DURATION: f32 = 0.5;
+const LEVEL_WATCH_INTERVAL: f32 = 0.5;
 
 #[derive(Component)]
 pub struct BoundToLevel;
 
+/// Tracks the modification time of the current level file for hot-reloading
+#[derive(Resource, Default)]
+pub struct LevelFileWatcher {
+    pub path: Option<String>,
+    pub last_modified: Option<SystemTime>,
+    pub check_timer: f32,
+}
+
 #[derive(Component)]
 pub struct FallingIntoPit {
     pub timer: f32,
@@ -52,134 +120,442 @@ pub fn enforce_level_bounds(
     }
 }
 
-pub fn check_win_zone(
+/// Poll the level file and reload it when it changes on disk.
+/// Broken edits are reported and the running level is left as it is.
+pub fn watch_level_file(
+    time: Res<Time>,
+    mut watcher: ResMut<LevelFileWatcher>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut reloaded: MessageWriter<LevelReloaded>,
+) {
+    watcher.check_timer += time.delta_secs();
+    if watcher.check_timer < LEVEL_WATCH_INTERVAL {
+        return;
+    }
+    watcher.check_timer = 0.0;
+
+    let Some(path) = current_level.path.clone() else { return };
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    // Start watching a newly loaded level without reloading it
+    if watcher.path.as_deref() != Some(path.as_str()) {
+        watcher.path = Some(path);
+        watcher.last_modified = modified;
+        return;
+    }
+
+    if modified.is_none() || modified == watcher.last_modified {
+        return;
+    }
+    watcher.last_modified = modified;
+
+    match current_level.reload() {
+        Ok(()) => {
+            info!("Reloaded level file {}", path);
+            reloaded.write(LevelReloaded);
+        }
+        Err(error) => {
+            error!("{}", error);
+        }
+    }
+}
+
+/// Apply health and inventory carried over from the previous campaign level
+pub fn restore_carried_player_state(
+    mut carry_over: ResMut<LevelCarryOver>,
+    mut selected_slot: ResMut<SelectedHotbarSlot>,
+    mut player_query: Query<(&mut Health, &mut Inventory), With<Player>>,
+) {
+    let Ok((mut health, mut inventory)) = player_query.single_mut() else { return };
+
+    if let Some(carried_health) = carry_over.health.take() {
+        *health = carried_health;
+    }
+    if let Some(carried_inventory) = carry_over.inventory.take() {
+        *inventory = carried_inventory;
+        // Re-equip the weapon in the selected slot of the carried inventory
+        selected_slot.set_changed();
+    }
+}
+
+pub fn update_wave_triggers(
     time: Res<Time>,
-    mut timer: ResMut<WinZoneTimer>,
+    current_level: Res<CurrentLevel>,
     mut wave_state: ResMut<WaveSpawnState>,
-    mut next_state: ResMut<NextState<GameState>>,
     enemies_query: Query<(), (With<Hostile>, Without<Dead>)>,
     player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
-    win_zone_query: Query<(&Transform, &WinZone)>,
-    mut timer_text_query: Query<&mut Text2d, With<WinZoneTimerText>>,
 ) {
+    let waves = current_level.waves();
+    wave_state.elapsed += time.delta_secs();
+
+    // Keep progress in step with the level's wave list (it may change on hot-reload)
+    if wave_state.waves.len() != waves.len() {
+        wave_state.waves.resize(waves.len(), WaveProgress::default());
+    }
+
     let enemies_alive = !enemies_query.is_empty();
+    let player_pos = player_query.single().ok().map(|transform| transform.translation.truncate());
+    let elapsed = wave_state.elapsed;
+
+    for (wave, progress) in waves.iter().zip(wave_state.waves.iter_mut()) {
+        if progress.triggered {
+            continue;
+        }
 
-    // Update timer text visibility and content
-    if let Ok(mut text) = timer_text_query.single_mut() {
-        if enemies_alive {
-            **text = "".to_string();
-        } else if timer.0 > 0.0 {
-            let remaining = (WIN_ZONE_TIME - timer.0).ceil() as i32;
-            **text = format!("{}", remaining.max(1));
-        } else {
-            **text = "".to_string();
+        let all_hold = wave.triggers.iter().all(|trigger| match trigger {
+            WaveTrigger::Timer { seconds } => elapsed >= *seconds,
+            WaveTrigger::RegionEntered { center, radius } => {
+                player_pos.is_some_and(|pos| pos.distance(*center) <= *radius)
+            }
+            WaveTrigger::AllEnemiesDead => !enemies_alive,
+        });
+
+        if all_hold {
+            progress.triggered = true;
+            // First round fires once the delay has passed
+            progress.spawn_timer = wave.interval - wave.delay;
         }
     }
+}
 
-    // Need all enemies dead
-    if enemies_alive {
-        timer.0 = 0.0;
-        return;
+pub fn spawn_wave_creatures(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    mut wave_state: ResMut<WaveSpawnState>,
+    config: Res<GameConfig>,
+    character_assets: Res<CharacterAssets>,
+    player_sprite_sheet: Res<PlayerSpriteSheet>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let waves = current_level.waves();
+
+    for (wave, progress) in waves.iter().zip(wave_state.waves.iter_mut()) {
+        let total = wave.total_count();
+        if !progress.triggered || progress.spawned >= total || wave.spawn_points.is_empty() {
+            continue;
+        }
+
+        progress.spawn_timer += time.delta_secs();
+        if progress.spawn_timer < wave.interval {
+            continue;
+        }
+        progress.spawn_timer = 0.0;
+
+        for _ in 0..wave.per_round {
+            let Some(creature) = wave.creature_for(progress.spawned) else { break };
+            let position = wave.spawn_points[progress.spawned as usize % wave.spawn_points.len()];
+
+            match creature {
+                CreatureType::Goblin => {
+                    crate::creatures::spawn_goblin(
+                        &mut commands,
+                        &config,
+                        &character_assets,
+                        &player_sprite_sheet,
+                        &mut meshes,
+                        &mut materials,
+                        position,
+                        wave.target,
+                    );
+                }
+            }
+            progress.spawned += 1;
+        }
     }
+}
 
-    // Need player alive
-    let Ok(player_transform) = player_query.single() else {
-        timer.0 = 0.0;
-        return;
-    };
+/// Track the player against each trigger volume and publish `TriggerFired`
+/// when its firing rule and conditions hold
+pub fn detect_trigger_volumes(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    mut trigger_state: ResMut<TriggerVolumeState>,
+    mut fired: MessageWriter<TriggerFired>,
+    enemies_query: Query<(), (With<Hostile>, Without<Dead>)>,
+    player_query: Query<(&Transform, &Inventory), With<Player>>,
+) {
+    let triggers = current_level.triggers();
 
-    // Need win zone in level
-    let Ok((zone_transform, win_zone)) = win_zone_query.single() else {
-        return;
-    };
+    // Keep progress in step with the level's trigger list (it may change on hot-reload)
+    if trigger_state.triggers.len() != triggers.len() {
+        trigger_state.triggers.resize(triggers.len(), TriggerProgress::default());
+    }
 
-    // Check if player is inside win zone circle
+    let Ok((player_transform, inventory)) = player_query.single() else { return };
     let player_pos = player_transform.translation.truncate();
-    let zone_pos = zone_transform.translation.truncate();
-    let distance = player_pos.distance(zone_pos);
+    let enemies_alive = !enemies_query.is_empty();
 
-    if distance <= win_zone.radius {
-        timer.0 += time.delta_secs();
+    for (index, (volume, progress)) in triggers.iter().zip(trigger_state.triggers.iter_mut()).enumerate() {
+        let inside = volume.area.contains(player_pos);
+        let was_inside = progress.inside;
+        progress.inside = inside;
+
+        let rule_matches = match volume.firing {
+            TriggerFiring::Enter => inside && !was_inside,
+            TriggerFiring::Exit => !inside && was_inside,
+            TriggerFiring::Stay { interval } => {
+                if inside {
+                    progress.stay_timer += time.delta_secs();
+                } else {
+                    progress.stay_timer = 0.0;
+                }
+                if inside && progress.stay_timer >= interval {
+                    progress.stay_timer = 0.0;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
 
-        // Trigger wave at WAVE_TRIGGER_TIME if not yet triggered
-        if timer.0 >= WAVE_TRIGGER_TIME && !wave_state.triggered {
-            wave_state.triggered = true;
-            wave_state.spawn_timer = WAVE_SPAWN_INTERVAL; // Spawn first pair immediately
+        if !rule_matches || (progress.fired && !volume.repeat) {
+            continue;
         }
 
-        if timer.0 >= WIN_ZONE_TIME {
-            next_state.set(GameState::Victory);
+        let conditions_hold = volume.conditions.iter().all(|condition| match condition {
+            TriggerCondition::AllHostilesDead => !enemies_alive,
+            TriggerCondition::ItemHeld { item, count } => inventory.count(*item) >= *count,
+        });
+
+        if conditions_hold {
+            if !volume.name.is_empty() {
+                info!("Trigger \"{}\" fired", volume.name);
+            }
+            progress.fired = true;
+            fired.write(TriggerFired { trigger: index });
         }
-    } else {
-        timer.0 = 0.0;
     }
 }
 
-pub fn spawn_wave_goblins(
+pub fn spawn_trigger_creatures(
     mut commands: Commands,
-    time: Res<Time>,
-    mut wave_state: ResMut<WaveSpawnState>,
+    mut fired: MessageReader<TriggerFired>,
+    current_level: Res<CurrentLevel>,
     config: Res<GameConfig>,
     character_assets: Res<CharacterAssets>,
     player_sprite_sheet: Res<PlayerSpriteSheet>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    if !wave_state.triggered {
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+
+        for action in &volume.actions {
+            let TriggerAction::SpawnCreatures { creature, positions, target } = action else { continue };
+
+            for position in positions {
+                match creature {
+                    CreatureType::Goblin => {
+                        crate::creatures::spawn_goblin(
+                            &mut commands,
+                            &config,
+                            &character_assets,
+                            &player_sprite_sheet,
+                            &mut meshes,
+                            &mut materials,
+                            *position,
+                            *target,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lock or unlock named areas and show their barriers accordingly
+pub fn apply_trigger_area_locks(
+    mut fired: MessageReader<TriggerFired>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut visual_query: Query<(&LockedAreaVisual, &mut Visibility)>,
+) {
+    let mut changes = Vec::new();
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+        for action in &volume.actions {
+            match action {
+                TriggerAction::LockArea { area } => changes.push((area.clone(), true)),
+                TriggerAction::UnlockArea { area } => changes.push((area.clone(), false)),
+                _ => {}
+            }
+        }
+    }
+
+    for (name, locked) in changes {
+        current_level.set_area_locked(&name, locked);
+        for (visual, mut visibility) in &mut visual_query {
+            if visual.name == name {
+                *visibility = if locked { Visibility::Inherited } else { Visibility::Hidden };
+            }
+        }
+    }
+}
+
+/// Open the nearest locked door in reach when the player interacts holding its key.
+/// The key is used up.
+pub fn use_keys_on_doors(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    bindings: Res<InputBindings>,
+    current_level: Res<CurrentLevel>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut requests: MessageWriter<SetDoorState>,
+) {
+    if !bindings.just_pressed(GameAction::Interact, &keyboard, &mouse) {
         return;
     }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else { return };
+    let player_pos = player_transform.translation.truncate();
 
-    let total_spawned = wave_state.spawned_left + wave_state.spawned_right;
-    let max_spawns = WAVE_GOBLINS_PER_SIDE * 2;
+    let nearest = current_level
+        .doors()
+        .iter()
+        .filter(|door| !door.open && door.key.is_some())
+        .map(|door| (door, door.footprint().clamp(player_pos).distance(player_pos)))
+        .filter(|(_, distance)| *distance <= INTERACT_RANGE)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let Some((door, _)) = nearest else { return };
+    let Some(key) = door.key else { return };
+
+    if inventory.take(key, 1) {
+        requests.write(SetDoorState { door: door.name.clone(), open: true });
+    } else {
+        info!("Door \"{}\" needs {:?}", door.name, key);
+    }
+}
 
-    if total_spawned >= max_spawns {
+/// Toggle the door of the nearest lever in reach when the player interacts
+pub fn pull_levers(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    bindings: Res<InputBindings>,
+    current_level: Res<CurrentLevel>,
+    player_query: Query<&Transform, With<Player>>,
+    lever_query: Query<(&Transform, &Lever)>,
+    mut requests: MessageWriter<SetDoorState>,
+) {
+    if !bindings.just_pressed(GameAction::Interact, &keyboard, &mouse) {
         return;
     }
+    let Ok(player_transform) = player_query.single() else { return };
+    let player_pos = player_transform.translation.truncate();
+
+    let nearest = lever_query
+        .iter()
+        .map(|(transform, lever)| (lever, transform.translation.truncate().distance(player_pos)))
+        .filter(|(_, distance)| *distance <= INTERACT_RANGE)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let Some((lever, _)) = nearest else { return };
+    let Some(door) = current_level.doors().iter().find(|d| d.name == lever.door) else { return };
+
+    requests.write(SetDoorState { door: door.name.clone(), open: !door.open });
+}
 
-    wave_state.spawn_timer += time.delta_secs();
-
-    if wave_state.spawn_timer >= WAVE_SPAWN_INTERVAL {
-        wave_state.spawn_timer = 0.0;
-
-        let y_positions = [920.0, 940.0, 960.0, 980.0];
-        let pair_index = wave_state.spawned_left as usize;
-        let spawn_y = y_positions.get(pair_index).copied().unwrap_or(950.0);
-        let pentagram_pos = Vec2::new(0.0, 1050.0);
-
-        if wave_state.spawned_left < WAVE_GOBLINS_PER_SIDE {
-            let left_pos = Vec2::new(-250.0, spawn_y);
-            crate::creatures::spawn_goblin(
-                &mut commands,
-                &config,
-                &character_assets,
-                &player_sprite_sheet,
-                &mut meshes,
-                &mut materials,
-                left_pos,
-                Some(pentagram_pos),
-            );
-            wave_state.spawned_left += 1;
+pub fn apply_trigger_doors(
+    mut fired: MessageReader<TriggerFired>,
+    current_level: Res<CurrentLevel>,
+    mut requests: MessageWriter<SetDoorState>,
+) {
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+        for action in &volume.actions {
+            match action {
+                TriggerAction::OpenDoor { door } => {
+                    requests.write(SetDoorState { door: door.clone(), open: true });
+                }
+                TriggerAction::CloseDoor { door } => {
+                    requests.write(SetDoorState { door: door.clone(), open: false });
+                }
+                _ => {}
+            }
         }
+    }
+}
 
-        if wave_state.spawned_right < WAVE_GOBLINS_PER_SIDE {
-            let right_pos = Vec2::new(250.0, spawn_y);
-            crate::creatures::spawn_goblin(
-                &mut commands,
-                &config,
-                &character_assets,
-                &player_sprite_sheet,
-                &mut meshes,
-                &mut materials,
-                right_pos,
-                Some(pentagram_pos),
-            );
-            wave_state.spawned_right += 1;
+/// Store requested door states in the level data and update door colliders and lever handles
+pub fn apply_door_state(
+    mut commands: Commands,
+    mut requests: MessageReader<SetDoorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut door_query: Query<(Entity, &mut Door, &Children)>,
+    mut leaf_query: Query<&mut Visibility, With<DoorLeaf>>,
+    mut lever_query: Query<(&mut Lever, &Children)>,
+    mut handle_query: Query<&mut Transform, With<LeverHandle>>,
+) {
+    for request in requests.read() {
+        if !current_level.set_door_open(&request.door, request.open) {
+            continue;
+        }
+        let Some(size) = current_level.doors().iter().find(|d| d.name == request.door).map(|d| d.size) else { continue };
+
+        for (entity, mut door, children) in &mut door_query {
+            if door.name != request.door {
+                continue;
+            }
+            door.open = request.open;
+            if request.open {
+                commands.entity(entity).remove::<StaticCollider>();
+            } else {
+                commands.entity(entity).insert(door_collider(size));
+            }
+            for child in children.iter() {
+                if let Ok(mut visibility) = leaf_query.get_mut(child) {
+                    *visibility = if request.open { Visibility::Hidden } else { Visibility::Inherited };
+                }
+            }
+        }
+
+        for (mut lever, children) in &mut lever_query {
+            if lever.door != request.door {
+                continue;
+            }
+            lever.pulled = request.open;
+            for child in children.iter() {
+                if let Ok(mut transform) = handle_query.get_mut(child) {
+                    transform.rotation = lever_handle_rotation(request.open);
+                }
+            }
+        }
+    }
+}
+
+/// Start or stop the looping level music track
+pub fn play_trigger_music(
+    mut commands: Commands,
+    mut fired: MessageReader<TriggerFired>,
+    current_level: Res<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+    music_query: Query<Entity, With<LevelMusic>>,
+) {
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+
+        for action in &volume.actions {
+            let track = match action {
+                TriggerAction::PlayMusic { track } => Some(track),
+                TriggerAction::StopMusic => None,
+                _ => continue,
+            };
+
+            for entity in &music_query {
+                commands.entity(entity).despawn();
+            }
+            if let Some(track) = track {
+                commands.spawn((
+                    LevelMusic,
+                    AudioPlayer::new(asset_server.load(track.clone())),
+                    PlaybackSettings::LOOP,
+                ));
+            }
         }
     }
 }
 
 pub fn apply_pit_edge_resistance(
     time: Res<Time>,
+    config: Res<GameConfig>,
     pit_query: Query<(&Transform, &Pit)>,
     mut entity_query: Query<(&mut Transform, Option<&WalkCollider>), (With<BoundToLevel>, Without<Dead>, Without<FallingIntoPit>, Without<Pit>, Without<Knockback>)>,
 ) {
@@ -197,7 +573,7 @@ pub fn apply_pit_edge_resistance(
 
             if distance >= pit.radius && distance < pit.edge_radius {
                 let edge_depth = 1.0 - (distance - pit.radius) / (pit.edge_radius - pit.radius);
-                let resistance = edge_depth.powi(2) * PIT_EDGE_RESISTANCE;
+                let resistance = edge_depth.powi(2) * config.pit_edge_resistance;
                 let push_dir = to_entity.normalize_or_zero();
                 let push = push_dir * resistance * time.delta_secs();
 
@@ -3,20 +3,25 @@ mod constants;
 mod core;
 mod creatures;
 mod debug;
+mod editor;
 mod effects;
 mod inventory;
+mod leaderboard;
 mod levels;
 mod player;
+mod profile;
 mod props;
+mod run_stats;
+mod save;
 mod state_machine;
 mod ui;
 mod world;
 
-use bevy::{image::ImageSamplerDescriptor, prelude::*};
+use bevy::{ecs::system::SystemParam, image::ImageSamplerDescriptor, prelude::*};
 use constants::*;
 
-use core::{CharacterAssets, CorePlugin, GameConfig, GameState, InputBindings};
-use levels::{CreatureType, CurrentLevel, LevelBackground, LevelsPlugin, PropType, VoidBackground, WaveSpawnState, WinZone, WinZoneTimer};
+use core::{CharacterAssets, CorePlugin, GameConfig, GameState, Health, InputBindings, StrictConfig, STRICT_CONFIG_FLAG};
+use levels::{Campaign, Checkpoint, CreatureType, CurrentLevel, Door, Hazard, LevelBackground, LevelCarryOver, LevelData, LevelProgress, Lever, LevelMusic, LevelReloaded, LevelSpawnIndex, LevelsPlugin, ObjectiveState, Pit, PropType, VoidBackground, WaveSpawnState, WinZone};
 use world::{NewGameRequested, WorldConfig};
 use creatures::{Creature, CreaturePlugin};
 use debug::{
@@ -24,19 +29,36 @@ use debug::{
     toggle_collision_debug, update_creature_debug_circles, update_debug_visibility,
     update_player_debug_cone, update_steering_debug, DebugConfig,
 };
+use editor::{EditorPlugin, LevelEdited};
 use effects::{BloodParticle, EffectsPlugin, Hitstop, ScreenShake, TargetOutline};
-use inventory::{build_item_registry, GroundItem, InventoryPlugin, ItemIcons, ItemId, ItemRegistry};
+use inventory::{build_item_registry, GroundItem, Inventory, InventoryPlugin, ItemIcons, ItemId, ItemRegistry};
 use player::{
     animate_sprites, load_player_sprite_sheet, update_player_sprite_animation,
-    Player, PlayerPlugin, PlayerSpriteSheet, Stats,
+    CameraState, Player, PlayerBonuses, PlayerPlugin, PlayerSpriteSheet, Stats,
 };
+use leaderboard::LeaderboardPlugin;
+use profile::{BankedStats, Profile, ProfilePlugin, PROFILE_FILE};
+use run_stats::RunStatsPlugin;
+use save::{PendingLoad, SavePlugin, SavedCreatureState, SavedRun};
 use props::{build_prop_registry, load_barrel_sprites, load_crate_sprites, load_crate2_sprites, BarrelSprites, CrateSprites, Crate2Sprites, Prop, PropRegistry};
 use ui::{
-    auto_start_new_game, hide_pause_menu, setup_ui, show_pause_menu, show_victory_menu,
-    spawn_key_bindings_panel, toggle_pause_menu, UiPlugin,
+    hide_level_error_menu, hide_pause_menu, setup_ui, show_level_error_menu,
+    show_pause_menu, show_victory_menu, spawn_key_bindings_panel, toggle_pause_menu, TriggerPrompt, UiPlugin,
 };
 
 fn main() {
+    // Read the config before the window opens so strict mode can refuse to start
+    let strict_config = std::env::args().any(|arg| arg == STRICT_CONFIG_FLAG);
+    let difficulty = Profile::load_from_file(PROFILE_FILE).map(|profile| profile.difficulty).unwrap_or_default();
+    let (game_config, config_report) = GameConfig::load_from_files(&difficulty.config_stack());
+    if strict_config && !config_report.diagnostics.is_empty() {
+        for diagnostic in &config_report.diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        eprintln!("Config {}; refusing to start in strict mode", config_report.summary());
+        std::process::exit(1);
+    }
+
     App::new()
         .add_plugins(DefaultPlugins
             .set(WindowPlugin {
@@ -51,6 +73,10 @@ fn main() {
             .set(ImagePlugin {
                 default_sampler: ImageSamplerDescriptor::nearest(),
             }))
+        .insert_resource(game_config)
+        .insert_resource(config_report)
+        .insert_resource(StrictConfig(strict_config))
+        .insert_resource(difficulty)
         .init_resource::<Stats>()
         .init_resource::<WorldConfig>()
         .init_resource::<NewGameRequested>()
@@ -58,7 +84,7 @@ fn main() {
         .init_resource::<Hitstop>()
         .init_resource::<ScreenShake>()
         .init_resource::<DebugConfig>()
-        .init_resource::<WinZoneTimer>()
+        .init_resource::<ObjectiveState>()
         .init_resource::<WaveSpawnState>()
         .init_state::<GameState>()
         .insert_resource(ClearColor(Color::srgb(0.2, 0.2, 0.25)))
@@ -77,17 +103,25 @@ fn main() {
             update_player_sprite_animation,
             animate_sprites,
         ))
-        .add_systems(OnEnter(GameState::Playing), spawn_world)
+        .add_systems(OnEnter(GameState::Playing), (spawn_world, levels::systems::restore_carried_player_state).chain())
         .add_systems(OnEnter(GameState::Paused), show_pause_menu)
         .add_systems(OnExit(GameState::Paused), hide_pause_menu)
-        .add_systems(OnEnter(GameState::Dead), auto_start_new_game)
         .add_systems(OnExit(GameState::Dead), (hide_pause_menu, cleanup_world).chain())
+        // The cleared level is entered on the leaderboard before it is torn down
+        .add_systems(OnEnter(GameState::LevelComplete), advance_to_next_level.after(leaderboard::systems::record_level_clear))
+        .add_systems(OnEnter(GameState::Respawning), respawn_at_checkpoint)
+        .add_systems(OnEnter(GameState::LoadingSave), (cleanup_world, load_saved_game).chain())
         .add_systems(OnEnter(GameState::Victory), show_victory_menu)
         .add_systems(OnExit(GameState::Victory), (hide_pause_menu, cleanup_world).chain())
+        .add_systems(OnEnter(GameState::LevelError), show_level_error_menu)
+        .add_systems(OnExit(GameState::LevelError), (hide_level_error_menu, cleanup_world).chain())
         .add_systems(Update, (
-            levels::systems::check_win_zone,
-            levels::systems::spawn_wave_goblins,
+            levels::check_objectives,
+            levels::systems::update_wave_triggers,
+            levels::systems::spawn_wave_creatures.after(levels::systems::update_wave_triggers),
+            respawn_reloaded_level.after(levels::systems::watch_level_file),
         ).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, respawn_edited_level.run_if(in_state(GameState::Editor)))
         .add_plugins((
             state_machine::StateMachinePlugin,
             CorePlugin,
@@ -97,6 +131,11 @@ fn main() {
             UiPlugin,
             InventoryPlugin,
             LevelsPlugin,
+            EditorPlugin,
+            SavePlugin,
+            ProfilePlugin,
+            RunStatsPlugin,
+            LeaderboardPlugin,
         ))
         .run();
 }
@@ -108,9 +147,13 @@ fn setup(
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut next_state: ResMut<NextState<GameState>>,
+    game_config: Res<GameConfig>,
 ) {
-    // Load game config from INI file (with fallback to defaults)
-    let game_config = GameConfig::load_from_file("config.ini");
+    // A broken campaign file shows up on the level error screen once the game starts
+    let campaign = Campaign::load_from_file("assets/levels/campaign.ron").unwrap_or_else(|error| {
+        error!("{}", error);
+        Campaign::failed(&error)
+    });
 
     // Camera
     commands.spawn((
@@ -129,7 +172,7 @@ fn setup(
     let item_icons = load_item_icons(&asset_server);
 
     // Insert resources
-    commands.insert_resource(game_config);
+    commands.insert_resource(campaign);
     commands.insert_resource(character_assets);
     commands.insert_resource(player_sprite_sheet);
     commands.insert_resource(item_registry);
@@ -153,20 +196,31 @@ fn load_item_icons(asset_server: &AssetServer) -> ItemIcons {
     icons
 }
 
+/// Config, sprites, registries and asset stores used to spawn a level's content
+#[derive(SystemParam)]
+struct LevelSpawnAssets<'w> {
+    config: Res<'w, GameConfig>,
+    character_assets: Res<'w, CharacterAssets>,
+    player_sprite_sheet: Res<'w, PlayerSpriteSheet>,
+    prop_registry: Res<'w, PropRegistry>,
+    crate_sprites: Res<'w, CrateSprites>,
+    crate2_sprites: Res<'w, Crate2Sprites>,
+    barrel_sprites: Res<'w, BarrelSprites>,
+    asset_server: Res<'w, AssetServer>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+    atlas_layouts: ResMut<'w, Assets<TextureAtlasLayout>>,
+    item_registry: Res<'w, ItemRegistry>,
+    item_icons: Res<'w, ItemIcons>,
+}
+
 fn spawn_world(
     mut commands: Commands,
-    config: Res<GameConfig>,
-    character_assets: Res<CharacterAssets>,
-    player_sprite_sheet: Res<PlayerSpriteSheet>,
-    item_registry: Res<ItemRegistry>,
-    item_icons: Res<ItemIcons>,
-    prop_registry: Res<PropRegistry>,
-    crate_sprites: Res<CrateSprites>,
-    crate2_sprites: Res<Crate2Sprites>,
-    barrel_sprites: Res<BarrelSprites>,
-    mut current_level: ResMut<CurrentLevel>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut assets: LevelSpawnAssets,
+    world_config: Res<WorldConfig>,
+    bonuses: Res<PlayerBonuses>,
+    mut progress: LevelProgress,
+    mut next_state: ResMut<NextState<GameState>>,
     player_query: Query<Entity, With<Player>>,
 ) {
     // Skip if player already exists (resuming from pause)
@@ -174,56 +228,179 @@ fn spawn_world(
         return;
     }
 
-    // Load level data
-    current_level.load("assets/levels/corridor.ron");
-    let level = current_level.data.as_ref().expect("Level should be loaded");
+    // Load level data, showing the error screen if it is missing or broken
+    if let Err(error) = progress.current_level.load_campaign_level(&progress.campaign) {
+        error!("{}", error);
+        next_state.set(GameState::LevelError);
+        return;
+    }
+    let level = progress.current_level.data.as_ref().expect("Level should be loaded");
+
+    spawn_level_content(&mut commands, level, &mut assets, &[], &[]);
 
+    // Spawn player at level's spawn position
+    let player = player::spawn_player(&mut commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, level.player_spawn);
+    player::spawn_target_outline(&mut commands, &assets.character_assets);
+
+    for spawn in &level.items {
+        player::spawn_ground_item(&mut commands, &assets.character_assets, &assets.item_registry, &assets.item_icons, spawn.item_id, spawn.quantity, spawn.position);
+    }
+
+    // A new run starts with the upgraded health and unlocked starting items.
+    // Later levels get the carried over health instead.
+    if progress.campaign.current == 0 {
+        commands.entity(player).insert(Health(bonuses.player_max_health()));
+        for (item_id, quantity, offset) in &world_config.starting_items {
+            let position = level.clamp_to_walkable(level.player_spawn + *offset);
+            player::spawn_ground_item(&mut commands, &assets.character_assets, &assets.item_registry, &assets.item_icons, *item_id, *quantity, position);
+        }
+    }
+}
+
+// Spawn everything the level file describes except the player and ground items.
+// When respawning at a checkpoint or loading a save, creatures and props gone by then are left out.
+fn spawn_level_content(commands: &mut Commands, level: &LevelData, assets: &mut LevelSpawnAssets, killed_creatures: &[usize], destroyed_props: &[usize]) {
     // Spawn level background (void and corridor)
-    levels::spawn_level_background(&mut commands, level, &mut meshes, &mut materials);
+    levels::spawn_level_background(commands, level, &mut assets.meshes, &mut assets.materials);
+
+    // Spawn autotiled terrain on top of the walkable shapes
+    if let Some(tiles) = &level.tiles {
+        levels::spawn_tile_layer(commands, tiles, &assets.asset_server, &mut assets.atlas_layouts);
+    }
+
+    // Spawn barriers over lockable areas
+    levels::spawn_locked_areas(commands, level, &mut assets.meshes, &mut assets.materials);
+
+    // Spawn doors and the levers that work them
+    for door in &level.doors {
+        levels::spawn_door(commands, door, &mut assets.meshes, &mut assets.materials);
+    }
+    for lever in &level.levers {
+        let pulled = level.doors.iter().any(|door| door.name == lever.door && door.open);
+        levels::spawn_lever(commands, lever, pulled, &mut assets.meshes, &mut assets.materials);
+    }
 
     // Spawn win zone if level has one
     if let Some(win_zone) = &level.win_zone {
-        levels::spawn_win_zone(&mut commands, win_zone.position, win_zone.radius, &mut meshes, &mut materials);
+        levels::spawn_win_zone(commands, win_zone.position, win_zone.radius, &mut assets.meshes, &mut assets.materials);
     }
 
     // Spawn pits
     for pit in &level.pits {
-        levels::spawn_pit(&mut commands, pit.position, pit.radius, pit.edge_radius, &mut meshes, &mut materials);
+        levels::spawn_pit(commands, pit.position, pit.radius, pit.edge_radius, &mut assets.meshes, &mut assets.materials);
     }
 
-    // Spawn player at level's spawn position
-    player::spawn_player(&mut commands, &config, &character_assets, &player_sprite_sheet, &mut meshes, &mut materials, level.player_spawn);
-    player::spawn_target_outline(&mut commands, &character_assets);
+    for (index, hazard) in level.hazards.iter().enumerate() {
+        levels::spawn_hazard(commands, index, hazard, &mut assets.meshes, &mut assets.materials);
+    }
 
-    for spawn in &level.items {
-        player::spawn_ground_item(&mut commands, &character_assets, &item_registry, &item_icons, spawn.item_id, spawn.quantity, spawn.position);
+    for (index, checkpoint) in level.checkpoints.iter().enumerate() {
+        levels::spawn_checkpoint(commands, index, checkpoint, &mut assets.meshes, &mut assets.materials);
     }
 
-    for spawn in &level.creatures {
-        match spawn.creature {
-            CreatureType::Goblin => {
-                creatures::spawn_goblin(&mut commands, &config, &character_assets, &player_sprite_sheet, &mut meshes, &mut materials, spawn.position, None);
-            }
+    // Named props can be targeted by level objectives.
+    // Spawn indices let checkpoints and saves remember which ones are gone.
+    for (index, spawn) in level.creatures.iter().enumerate() {
+        if !killed_creatures.contains(&index) {
+            spawn_level_creature(commands, level, index, assets, spawn.position);
         }
     }
 
-    for spawn in &level.props {
-        match spawn.prop {
-            PropType::Pillar => props::spawn_pillar(&mut commands, &prop_registry, spawn.position),
-            PropType::Barrel => props::spawn_barrel(&mut commands, &barrel_sprites, &prop_registry, spawn.position),
-            PropType::Crate => props::spawn_crate(&mut commands, &crate_sprites, &prop_registry, spawn.position),
-            PropType::Crate2 => props::spawn_crate2(&mut commands, &crate2_sprites, &prop_registry, spawn.position),
+    for (index, spawn) in level.props.iter().enumerate() {
+        if destroyed_props.contains(&index) {
+            continue;
+        }
+        let entity = match spawn.prop {
+            PropType::Pillar => props::spawn_pillar(commands, &assets.prop_registry, spawn.position),
+            PropType::Barrel => props::spawn_barrel(commands, &assets.barrel_sprites, &assets.prop_registry, spawn.position),
+            PropType::Crate => props::spawn_crate(commands, &assets.crate_sprites, &assets.prop_registry, spawn.position),
+            PropType::Crate2 => props::spawn_crate2(commands, &assets.crate2_sprites, &assets.prop_registry, spawn.position),
+        };
+        let Some(entity) = entity else { continue };
+        commands.entity(entity).insert(LevelSpawnIndex(index));
+        if let Some(name) = &spawn.name {
+            commands.entity(entity).insert(Name::new(name.clone()));
+        }
+    }
+}
+
+// Spawn creature `index` of the level at `position`.
+// Named creatures can be targeted by level objectives, and creatures may walk a patrol route.
+fn spawn_level_creature(commands: &mut Commands, level: &LevelData, index: usize, assets: &mut LevelSpawnAssets, position: Vec2) -> Entity {
+    let spawn = &level.creatures[index];
+    let entity = match spawn.creature {
+        CreatureType::Goblin => {
+            creatures::spawn_goblin(commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, position, None)
         }
+    };
+    commands.entity(entity).insert(LevelSpawnIndex(index));
+    if let Some(name) = &spawn.name {
+        commands.entity(entity).insert(Name::new(name.clone()));
+    }
+    if let Some(route) = &spawn.route {
+        commands.entity(entity).insert(creatures::PatrolRoute::new(route.clone()));
+    }
+    entity
+}
+
+type ReloadableEntityFilter = Or<(With<Creature>, With<Prop>, With<LevelBackground>, With<VoidBackground>, With<WinZone>, With<Pit>, With<Door>, With<Lever>, With<Checkpoint>, With<Hazard>)>;
+
+// Rebuild the level in place after its file was hot-reloaded.
+// The player, ground items and stats are left untouched.
+fn respawn_reloaded_level(
+    mut commands: Commands,
+    mut reloaded: MessageReader<LevelReloaded>,
+    mut assets: LevelSpawnAssets,
+    current_level: Res<CurrentLevel>,
+    query: Query<Entity, ReloadableEntityFilter>,
+) {
+    if reloaded.read().count() == 0 {
+        return;
+    }
+    let Some(level) = current_level.data.as_ref() else { return };
+
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    spawn_level_content(&mut commands, level, &mut assets, &[], &[]);
+}
+
+type EditableEntityFilter = Or<(ReloadableEntityFilter, With<GroundItem>)>;
+
+// Rebuild the level after an edit in the level editor.
+// Unlike a hot reload, ground items are respawned too since the editor places them.
+fn respawn_edited_level(
+    mut commands: Commands,
+    mut edited: MessageReader<LevelEdited>,
+    mut assets: LevelSpawnAssets,
+    current_level: Res<CurrentLevel>,
+    query: Query<Entity, EditableEntityFilter>,
+) {
+    if edited.read().count() == 0 {
+        return;
+    }
+    let Some(level) = current_level.data.as_ref() else { return };
+
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    spawn_level_content(&mut commands, level, &mut assets, &[], &[]);
+    for spawn in &level.items {
+        player::spawn_ground_item(&mut commands, &assets.character_assets, &assets.item_registry, &assets.item_icons, spawn.item_id, spawn.quantity, spawn.position);
     }
 }
 
+type LevelEntityFilter = Or<(ReloadableEntityFilter, With<Player>, With<BloodParticle>, With<TargetOutline>, With<GroundItem>, With<LevelMusic>, With<TriggerPrompt>)>;
+
 fn cleanup_world(
     mut commands: Commands,
-    query: Query<Entity, Or<(With<Player>, With<Creature>, With<BloodParticle>, With<TargetOutline>, With<GroundItem>, With<Prop>, With<LevelBackground>, With<VoidBackground>, With<WinZone>)>>,
+    query: Query<Entity, LevelEntityFilter>,
     mut stats: ResMut<Stats>,
-    mut current_level: ResMut<CurrentLevel>,
-    mut win_zone_timer: ResMut<WinZoneTimer>,
-    mut wave_spawn_state: ResMut<WaveSpawnState>,
+    mut banked: ResMut<BankedStats>,
+    mut progress: LevelProgress,
+    mut camera_state: ResMut<CameraState>,
 ) {
     for entity in &query {
         commands.entity(entity).despawn();
@@ -231,7 +408,144 @@ fn cleanup_world(
     stats.philosophy = 0;
     stats.nature_study = 0;
     stats.wisdom = 0;
-    current_level.data = None;
-    win_zone_timer.0 = 0.0;
-    *wave_spawn_state = WaveSpawnState::default();
+    *banked = BankedStats::default();
+    progress.current_level.data = None;
+    progress.current_level.error = None;
+    progress.campaign.reset();
+    *progress.carry_over = LevelCarryOver::default();
+    progress.reset_level();
+    camera_state.zoom = 1.0;
+}
+
+// Tear down the finished level and move on to the next one in the campaign.
+// Stats are left untouched; health and inventory are restored on entering Playing by
+// levels::systems::restore_carried_player_state.
+fn advance_to_next_level(
+    mut commands: Commands,
+    query: Query<Entity, LevelEntityFilter>,
+    player_query: Query<(&Health, &Inventory), With<Player>>,
+    mut progress: LevelProgress,
+    mut camera_state: ResMut<CameraState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Ok((health, inventory)) = player_query.single() {
+        progress.carry_over.health = Some(*health);
+        progress.carry_over.inventory = Some(inventory.clone());
+    }
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    progress.current_level.data = None;
+    progress.campaign.advance();
+    progress.reset_level();
+    camera_state.zoom = 1.0;
+    next_state.set(GameState::Playing);
+}
+
+// Rebuild the level as it was at the last checkpoint after the player died.
+// Stats are left untouched; health and inventory are restored on entering Playing by
+// levels::systems::restore_carried_player_state.
+fn respawn_at_checkpoint(
+    mut commands: Commands,
+    query: Query<Entity, LevelEntityFilter>,
+    mut assets: LevelSpawnAssets,
+    mut progress: LevelProgress,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    next_state.set(GameState::Playing);
+    // Without a snapshot the dead player stays and the death screen shows as usual
+    let Some(snapshot) = progress.checkpoint.snapshot.clone() else { return };
+
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    progress.checkpoint.respawns += 1;
+    progress.carry_over.health = Some(snapshot.health);
+    progress.carry_over.inventory = Some(snapshot.inventory.clone());
+    progress.objective.zone_time = 0.0;
+    *progress.waves = snapshot.waves.clone();
+    *progress.triggers = snapshot.triggers.clone();
+
+    let level = progress.current_level.data.insert(snapshot.level.clone());
+    spawn_level_content(&mut commands, level, &mut assets, &snapshot.killed_creatures, &snapshot.destroyed_props);
+
+    for position in &snapshot.extra_creatures {
+        creatures::spawn_goblin(&mut commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, *position, None);
+    }
+    for (item_id, quantity, position) in &snapshot.ground_items {
+        player::spawn_ground_item(&mut commands, &assets.character_assets, &assets.item_registry, &assets.item_icons, *item_id, *quantity, *position);
+    }
+
+    player::spawn_player(&mut commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, snapshot.position);
+    player::spawn_target_outline(&mut commands, &assets.character_assets);
+}
+
+// Rebuild the game from the save file read by the save plugin, after cleanup_world has cleared
+// the previous one. Health and inventory are restored by levels::systems::restore_carried_player_state
+// like between levels.
+fn load_saved_game(
+    mut commands: Commands,
+    mut assets: LevelSpawnAssets,
+    mut pending: ResMut<PendingLoad>,
+    mut progress: LevelProgress,
+    mut run: SavedRun,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    next_state.set(GameState::Playing);
+    // Without a save spawn_world starts a fresh game
+    let Some(save) = pending.0.take() else { return };
+
+    progress.campaign.current = save.campaign_level.min(progress.campaign.levels.len().saturating_sub(1));
+    let loaded = match &save.level_path {
+        Some(path) => progress.current_level.load(path),
+        None => progress.current_level.load_campaign_level(&progress.campaign),
+    };
+    if let Err(error) = loaded {
+        error!("{}", error);
+        next_state.set(GameState::LevelError);
+        return;
+    }
+
+    let level = progress.current_level.data.as_mut().expect("Level should be loaded");
+    for door in &mut level.doors {
+        door.open = save.open_doors.contains(&door.name);
+    }
+    for area in &mut level.areas {
+        area.locked = save.locked_areas.contains(&area.name);
+    }
+    let level = progress.current_level.data.as_ref().expect("Level should be loaded");
+
+    // Surviving creatures are spawned from the save, where they were
+    let all_creatures: Vec<usize> = (0..level.creatures.len()).collect();
+    spawn_level_content(&mut commands, level, &mut assets, &all_creatures, &save.destroyed_props);
+
+    for creature in &save.creatures {
+        let entity = match creature.spawn_index.filter(|index| *index < level.creatures.len()) {
+            Some(index) => spawn_level_creature(&mut commands, level, index, &mut assets, creature.position),
+            None => creatures::spawn_goblin(&mut commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, creature.position, None),
+        };
+        commands.entity(entity).insert((Health(creature.health), creatures::Awareness(creature.awareness)));
+        if creature.state == SavedCreatureState::Chase {
+            commands.entity(entity).insert(creatures::Activated);
+        }
+    }
+    for item in &save.ground_items {
+        player::spawn_ground_item(&mut commands, &assets.character_assets, &assets.item_registry, &assets.item_icons, item.item_id, item.quantity, item.position);
+    }
+
+    player::spawn_player(&mut commands, &assets.config, &assets.character_assets, &assets.player_sprite_sheet, &mut assets.meshes, &mut assets.materials, save.player_position);
+    player::spawn_target_outline(&mut commands, &assets.character_assets);
+
+    progress.carry_over.health = Some(Health(save.health));
+    progress.carry_over.inventory = Some(save.inventory);
+    *progress.waves = save.waves;
+    *progress.triggers = save.triggers;
+    progress.checkpoint.snapshot = save.checkpoint;
+    progress.checkpoint.respawns = save.respawns;
+    progress.objective.elapsed = save.elapsed;
+    progress.objective.zone_time = save.zone_time;
+    run.selected_slot.0 = save.selected_slot;
+    *run.stats = save.stats;
+    run.banked.0 = save.banked;
+    *run.run_stats = save.run_stats;
 }
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-use crate::constants::{CAMERA_BASE_SCALE, CAMERA_ZOOM_OUT_SCALE, COMBO_TIMEOUT};
+use crate::constants::{CAMERA_BASE_SCALE, PIXEL_SCALE};
+use crate::core::GameConfig;
 
 /// Player marker component
 #[derive(Component)]
@@ -96,8 +97,8 @@ impl ComboState {
     }
 
     /// Check if combo should be reset due to timeout
-    pub fn should_reset(&self) -> bool {
-        self.time_since_attack >= COMBO_TIMEOUT
+    pub fn should_reset(&self, timeout: f32) -> bool {
+        self.time_since_attack >= timeout
     }
 
     /// Get the attack number (1, 2, or 3)
@@ -111,6 +112,8 @@ impl ComboState {
 pub struct CameraState {
     pub current_scale: f32,
     pub target_scale: f32,
+    /// Extra zoom multiplier set by level triggers
+    pub zoom: f32,
 }
 
 impl Default for CameraState {
@@ -118,17 +121,19 @@ impl Default for CameraState {
         Self {
             current_scale: CAMERA_BASE_SCALE,
             target_scale: CAMERA_BASE_SCALE,
+            zoom: 1.0,
         }
     }
 }
 
 impl CameraState {
-    pub fn set_moving(&mut self, is_moving: bool) {
-        self.target_scale = if is_moving {
-            CAMERA_ZOOM_OUT_SCALE
+    pub fn set_moving(&mut self, is_moving: bool, config: &GameConfig) {
+        let zoom = if is_moving {
+            config.camera_zoom_out
         } else {
-            CAMERA_BASE_SCALE
+            config.camera_base_zoom
         };
+        self.target_scale = zoom / PIXEL_SCALE * self.zoom;
     }
 }
 
@@ -21,7 +21,7 @@ use bevy::prelude::*;
 use crate::core::GameState;
 use crate::combat::{
     aim_weapon, toggle_weapon, sync_range_indicator, update_weapon_visual,
-    handle_block, apply_mesh_attack_hits, apply_smash_attack_hits,
+    handle_block, apply_mesh_attack_hits, apply_smash_attack_hits, DamageDealt, PlayerDamaged,
 };
 use crate::inventory::cursor_not_over_ui;
 use crate::state_machine::{register_state_type, StateMachineSet};
@@ -32,11 +32,14 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         register_state_type::<PlayerState>(app);
 
-        app.init_resource::<CameraState>();
+        app.init_resource::<CameraState>()
+            .init_resource::<PlayerBonuses>();
 
         app.add_message::<DashInputDetected>()
             .add_message::<AttackInputDetected>()
-            .add_message::<MovementInputDetected>();
+            .add_message::<MovementInputDetected>()
+            .add_message::<DamageDealt>()
+            .add_message::<PlayerDamaged>();
 
         app.add_systems(
             Update,
@@ -75,6 +78,7 @@ impl Plugin for PlayerPlugin {
                 apply_static_collision.after(apply_player_movement),
                 apply_dash_state,
                 tick_dash_cooldown,
+                apply_max_health_bonus,
                 tick_phase_through,
                 tick_combo_timer,
                 tick_hurt_animation,
@@ -102,6 +106,10 @@ impl Plugin for PlayerPlugin {
                 .run_if(in_state(GameState::Playing))
                 .run_if(cursor_not_over_ui),
         )
-        .add_systems(Update, camera_follow);
+        .add_systems(
+            Update,
+            apply_trigger_camera.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Update, camera_follow.run_if(not(in_state(GameState::Editor))));
     }
 }
@@ -4,13 +4,13 @@ use rand::Rng;
 use crate::combat::{create_half_circle_arc, Equipment, PlayerRangeIndicator, WeaponRangeIndicator};
 use crate::inventory::weapons::{Drawn, PlayerWeapon, WeaponVisualMesh, weapon_catalog};
 use crate::constants::*;
-use crate::core::{CharacterAssets, GameConfig, Health, Shadow, WalkCollider, HitCollider, YSorted};
+use crate::core::{CharacterAssets, GameConfig, Health, MaxHealth, Shadow, WalkCollider, HitCollider, YSorted};
 use crate::creatures::ColliderDef;
 use crate::effects::TargetOutline;
 use crate::inventory::{EquippedWeaponId, GroundItem, GroundItemBob, Inventory, ItemIcons, ItemId, ItemRegistry, Pickupable};
 use crate::state_machine::StateMachine;
 use crate::ui::{HeartSprite, HpText};
-use crate::levels::BoundToLevel;
+use crate::levels::{BoundToLevel, HazardExposure};
 use super::{ComboState, FacingDirection, MovementInput, Player, PlayerAnimation, PlayerSpriteSheet, PlayerState, SpriteAnimation};
 
 pub fn spawn_ground_item(
@@ -69,7 +69,7 @@ pub fn spawn_player(
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<ColorMaterial>,
     spawn_pos: Vec2,
-) {
+) -> Entity {
     let weapon = weapon_catalog::sword(config, meshes, materials);
     let weapon_visual = weapon.visual.clone();
 
@@ -102,7 +102,7 @@ pub fn spawn_player(
         ComboState::default(),
         SpriteAnimation::new("idle_down", initial_anim.frame_duration_ms),
         // Combat/inventory
-        Health(10),
+        Health(PLAYER_HEALTH),
         Equipment::default(),
         inventory,
         EquippedWeaponId(ItemId::Sword),
@@ -116,6 +116,8 @@ pub fn spawn_player(
             },
         ),
         Transform::from_xyz(spawn_pos.x, spawn_pos.y, 0.0),
+        HazardExposure::default(),
+        MaxHealth(PLAYER_HEALTH),
     )).with_children(|parent| {
         // Shadow - right under feet
         parent.spawn((
@@ -174,7 +176,7 @@ pub fn spawn_player(
             MeshMaterial2d(assets.range_indicator_material.clone()),
             Transform::from_xyz(0.0, config.attack_center_offset_y, Z_WEAPON + 0.1),
         ));
-    });
+    }).id()
 }
 
 pub fn spawn_target_outline(commands: &mut Commands, assets: &CharacterAssets) {
@@ -187,21 +189,3 @@ pub fn spawn_target_outline(commands: &mut Commands, assets: &CharacterAssets) {
     ));
 }
 
-pub fn spawn_background_grid(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
-) {
-    let dot_mesh = meshes.add(Circle::new(1.0));
-    let dot_material = materials.add(Color::srgb(0.35, 0.35, 0.4));
-
-    for x in -WORLD_SIZE..=WORLD_SIZE {
-        for y in -WORLD_SIZE..=WORLD_SIZE {
-            commands.spawn((
-                Mesh2d(dot_mesh.clone()),
-                MeshMaterial2d(dot_material.clone()),
-                Transform::from_xyz(x as f32 * GRID_SPACING, y as f32 * GRID_SPACING, Z_BACKGROUND),
-            ));
-        }
-    }
-}
@@ -3,12 +3,11 @@ use bevy::prelude::*;
 use crate::combat::hit_detection::snap_to_cardinal;
 use crate::inventory::AttackType;
 use crate::inventory::weapons::{Drawn, PlayerWeapon, Weapon, WeaponSwing};
-use crate::constants::ATTACK_HIT_DELAY_PERCENT;
-use crate::core::{Dead, DeathAnimation, GameAction, InputBindings};
+use crate::core::{Dead, DeathAnimation, GameAction, GameConfig, InputBindings};
 use crate::state_machine::{AttackPhase, RequestTransition, StateEntered, StateExited, StateMachine};
 use super::{
     ComboState, DashCooldown, DashInputDetected, AttackInputDetected, MovementInputDetected,
-    FacingDirection, PhaseThrough, Player, PlayerAnimation, PlayerState,
+    FacingDirection, PhaseThrough, Player, PlayerAnimation, PlayerBonuses, PlayerState,
 };
 
 pub fn detect_movement_input(
@@ -165,17 +164,17 @@ pub fn handle_movement_input(
 
 pub fn handle_dash_input(
     mut commands: Commands,
+    config: Res<GameConfig>,
+    bonuses: Res<PlayerBonuses>,
     mut transitions: MessageWriter<RequestTransition<PlayerState>>,
     mut events: MessageReader<DashInputDetected>,
 ) {
-    use crate::constants::{DASH_COOLDOWN, DASH_DURATION};
-
     for event in events.read() {
         commands.entity(event.player).insert(PlayerDashing {
             direction: event.direction,
-            timer: DASH_DURATION,
+            timer: config.dash_duration,
         });
-        commands.entity(event.player).insert(DashCooldown { timer: DASH_COOLDOWN });
+        commands.entity(event.player).insert(DashCooldown { timer: config.dash_cooldown * bonuses.dash_cooldown });
         transitions.write(RequestTransition::new(event.player, PlayerState::Dashing));
     }
 }
@@ -184,13 +183,14 @@ pub fn handle_attack_input(
     mut transitions: MessageWriter<RequestTransition<PlayerState>>,
     mut events: MessageReader<AttackInputDetected>,
     mut commands: Commands,
+    config: Res<GameConfig>,
     mut combo_query: Query<(&mut ComboState, &mut FacingDirection), With<Player>>,
 ) {
     for event in events.read() {
         let Ok((mut combo, mut facing)) = combo_query.get_mut(event.player) else { continue };
 
         // Reset combo if timed out
-        if combo.should_reset() {
+        if combo.should_reset(config.combo_timeout) {
             combo.reset();
         }
 
@@ -249,6 +249,7 @@ pub fn on_dashing_exit(
 pub fn on_attacking_windup_enter(
     mut commands: Commands,
     mut events: MessageReader<StateEntered<PlayerState>>,
+    config: Res<GameConfig>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     query: Query<(&Transform, &Children), With<Player>>,
@@ -288,7 +289,7 @@ pub fn on_attacking_windup_enter(
                         duration,
                         base_angle: Some(attack_angle),
                         attack_type: weapon.attack_type,
-                        hit_delay: duration * ATTACK_HIT_DELAY_PERCENT,
+                        hit_delay: duration * config.attack_hit_delay_percent,
                         hit_applied: false,
                     });
                 }
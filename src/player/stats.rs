@@ -1,8 +1,42 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Resource, Default)]
+use crate::constants::PLAYER_HEALTH;
+
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
 pub struct Stats {
     pub philosophy: u32,
     pub nature_study: u32,
     pub wisdom: u32,
 }
+
+/// Permanent bonuses bought with banked resources, applied to every run
+#[derive(Resource, Clone, Debug)]
+pub struct PlayerBonuses {
+    /// Extra health at the start of a run
+    pub max_health: i32,
+    /// Multiplier on the dash cooldown
+    pub dash_cooldown: f32,
+    /// Multiplier on the speed while sprinting
+    pub sprint_speed: f32,
+    /// Added to the blocking weapon's damage reduction
+    pub block_reduction: f32,
+}
+
+impl PlayerBonuses {
+    /// Health at the start of a run and the cap for healing
+    pub fn player_max_health(&self) -> i32 {
+        PLAYER_HEALTH + self.max_health
+    }
+}
+
+impl Default for PlayerBonuses {
+    fn default() -> Self {
+        Self {
+            max_health: 0,
+            dash_cooldown: 1.0,
+            sprint_speed: 1.0,
+            block_reduction: 0.0,
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use bevy::prelude::*;
 
 use crate::constants::{
-    CAMERA_ZOOM_SPEED, DEATH_COLLAPSE_DURATION, DEATH_EXPAND_DURATION, Z_DEAD,
+    DEATH_COLLAPSE_DURATION, DEATH_EXPAND_DURATION, Z_DEAD,
 };
-use crate::core::{Blocking, Dead, DeathAnimation, GameConfig, WalkCollider, StaticCollider, ellipses_overlap, ellipse_push, Health, Knockback};
+use crate::core::{Blocking, Dead, DeathAnimation, GameConfig, WalkCollider, StaticCollider, ellipses_overlap, ellipse_push, Health, Knockback, MaxHealth};
 use crate::effects::{Hitstop, ScreenShake};
 use crate::core::{GameAction, InputBindings};
 use crate::core::CharacterAssets;
@@ -11,12 +11,12 @@ use crate::state_machine::{AttackPhase, RequestTransition, StateMachine};
 use super::{
     Player, PlayerAnimation, DashCooldown, Sprinting, PhaseThrough, MovementInput,
     SpriteAnimation, PlayerSpriteSheet, PlayerState, CameraState, ComboState, FacingDirection,
-    PlayerDashing, PlayerAttacking, PlayerSmashAttack, HurtAnimation,
+    PlayerDashing, PlayerAttacking, PlayerSmashAttack, HurtAnimation, PlayerBonuses,
 };
 use crate::inventory::AttackType;
 use crate::inventory::weapons::{PlayerWeapon, WeaponSwing, Fist};
 use crate::creatures::Creature;
-use crate::levels::CurrentLevel;
+use crate::levels::{CurrentLevel, TriggerAction, TriggerFired};
 
 /// System 1: Read WASD input into MovementInput component
 pub fn read_movement_input(
@@ -69,6 +69,7 @@ pub fn update_sprint_state(
 /// System 3: Apply acceleration/friction to calculate velocity, handle state transitions
 pub fn apply_player_velocity(
     config: Res<GameConfig>,
+    bonuses: Res<PlayerBonuses>,
     time: Res<Time>,
     hitstop: Res<Hitstop>,
     mut transitions: MessageWriter<RequestTransition<PlayerState>>,
@@ -86,7 +87,8 @@ pub fn apply_player_velocity(
     let sprint_multiplier = sprinting
         .map(|s| {
             let t = (s.duration / config.sprint_ramp_time).min(1.0);
-            config.sprint_min_multiplier + t * (config.sprint_max_multiplier - config.sprint_min_multiplier)
+            let multiplier = config.sprint_min_multiplier + t * (config.sprint_max_multiplier - config.sprint_min_multiplier);
+            multiplier * bonuses.sprint_speed
         })
         .unwrap_or(1.0);
 
@@ -287,6 +289,18 @@ pub fn tick_dash_cooldown(
     }
 }
 
+/// Keep the player's health cap in step with the Max health upgrade
+pub fn apply_max_health_bonus(
+    bonuses: Res<PlayerBonuses>,
+    mut query: Query<(&mut MaxHealth, Ref<Player>)>,
+) {
+    for (mut max_health, player) in &mut query {
+        if bonuses.is_changed() || player.is_added() {
+            max_health.0 = bonuses.player_max_health();
+        }
+    }
+}
+
 /// Tick combo timer and reset combo if timed out
 pub fn tick_combo_timer(
     time: Res<Time>,
@@ -660,6 +674,7 @@ pub fn animate_sprites(
 
 pub fn camera_follow(
     time: Res<Time>,
+    config: Res<GameConfig>,
     player_query: Query<(&Transform, &PlayerAnimation), With<Player>>,
     mut camera_query: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
     mut camera_state: ResMut<CameraState>,
@@ -671,12 +686,12 @@ pub fn camera_follow(
 
     // Update zoom based on player velocity
     let is_moving = player_anim.velocity.length() > 1.0;
-    camera_state.set_moving(is_moving);
+    camera_state.set_moving(is_moving, &config);
 
     // Smooth lerp toward target zoom
     let dt = time.delta_secs();
     camera_state.current_scale = camera_state.current_scale
-        + (camera_state.target_scale - camera_state.current_scale) * CAMERA_ZOOM_SPEED * dt;
+        + (camera_state.target_scale - camera_state.current_scale) * config.camera_zoom_speed * dt;
 
     // Calculate base camera position from player
     let mut camera_x = player_transform.translation.x;
@@ -712,3 +727,21 @@ pub fn camera_follow(
     // Apply zoom
     camera_transform.scale = Vec3::splat(camera_state.current_scale);
 }
+
+/// Apply camera zoom actions from fired level triggers
+pub fn apply_trigger_camera(
+    mut fired: MessageReader<TriggerFired>,
+    current_level: Res<CurrentLevel>,
+    mut camera_state: ResMut<CameraState>,
+) {
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+        for action in &volume.actions {
+            match action {
+                TriggerAction::CameraZoom { scale } => camera_state.zoom = *scale,
+                TriggerAction::CameraReset => camera_state.zoom = 1.0,
+                _ => {}
+            }
+        }
+    }
+}
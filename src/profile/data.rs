@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::Difficulty;
+use crate::inventory::ItemId;
+use crate::player::{PlayerBonuses, Stats};
+use crate::world::WorldConfig;
+
+/// Profile file holding banked resources and bought upgrades
+pub const PROFILE_FILE: &str = "profile.ron";
+
+/// Extra health per max health level
+const HEALTH_PER_LEVEL: i32 = 2;
+/// Dash cooldown cut per dash level
+const DASH_COOLDOWN_PER_LEVEL: f32 = 0.1;
+/// Sprint speed gained per sprint level
+const SPRINT_SPEED_PER_LEVEL: f32 = 0.05;
+/// Block damage reduction gained per block level
+const BLOCK_REDUCTION_PER_LEVEL: f32 = 0.05;
+
+/// Items added to the default starting kit, one more per starting items level.
+/// Offsets are relative to the spawn.
+const BONUS_STARTING_ITEMS: [(ItemId, u32, Vec2); 3] = [
+    (ItemId::HealthPotion, 1, Vec2::new(-30.0, -25.0)),
+    (ItemId::Mushroom, 2, Vec2::new(0.0, 40.0)),
+    (ItemId::HealthPotion, 1, Vec2::new(-60.0, -5.0)),
+];
+
+/// One of the three resources collected from creatures
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Currency {
+    Philosophy,
+    NatureStudy,
+    Wisdom,
+}
+
+impl Currency {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Currency::Philosophy => "philosophy",
+            Currency::NatureStudy => "nature study",
+            Currency::Wisdom => "wisdom",
+        }
+    }
+
+    fn balance(&self, stats: &Stats) -> u32 {
+        match self {
+            Currency::Philosophy => stats.philosophy,
+            Currency::NatureStudy => stats.nature_study,
+            Currency::Wisdom => stats.wisdom,
+        }
+    }
+
+    fn balance_mut<'a>(&self, stats: &'a mut Stats) -> &'a mut u32 {
+        match self {
+            Currency::Philosophy => &mut stats.philosophy,
+            Currency::NatureStudy => &mut stats.nature_study,
+            Currency::Wisdom => &mut stats.wisdom,
+        }
+    }
+}
+
+/// Permanent upgrade bought in the upgrade screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Upgrade {
+    MaxHealth,
+    DashCooldown,
+    SprintSpeed,
+    BlockReduction,
+    StartingItems,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 5] = [
+        Upgrade::MaxHealth,
+        Upgrade::DashCooldown,
+        Upgrade::SprintSpeed,
+        Upgrade::BlockReduction,
+        Upgrade::StartingItems,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::MaxHealth => "Max health",
+            Upgrade::DashCooldown => "Dash cooldown",
+            Upgrade::SprintSpeed => "Sprint speed",
+            Upgrade::BlockReduction => "Block reduction",
+            Upgrade::StartingItems => "Starting items",
+        }
+    }
+
+    pub fn currency(&self) -> Currency {
+        match self {
+            Upgrade::MaxHealth | Upgrade::SprintSpeed => Currency::NatureStudy,
+            Upgrade::DashCooldown => Currency::Philosophy,
+            Upgrade::BlockReduction | Upgrade::StartingItems => Currency::Wisdom,
+        }
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Upgrade::StartingItems => BONUS_STARTING_ITEMS.len() as u32,
+            _ => 5,
+        }
+    }
+
+    /// Price of going from `level` to the next one
+    fn cost(&self, level: u32) -> u32 {
+        3 + level * 2
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: ron::error::SpannedError },
+    Serialize { path: String, error: ron::Error },
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Io { path, error } => write!(f, "Failed to access profile file {}: {}", path, error),
+            ProfileError::Parse { path, error } => write!(f, "Failed to parse profile file {}: {}", path, error),
+            ProfileError::Serialize { path, error } => write!(f, "Failed to serialize profile for {}: {}", path, error),
+        }
+    }
+}
+
+/// Part of the run's `Stats` already added to the profile bank. Saving banks the run so far,
+/// so a run continued from a save only banks what it collects after loading.
+#[derive(Resource, Clone, Default)]
+pub struct BankedStats(pub Stats);
+
+impl BankedStats {
+    /// Resources in `stats` that were not banked yet; they count as banked from now on
+    pub fn take_new(&mut self, stats: &Stats) -> Stats {
+        let new = Stats {
+            philosophy: stats.philosophy.saturating_sub(self.0.philosophy),
+            nature_study: stats.nature_study.saturating_sub(self.0.nature_study),
+            wisdom: stats.wisdom.saturating_sub(self.0.wisdom),
+        };
+        self.0 = stats.clone();
+        new
+    }
+}
+
+/// Progress kept between runs
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
+pub struct Profile {
+    /// Resources banked at the end of runs and not spent yet
+    pub bank: Stats,
+    /// Level of each bought upgrade; missing upgrades are level 0
+    #[serde(default)]
+    pub upgrades: BTreeMap<Upgrade, u32>,
+    /// Difficulty picked in the menu
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+impl Profile {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        self.upgrades.get(&upgrade).copied().unwrap_or(0)
+    }
+
+    /// Price of the next level, or None when the upgrade is maxed out
+    pub fn next_cost(&self, upgrade: Upgrade) -> Option<u32> {
+        let level = self.level(upgrade);
+        (level < upgrade.max_level()).then(|| upgrade.cost(level))
+    }
+
+    pub fn can_afford(&self, upgrade: Upgrade) -> bool {
+        self.next_cost(upgrade).is_some_and(|cost| upgrade.currency().balance(&self.bank) >= cost)
+    }
+
+    pub fn balance(&self, currency: Currency) -> u32 {
+        currency.balance(&self.bank)
+    }
+
+    /// Pay for the next level of `upgrade`. Returns false if it is maxed out or too expensive.
+    pub fn purchase(&mut self, upgrade: Upgrade) -> bool {
+        if !self.can_afford(upgrade) {
+            return false;
+        }
+        let Some(cost) = self.next_cost(upgrade) else { return false };
+        *upgrade.currency().balance_mut(&mut self.bank) -= cost;
+        *self.upgrades.entry(upgrade).or_insert(0) += 1;
+        true
+    }
+
+    /// Add what a run collected to the bank
+    pub fn bank_run(&mut self, stats: &Stats) {
+        self.bank.philosophy += stats.philosophy;
+        self.bank.nature_study += stats.nature_study;
+        self.bank.wisdom += stats.wisdom;
+    }
+
+    pub fn player_bonuses(&self) -> PlayerBonuses {
+        PlayerBonuses {
+            max_health: self.level(Upgrade::MaxHealth) as i32 * HEALTH_PER_LEVEL,
+            dash_cooldown: 1.0 - self.level(Upgrade::DashCooldown) as f32 * DASH_COOLDOWN_PER_LEVEL,
+            sprint_speed: 1.0 + self.level(Upgrade::SprintSpeed) as f32 * SPRINT_SPEED_PER_LEVEL,
+            block_reduction: self.level(Upgrade::BlockReduction) as f32 * BLOCK_REDUCTION_PER_LEVEL,
+        }
+    }
+
+    /// Default starting kit plus the bonus items unlocked so far,
+    /// as (item, quantity, offset from the player spawn)
+    pub fn starting_items(&self) -> Vec<(ItemId, u32, Vec2)> {
+        let unlocked = (self.level(Upgrade::StartingItems) as usize).min(BONUS_STARTING_ITEMS.len());
+        let mut items = WorldConfig::default().starting_items;
+        items.extend_from_slice(&BONUS_STARTING_ITEMS[..unlocked]);
+        items
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, ProfileError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| ProfileError::Io { path: path.to_string(), error })?;
+        ron::from_str(&contents)
+            .map_err(|error| ProfileError::Parse { path: path.to_string(), error })
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), ProfileError> {
+        let config = ron::ser::PrettyConfig::new().indentor("    ".to_string());
+        let contents = ron::ser::to_string_pretty(self, config)
+            .map_err(|error| ProfileError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| ProfileError::Io { path: path.to_string(), error })
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+use super::Upgrade;
+
+/// Request to buy the next level of an upgrade with banked resources
+#[derive(Event, Message, Debug)]
+pub struct PurchaseUpgrade {
+    pub upgrade: Upgrade,
+}
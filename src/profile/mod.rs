@@ -0,0 +1,31 @@
+mod data;
+mod events;
+pub mod systems;
+
+pub use data::{BankedStats, Currency, Profile, ProfileError, Upgrade, PROFILE_FILE};
+pub use events::PurchaseUpgrade;
+
+use bevy::prelude::*;
+
+use crate::core::GameState;
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .init_resource::<BankedStats>()
+            .add_message::<PurchaseUpgrade>()
+            .add_systems(Startup, (systems::load_profile, systems::apply_profile_upgrades).chain())
+            .add_systems(OnEnter(GameState::Dead), systems::bank_run_resources)
+            .add_systems(OnEnter(GameState::Victory), systems::bank_run_resources)
+            .add_systems(
+                Update,
+                (
+                    systems::purchase_upgrades,
+                    systems::apply_profile_upgrades.after(systems::purchase_upgrades),
+                    systems::store_difficulty,
+                ),
+            );
+    }
+}
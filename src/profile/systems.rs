@@ -0,0 +1,70 @@
+use std::io::ErrorKind;
+
+use bevy::prelude::*;
+
+use crate::core::Difficulty;
+use crate::player::{PlayerBonuses, Stats};
+use crate::world::WorldConfig;
+use super::{BankedStats, Profile, ProfileError, PurchaseUpgrade, PROFILE_FILE};
+
+fn store(profile: &Profile) {
+    if let Err(error) = profile.save_to_file(PROFILE_FILE) {
+        error!("{}", error);
+    }
+}
+
+/// Read the profile at startup. Without a profile file every upgrade starts at level 0.
+pub fn load_profile(mut profile: ResMut<Profile>) {
+    match Profile::load_from_file(PROFILE_FILE) {
+        Ok(loaded) => *profile = loaded,
+        Err(ProfileError::Io { error, .. }) if error.kind() == ErrorKind::NotFound => {}
+        Err(error) => error!("{}", error),
+    }
+}
+
+/// Keep the player bonuses and starting items in step with the bought upgrades
+pub fn apply_profile_upgrades(
+    profile: Res<Profile>,
+    mut bonuses: ResMut<PlayerBonuses>,
+    mut world_config: ResMut<WorldConfig>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+    *bonuses = profile.player_bonuses();
+    world_config.starting_items = profile.starting_items();
+}
+
+/// Bank what the run collected since it was last banked and write the profile
+pub fn bank_new_resources(stats: &Stats, banked: &mut BankedStats, profile: &mut Profile) {
+    let new = banked.take_new(stats);
+    profile.bank_run(&new);
+    store(profile);
+    info!(
+        "Banked {} philosophy, {} nature study and {} wisdom",
+        new.philosophy, new.nature_study, new.wisdom
+    );
+}
+
+/// Bank what the run collected when it ends in death or victory
+pub fn bank_run_resources(stats: Res<Stats>, mut banked: ResMut<BankedStats>, mut profile: ResMut<Profile>) {
+    bank_new_resources(&stats, &mut banked, &mut profile);
+}
+
+/// Remember the difficulty picked in the menu for the next session
+pub fn store_difficulty(difficulty: Res<Difficulty>, mut profile: ResMut<Profile>) {
+    if !difficulty.is_changed() || profile.difficulty == *difficulty {
+        return;
+    }
+    profile.difficulty = *difficulty;
+    store(&profile);
+}
+
+pub fn purchase_upgrades(mut requests: MessageReader<PurchaseUpgrade>, mut profile: ResMut<Profile>) {
+    for request in requests.read() {
+        if profile.purchase(request.upgrade) {
+            info!("Bought {} level {}", request.upgrade.name(), profile.level(request.upgrade));
+            store(&profile);
+        }
+    }
+}
@@ -1,8 +1,7 @@
 use bevy::prelude::*;
-use rand::Rng;
 
 use crate::core::{StaticCollider, YSorted};
-use crate::constants::{COLLISION_RADIUS, GRID_SPACING, WORLD_SIZE, Z_SHADOW_OFFSET};
+use crate::constants::Z_SHADOW_OFFSET;
 use super::components::{BarrelSprite, CrateSprite, Crate2Sprite, Destructible, Prop};
 use super::data::{BarrelSprites, CrateSprites, Crate2Sprites, PropDefinition, PropRegistry, PropType};
 
@@ -11,7 +10,7 @@ pub fn spawn_prop(
     commands: &mut Commands,
     definition: &PropDefinition,
     position: Vec2,
-) {
+) -> Entity {
     let visual = &definition.visual;
 
     let mut entity = commands.spawn((
@@ -59,7 +58,7 @@ pub fn spawn_prop(
                     .with_rotation(Quat::from_rotation_z(detail.rotation)),
             ));
         }
-    });
+    }).id()
 }
 
 /// Spawns a sprite-based crate
@@ -68,10 +67,10 @@ pub fn spawn_crate(
     crate_sprites: &CrateSprites,
     registry: &PropRegistry,
     position: Vec2,
-) {
-    let Some(definition) = registry.get(PropType::Crate) else { return };
+) -> Option<Entity> {
+    let definition = registry.get(PropType::Crate)?;
 
-    commands.spawn((
+    let entity = commands.spawn((
         Prop { prop_type: PropType::Crate },
         CrateSprite { damaged: false },
         Destructible { health: 2 },
@@ -91,7 +90,9 @@ pub fn spawn_crate(
             ..default()
         },
         Transform::from_xyz(position.x, position.y, 0.0),
-    ));
+    )).id();
+
+    Some(entity)
 }
 
 /// Spawns a sprite-based crate2
@@ -100,10 +101,10 @@ pub fn spawn_crate2(
     crate2_sprites: &Crate2Sprites,
     registry: &PropRegistry,
     position: Vec2,
-) {
-    let Some(definition) = registry.get(PropType::Crate2) else { return };
+) -> Option<Entity> {
+    let definition = registry.get(PropType::Crate2)?;
 
-    commands.spawn((
+    let entity = commands.spawn((
         Prop { prop_type: PropType::Crate2 },
         Crate2Sprite { damaged: false },
         Destructible { health: 2 },
@@ -124,7 +125,9 @@ pub fn spawn_crate2(
         },
         Transform::from_xyz(position.x, position.y, 0.0)
             .with_scale(Vec3::splat(2.0)),
-    ));
+    )).id();
+
+    Some(entity)
 }
 
 /// Spawns a sprite-based barrel
@@ -133,10 +136,10 @@ pub fn spawn_barrel(
     barrel_sprites: &BarrelSprites,
     registry: &PropRegistry,
     position: Vec2,
-) {
-    let Some(definition) = registry.get(PropType::Barrel) else { return };
+) -> Option<Entity> {
+    let definition = registry.get(PropType::Barrel)?;
 
-    commands.spawn((
+    let entity = commands.spawn((
         Prop { prop_type: PropType::Barrel },
         BarrelSprite { damaged: false },
         Destructible { health: 2 },
@@ -157,76 +160,17 @@ pub fn spawn_barrel(
         },
         Transform::from_xyz(position.x, position.y, 0.0)
             .with_scale(Vec3::splat(2.0)),
-    ));
+    )).id();
+
+    Some(entity)
 }
 
 pub fn spawn_pillar(
     commands: &mut Commands,
     registry: &PropRegistry,
     position: Vec2,
-) {
-    let Some(definition) = registry.get(PropType::Pillar) else { return };
-    spawn_prop(commands, definition, position);
+) -> Option<Entity> {
+    let definition = registry.get(PropType::Pillar)?;
+    Some(spawn_prop(commands, definition, position))
 }
 
-pub fn spawn_world_props(
-    commands: &mut Commands,
-    registry: &PropRegistry,
-    crate_sprites: &CrateSprites,
-    crate2_sprites: &Crate2Sprites,
-    barrel_sprites: &BarrelSprites,
-) {
-    let mut rng = rand::rng();
-    let world_size = WORLD_SIZE as f32 * GRID_SPACING;
-    let min_distance = COLLISION_RADIUS * 4.0;
-    let mut positions: Vec<Vec2> = Vec::new();
-
-    let prop_count = 20;
-
-    for _ in 0..prop_count * 3 {
-        if positions.len() >= prop_count {
-            break;
-        }
-
-        let x = rng.random_range(-world_size..world_size);
-        let y = rng.random_range(-world_size..world_size);
-
-        if x.abs() < COLLISION_RADIUS * 6.0 && y.abs() < COLLISION_RADIUS * 6.0 {
-            continue;
-        }
-
-        let pos = Vec2::new(x, y);
-        let too_close = positions.iter().any(|p| p.distance(pos) < min_distance);
-        if too_close {
-            continue;
-        }
-
-        positions.push(pos);
-
-        // Random prop type
-        let prop_type = match rng.random_range(0..5) {
-            0 => PropType::Pillar,
-            1 => PropType::Barrel,
-            2 => PropType::Crate,
-            3 => PropType::Crate2,
-            _ => PropType::Pillar,
-        };
-
-        match prop_type {
-            PropType::Crate => {
-                spawn_crate(commands, crate_sprites, registry, pos);
-            }
-            PropType::Crate2 => {
-                spawn_crate2(commands, crate2_sprites, registry, pos);
-            }
-            PropType::Barrel => {
-                spawn_barrel(commands, barrel_sprites, registry, pos);
-            }
-            _ => {
-                if let Some(definition) = registry.get(prop_type) {
-                    spawn_prop(commands, definition, pos);
-                }
-            }
-        }
-    }
-}
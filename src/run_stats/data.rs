@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::ItemId;
+use crate::player::Stats;
+
+/// Folder the statistics of every finished run are exported to
+pub const RUN_STATS_DIR: &str = "run_stats";
+
+/// How a run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RunOutcome {
+    Died,
+    Victory,
+    /// A new game was started from the pause menu
+    Abandoned,
+}
+
+impl RunOutcome {
+    pub fn title(&self) -> &'static str {
+        match self {
+            RunOutcome::Died => "RUN OVER",
+            RunOutcome::Victory => "VICTORY",
+            RunOutcome::Abandoned => "RUN ABANDONED",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RunStatsError {
+    Io { path: String, error: std::io::Error },
+    Serialize { path: String, error: serde_json::Error },
+}
+
+impl fmt::Display for RunStatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunStatsError::Io { path, error } => write!(f, "Failed to write run statistics {}: {}", path, error),
+            RunStatsError::Serialize { path, error } => write!(f, "Failed to serialize run statistics for {}: {}", path, error),
+        }
+    }
+}
+
+/// Run totals when the current level started, to measure that level on its own
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct LevelStart {
+    pub time: f32,
+    pub damage_taken: i32,
+    pub kills: u32,
+    pub resources: Stats,
+}
+
+/// Statistics of the current run, gathered from combat and inventory events
+#[derive(Resource, Clone, Default, Deserialize, Serialize)]
+pub struct RunStats {
+    /// Set once the run is over
+    #[serde(default)]
+    pub outcome: Option<RunOutcome>,
+    /// Name of the level the run ended on
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Seconds spent playing, pauses excluded
+    pub time: f32,
+    /// Kills by creature type
+    pub kills: BTreeMap<String, u32>,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub hits_blocked: u32,
+    pub dashes: u32,
+    pub items_picked_up: BTreeMap<ItemId, u32>,
+    pub items_used: BTreeMap<ItemId, u32>,
+    /// Resources collected, filled in when the run ends
+    pub resources: Stats,
+    #[serde(default)]
+    pub level_start: LevelStart,
+}
+
+impl RunStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    /// Mark the start of the next level; `resources` is what the run has collected so far
+    pub fn start_level(&mut self, resources: &Stats) {
+        self.level_start = LevelStart {
+            time: self.time,
+            damage_taken: self.damage_taken,
+            kills: self.total_kills(),
+            resources: resources.clone(),
+        };
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), RunStatsError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|error| RunStatsError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| RunStatsError::Io { path: path.to_string(), error })
+    }
+}
+
+/// Run time as minutes and seconds, e.g. "3:07"
+pub fn format_run_time(seconds: f32) -> String {
+    let whole = seconds.max(0.0) as u32;
+    format!("{}:{:02}", whole / 60, whole % 60)
+}
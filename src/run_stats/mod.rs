@@ -0,0 +1,25 @@
+mod data;
+pub mod systems;
+
+pub use data::{format_run_time, RunOutcome, RunStats, RUN_STATS_DIR};
+
+use bevy::prelude::*;
+
+use crate::core::GameState;
+
+pub struct RunStatsPlugin;
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(Update, systems::track_run_time.run_if(in_state(GameState::Playing)))
+            // After Update so hits landing on the frame the run ends are still counted
+            .add_systems(PostUpdate, (systems::record_combat_events, systems::record_item_events))
+            .add_systems(OnEnter(GameState::LevelComplete), systems::start_next_level)
+            .add_systems(OnEnter(GameState::Dead), systems::finish_run)
+            .add_systems(OnEnter(GameState::Victory), systems::finish_run)
+            .add_systems(OnExit(GameState::Dead), systems::reset_run_stats)
+            .add_systems(OnExit(GameState::Victory), systems::reset_run_stats)
+            .add_systems(OnExit(GameState::LevelError), systems::reset_run_stats);
+    }
+}
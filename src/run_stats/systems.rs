@@ -0,0 +1,93 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+use crate::combat::{DamageDealt, PlayerDamaged};
+use crate::core::GameState;
+use crate::creatures::CreatureKilled;
+use crate::inventory::{ItemPickedUp, ItemUsed};
+use crate::levels::CurrentLevel;
+use crate::player::{DashInputDetected, Stats};
+use crate::world::NewGameRequested;
+use super::{RunOutcome, RunStats, RUN_STATS_DIR};
+
+/// Count the time spent playing
+pub fn track_run_time(time: Res<Time>, mut run_stats: ResMut<RunStats>) {
+    run_stats.time += time.delta_secs();
+}
+
+/// Count kills, damage, blocks and dashes
+pub fn record_combat_events(
+    mut run_stats: ResMut<RunStats>,
+    mut damage_dealt: MessageReader<DamageDealt>,
+    mut player_damaged: MessageReader<PlayerDamaged>,
+    mut killed: MessageReader<CreatureKilled>,
+    mut dashes: MessageReader<DashInputDetected>,
+) {
+    for event in damage_dealt.read() {
+        run_stats.damage_dealt += event.amount;
+    }
+    for event in player_damaged.read() {
+        run_stats.damage_taken += event.amount;
+        if event.blocked {
+            run_stats.hits_blocked += 1;
+        }
+    }
+    for event in killed.read() {
+        *run_stats.kills.entry(event.kind.clone()).or_insert(0) += 1;
+    }
+    run_stats.dashes += dashes.read().count() as u32;
+}
+
+/// Count items picked up and used
+pub fn record_item_events(
+    mut run_stats: ResMut<RunStats>,
+    mut picked_up: MessageReader<ItemPickedUp>,
+    mut used: MessageReader<ItemUsed>,
+) {
+    for event in picked_up.read() {
+        *run_stats.items_picked_up.entry(event.item_id).or_insert(0) += event.quantity;
+    }
+    for event in used.read() {
+        *run_stats.items_used.entry(event.item_id).or_insert(0) += 1;
+    }
+}
+
+/// Close the run when it ends in death, victory or a new game from the pause menu,
+/// and export its statistics for balancing
+pub fn finish_run(
+    game_state: Res<State<GameState>>,
+    new_game_requested: Res<NewGameRequested>,
+    current_level: Res<CurrentLevel>,
+    stats: Res<Stats>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    run_stats.outcome = Some(match game_state.get() {
+        GameState::Victory => RunOutcome::Victory,
+        _ if new_game_requested.0 => RunOutcome::Abandoned,
+        _ => RunOutcome::Died,
+    });
+    run_stats.level = current_level.data.as_ref().map(|level| level.name.clone());
+    run_stats.resources = stats.clone();
+
+    if let Err(error) = std::fs::create_dir_all(RUN_STATS_DIR) {
+        error!("Failed to create {}: {}", RUN_STATS_DIR, error);
+        return;
+    }
+    let finished_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = format!("{}/run-{}.json", RUN_STATS_DIR, finished_at);
+    match run_stats.save_to_file(&path) {
+        Ok(()) => info!("Exported run statistics to {}", path),
+        Err(error) => error!("{}", error),
+    }
+}
+
+/// Measure the next campaign level from here
+pub fn start_next_level(stats: Res<Stats>, mut run_stats: ResMut<RunStats>) {
+    run_stats.start_level(&stats);
+}
+
+/// Start counting from zero for the next run
+pub fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = RunStats::default();
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::{Inventory, ItemId};
+use crate::levels::{CheckpointSnapshot, TriggerVolumeState, WaveSpawnState};
+use crate::player::Stats;
+use crate::run_stats::RunStats;
+
+/// Save file written by the pause menu and the quick-save key
+pub const SAVE_FILE: &str = "save.ron";
+
+/// What a creature was doing when the game was saved
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SavedCreatureState {
+    /// Patrolling or alert; alert creatures come back with their awareness
+    Patrol,
+    /// Chasing or fighting the player
+    Chase,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedCreature {
+    /// Index into `LevelData::creatures`; None for creatures spawned by waves or triggers, which are all goblins
+    #[serde(default)]
+    pub spawn_index: Option<usize>,
+    pub position: Vec2,
+    pub health: i32,
+    pub state: SavedCreatureState,
+    #[serde(default)]
+    pub awareness: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedGroundItem {
+    pub item_id: ItemId,
+    pub quantity: u32,
+    pub position: Vec2,
+}
+
+/// A game in progress, as written to a save file
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SaveGame {
+    /// Index into `Campaign::levels`
+    pub campaign_level: usize,
+    /// Level file being played; None for generated levels, which are rebuilt from the campaign entry
+    pub level_path: Option<String>,
+    pub player_position: Vec2,
+    pub health: i32,
+    pub inventory: Inventory,
+    pub selected_slot: Option<usize>,
+    pub stats: Stats,
+    /// Part of `stats` already in the profile bank, so loading does not bank it a second time
+    #[serde(default)]
+    pub banked: Stats,
+    /// Creatures still alive
+    pub creatures: Vec<SavedCreature>,
+    /// Indices into `LevelData::props` of props already destroyed
+    pub destroyed_props: Vec<usize>,
+    pub ground_items: Vec<SavedGroundItem>,
+    /// Names of the doors open at the time of saving
+    #[serde(default)]
+    pub open_doors: Vec<String>,
+    /// Names of the areas locked at the time of saving
+    #[serde(default)]
+    pub locked_areas: Vec<String>,
+    #[serde(default)]
+    pub waves: WaveSpawnState,
+    #[serde(default)]
+    pub triggers: TriggerVolumeState,
+    /// Last checkpoint reached in the level, to respawn at after loading
+    #[serde(default)]
+    pub checkpoint: Option<CheckpointSnapshot>,
+    /// Respawns already used in the level, counted against its lives
+    #[serde(default)]
+    pub respawns: u32,
+    /// Seconds since the level started, for survive objectives
+    #[serde(default)]
+    pub elapsed: f32,
+    /// Seconds the player had held the win zone
+    #[serde(default)]
+    pub zone_time: f32,
+    /// Statistics of the run so far, so they carry on after loading
+    #[serde(default)]
+    pub run_stats: RunStats,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io { path: String, error: std::io::Error },
+    Parse { path: String, error: String },
+    Serialize { path: String, error: String },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io { path, error } => write!(f, "Failed to access save file {}: {}", path, error),
+            SaveError::Parse { path, error } => write!(f, "Failed to parse save file {}: {}", path, error),
+            SaveError::Serialize { path, error } => write!(f, "Failed to serialize game for {}: {}", path, error),
+        }
+    }
+}
+
+/// Save files ending in .json are JSON; anything else is RON
+fn is_json(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+impl SaveGame {
+    pub fn load_from_file(path: &str) -> Result<Self, SaveError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| SaveError::Io { path: path.to_string(), error })?;
+        let parsed = if is_json(path) {
+            serde_json::from_str(&contents).map_err(|error| error.to_string())
+        } else {
+            ron::from_str(&contents).map_err(|error| error.to_string())
+        };
+        parsed.map_err(|error| SaveError::Parse { path: path.to_string(), error })
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), SaveError> {
+        let contents = if is_json(path) {
+            serde_json::to_string_pretty(self).map_err(|error| error.to_string())
+        } else {
+            let config = ron::ser::PrettyConfig::new().indentor("    ".to_string());
+            ron::ser::to_string_pretty(self, config).map_err(|error| error.to_string())
+        };
+        let contents = contents.map_err(|error| SaveError::Serialize { path: path.to_string(), error })?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|error| SaveError::Io { path: path.to_string(), error })
+    }
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+/// Request to write the game to the save file, from the pause menu or the quick-save key
+#[derive(Event, Message, Debug)]
+pub struct SaveGameRequested;
+
+/// Request to read the save file and continue from it
+#[derive(Event, Message, Debug)]
+pub struct LoadGameRequested;
+
+/// Outcome of a save or load, shown to the player
+#[derive(Event, Message, Debug)]
+pub struct SaveNotice {
+    pub message: String,
+}
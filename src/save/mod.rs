@@ -0,0 +1,30 @@
+mod data;
+mod events;
+pub mod systems;
+
+pub use data::{SaveError, SaveGame, SavedCreature, SavedCreatureState, SavedGroundItem, SAVE_FILE};
+pub use events::{LoadGameRequested, SaveGameRequested, SaveNotice};
+pub use systems::{PendingLoad, SavedRun};
+
+use bevy::prelude::*;
+
+use crate::core::GameState;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingLoad>()
+            .add_message::<SaveGameRequested>()
+            .add_message::<LoadGameRequested>()
+            .add_message::<SaveNotice>()
+            .add_systems(Update, systems::quick_save.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                Update,
+                (
+                    systems::save_game.after(systems::quick_save),
+                    systems::load_game,
+                ),
+            );
+    }
+}
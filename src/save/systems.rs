@@ -0,0 +1,174 @@
+use std::io::ErrorKind;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::core::{Dead, DeathAnimation, GameAction, GameState, Health, InputBindings};
+use crate::creatures::{Awareness, Creature, CreatureState};
+use crate::inventory::{GroundItem, Inventory, SelectedHotbarSlot};
+use crate::levels::{LevelProgress, LevelSpawnIndex};
+use crate::player::{Player, Stats};
+use crate::profile::systems::bank_new_resources;
+use crate::profile::{BankedStats, Profile};
+use crate::props::Prop;
+use crate::run_stats::RunStats;
+use crate::state_machine::StateMachine;
+use super::{
+    LoadGameRequested, SaveError, SaveGame, SaveGameRequested, SaveNotice, SavedCreature, SavedCreatureState,
+    SavedGroundItem, SAVE_FILE,
+};
+
+type LivingPlayer = (With<Player>, Without<Dead>);
+type LivingCreature = (With<Creature>, Without<Dead>, Without<DeathAnimation>);
+type CreatureSaveData = (
+    &'static Transform,
+    &'static Health,
+    Option<&'static LevelSpawnIndex>,
+    &'static StateMachine<CreatureState>,
+    Option<&'static Awareness>,
+);
+
+/// Run stats, banked resources and the selected hotbar slot carried by a save file
+#[derive(SystemParam)]
+pub struct SavedRun<'w> {
+    pub stats: ResMut<'w, Stats>,
+    pub banked: ResMut<'w, BankedStats>,
+    pub run_stats: ResMut<'w, RunStats>,
+    pub selected_slot: ResMut<'w, SelectedHotbarSlot>,
+}
+
+/// Level content written to a save file
+#[derive(SystemParam)]
+pub struct SavedContentQuery<'w, 's> {
+    player: Query<'w, 's, (&'static Transform, &'static Health, &'static Inventory), LivingPlayer>,
+    creatures: Query<'w, 's, CreatureSaveData, LivingCreature>,
+    props: Query<'w, 's, &'static LevelSpawnIndex, With<Prop>>,
+    ground_items: Query<'w, 's, (&'static Transform, &'static GroundItem)>,
+}
+
+/// A save file read by the load menu entry, waiting for the level to be rebuilt from it
+#[derive(Resource, Default)]
+pub struct PendingLoad(pub Option<SaveGame>);
+
+/// Ask for a save when the quick-save key is pressed
+pub fn quick_save(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    bindings: Res<InputBindings>,
+    mut requests: MessageWriter<SaveGameRequested>,
+) {
+    if bindings.just_pressed(GameAction::QuickSave, &keyboard, &mouse) {
+        requests.write(SaveGameRequested);
+    }
+}
+
+/// Write the current level, player and creatures to the save file
+pub fn save_game(
+    mut requests: MessageReader<SaveGameRequested>,
+    mut notices: MessageWriter<SaveNotice>,
+    progress: LevelProgress,
+    mut run: SavedRun,
+    mut profile: ResMut<Profile>,
+    content: SavedContentQuery,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+    let (Some(level), Ok((player_transform, health, inventory))) = (progress.current_level.data.as_ref(), content.player.single()) else {
+        notices.write(SaveNotice { message: "Nothing to save".to_string() });
+        return;
+    };
+
+    let creatures = content
+        .creatures
+        .iter()
+        .map(|(transform, health, spawn_index, state_machine, awareness)| {
+            let state = match state_machine.current() {
+                CreatureState::Chase | CreatureState::Attack(_) | CreatureState::Cooldown | CreatureState::Stunned => {
+                    SavedCreatureState::Chase
+                }
+                _ => SavedCreatureState::Patrol,
+            };
+            SavedCreature {
+                spawn_index: spawn_index.map(|LevelSpawnIndex(index)| *index),
+                position: transform.translation.truncate(),
+                health: health.0,
+                state,
+                awareness: awareness.map(|a| a.0).unwrap_or(0.0),
+            }
+        })
+        .collect();
+    let standing_props: Vec<usize> = content.props.iter().map(|LevelSpawnIndex(index)| *index).collect();
+
+    let save = SaveGame {
+        campaign_level: progress.campaign.current,
+        level_path: progress.current_level.path.clone(),
+        player_position: player_transform.translation.truncate(),
+        health: health.0,
+        inventory: inventory.clone(),
+        selected_slot: run.selected_slot.0,
+        stats: run.stats.clone(),
+        // The run so far is banked once the file is written
+        banked: run.stats.clone(),
+        creatures,
+        destroyed_props: (0..level.props.len()).filter(|i| !standing_props.contains(i)).collect(),
+        ground_items: content
+            .ground_items
+            .iter()
+            .map(|(transform, item)| SavedGroundItem {
+                item_id: item.item_id,
+                quantity: item.quantity,
+                position: transform.translation.truncate(),
+            })
+            .collect(),
+        open_doors: level.doors.iter().filter(|d| d.open).map(|d| d.name.clone()).collect(),
+        locked_areas: level.areas.iter().filter(|a| a.locked).map(|a| a.name.clone()).collect(),
+        waves: progress.waves.clone(),
+        triggers: progress.triggers.clone(),
+        checkpoint: progress.checkpoint.snapshot.clone(),
+        respawns: progress.checkpoint.respawns,
+        elapsed: progress.objective.elapsed,
+        zone_time: progress.objective.zone_time,
+        run_stats: run.run_stats.clone(),
+    };
+
+    let message = match save.save_to_file(SAVE_FILE) {
+        Ok(()) => {
+            info!("Saved game to {}", SAVE_FILE);
+            let SavedRun { stats, banked, .. } = &mut run;
+            bank_new_resources(stats, banked, &mut profile);
+            "Game saved".to_string()
+        }
+        Err(error) => {
+            error!("{}", error);
+            "Could not save the game".to_string()
+        }
+    };
+    notices.write(SaveNotice { message });
+}
+
+/// Read the save file and hand it to the level rebuild in `GameState::LoadingSave`
+pub fn load_game(
+    mut requests: MessageReader<LoadGameRequested>,
+    mut notices: MessageWriter<SaveNotice>,
+    mut pending: ResMut<PendingLoad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+
+    match SaveGame::load_from_file(SAVE_FILE) {
+        Ok(save) => {
+            info!("Loading game from {}", SAVE_FILE);
+            pending.0 = Some(save);
+            next_state.set(GameState::LoadingSave);
+        }
+        Err(SaveError::Io { error, .. }) if error.kind() == ErrorKind::NotFound => {
+            notices.write(SaveNotice { message: "No saved game".to_string() });
+        }
+        Err(error) => {
+            error!("{}", error);
+            notices.write(SaveNotice { message: "Could not load the saved game".to_string() });
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+use crate::core::GameAction;
+use crate::profile::Upgrade;
+
 // Game Menu components (unified pause/death menu)
 #[derive(Component)]
 pub struct GameMenu;
@@ -7,12 +10,101 @@ pub struct GameMenu;
 #[derive(Component)]
 pub struct MenuTitle;
 
+#[derive(Component)]
+pub struct MenuMessage;
+
 #[derive(Component)]
 pub struct ResumeButton;
 
 #[derive(Component)]
 pub struct MenuNewGameButton;
 
+#[derive(Component)]
+pub struct SaveGameButton;
+
+#[derive(Component)]
+pub struct LoadGameButton;
+
+#[derive(Component)]
+pub struct MenuUpgradesButton;
+
+/// Cycles through the difficulty presets; its label shows the selected one
+#[derive(Component)]
+pub struct DifficultyButton;
+
+#[derive(Component)]
+pub struct MenuControlsButton;
+
+// Controls screen components
+#[derive(Component)]
+pub struct ControlsScreen;
+
+/// Prompt while waiting for a press, or why a binding was refused
+#[derive(Component)]
+pub struct ControlsMessage;
+
+/// Name and bindings of an action
+#[derive(Component)]
+pub struct ControlsRowText(pub GameAction);
+
+/// Binds the next key or mouse press to the action, on top of its current bindings
+#[derive(Component)]
+pub struct ControlsAddButton(pub GameAction);
+
+#[derive(Component)]
+pub struct ControlsClearButton(pub GameAction);
+
+#[derive(Component)]
+pub struct ControlsResetButton;
+
+#[derive(Component)]
+pub struct ControlsBackButton;
+
+/// Action waiting for the next key or mouse press in the controls screen
+#[derive(Resource, Default)]
+pub struct BindingCapture(pub Option<GameAction>);
+
+// Upgrade screen components
+#[derive(Component)]
+pub struct UpgradeScreen;
+
+#[derive(Component)]
+pub struct UpgradeBankText;
+
+/// Name, level and price of an upgrade
+#[derive(Component)]
+pub struct UpgradeRowText(pub Upgrade);
+
+#[derive(Component)]
+pub struct UpgradeBuyButton(pub Upgrade);
+
+#[derive(Component)]
+pub struct UpgradeBackButton;
+
+// Leaderboard shown on the victory screen
+#[derive(Component)]
+pub struct LeaderboardPanel;
+
+#[derive(Component)]
+pub struct LeaderboardTitle;
+
+/// Text of one leaderboard place, 0 being the best
+#[derive(Component)]
+pub struct LeaderboardRow(pub usize);
+
+// Run summary screen components
+#[derive(Component)]
+pub struct RunSummaryScreen;
+
+#[derive(Component)]
+pub struct RunSummaryTitle;
+
+#[derive(Component)]
+pub struct RunSummaryText;
+
+#[derive(Component)]
+pub struct RunSummaryContinueButton;
+
 #[derive(Component)]
 pub struct ExitButton;
 
@@ -82,3 +174,26 @@ pub struct InventorySlotIcon(pub usize);
 
 #[derive(Component)]
 pub struct KeyBindingsPanel;
+
+/// Key column of a key bindings panel line, listing the bindings of its actions
+#[derive(Component)]
+pub struct KeyBindingsKeys(pub &'static [GameAction]);
+
+/// On-screen text shown by a level trigger or checkpoint, removed when its timer runs out
+#[derive(Component)]
+pub struct TriggerPrompt {
+    pub timer: Timer,
+}
+
+/// Config problems found at startup, removed when its timer runs out
+#[derive(Component)]
+pub struct ConfigNotice {
+    pub timer: Timer,
+}
+
+/// Panel listing the level objective and its progress
+#[derive(Component)]
+pub struct ObjectivePanel;
+
+#[derive(Component)]
+pub struct ObjectiveText;
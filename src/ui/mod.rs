@@ -9,16 +9,22 @@ pub use systems::*;
 use bevy::prelude::*;
 
 use crate::core::GameState;
+use crate::leaderboard::systems::record_level_clear;
+use crate::run_stats::systems::finish_run;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<BindingCapture>()
+        .add_systems(Startup, show_config_problems)
+        .add_systems(
             Update,
             (
+                update_config_notice,
                 update_counters,
                 update_hp_text,
+                update_objective_panel,
                 update_weapon_info,
                 stabilize_text_rotation,
                 stabilize_shadow,
@@ -28,13 +34,46 @@ impl Plugin for UiPlugin {
             Update,
             show_death_menu.run_if(in_state(GameState::Playing)),
         )
+        .add_systems(
+            Update,
+            (show_trigger_prompts, show_checkpoint_prompts, update_trigger_prompts).run_if(in_state(GameState::Playing)),
+        )
         .add_systems(
             Update,
             (
                 handle_resume_button,
                 handle_menu_new_game_button,
+                handle_save_button,
+                handle_load_button,
+                handle_upgrades_button,
+                handle_upgrade_back_button,
+                handle_upgrade_buy_buttons,
+                handle_difficulty_button,
+                update_difficulty_button.after(handle_difficulty_button),
+                handle_run_summary_continue,
                 handle_exit_button,
+                show_save_notices,
+                update_upgrade_screen,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                handle_controls_button,
+                handle_controls_back_button,
+                handle_controls_add_buttons,
+                handle_controls_clear_buttons,
+                handle_controls_reset_button,
+                capture_binding.after(toggle_pause_menu).before(handle_controls_add_buttons),
+                update_controls_screen,
+                update_key_bindings_panel,
             ),
-        );
+        )
+        .add_systems(OnEnter(GameState::Dead), show_run_summary.after(finish_run))
+        .add_systems(OnEnter(GameState::Victory), (show_run_summary.after(finish_run), show_leaderboard.after(record_level_clear)))
+        .add_systems(OnExit(GameState::Paused), hide_controls_screen)
+        .add_systems(OnExit(GameState::Dead), (hide_upgrade_screen, hide_run_summary, hide_controls_screen))
+        .add_systems(OnExit(GameState::Victory), (hide_upgrade_screen, hide_run_summary, hide_leaderboard, hide_controls_screen))
+        .add_systems(OnExit(GameState::LevelError), (hide_upgrade_screen, hide_controls_screen));
     }
 }
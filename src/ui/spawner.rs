@@ -1,13 +1,20 @@
 use bevy::prelude::*;
 
 use super::components::*;
+use crate::core::GameAction;
+use crate::leaderboard::LEADERBOARD_SIZE;
+use crate::profile::Upgrade;
 
 pub fn setup_ui(mut commands: Commands) {
     spawn_stat_counters(&mut commands);
     spawn_weapon_info_panel(&mut commands);
+    spawn_objective_panel(&mut commands);
     spawn_hotbar(&mut commands);
     spawn_inventory_panel(&mut commands);
     spawn_game_menu(&mut commands);
+    spawn_upgrade_screen(&mut commands);
+    spawn_controls_screen(&mut commands);
+    spawn_run_summary_screen(&mut commands);
 }
 
 fn spawn_stat_counters(commands: &mut Commands) {
@@ -64,6 +71,44 @@ fn spawn_weapon_info_panel(commands: &mut Commands) {
         });
 }
 
+fn spawn_objective_panel(commands: &mut Commands) {
+    commands
+        .spawn((
+            ObjectivePanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                right: Val::Px(20.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.12, 0.85)),
+            BorderRadius::all(Val::Px(4.0)),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Objective"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.95, 0.85, 0.5)),
+            ));
+            parent.spawn((
+                ObjectiveText,
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+            ));
+        });
+}
+
 fn spawn_hotbar(commands: &mut Commands) {
     commands
         .spawn((
@@ -248,7 +293,7 @@ fn spawn_game_menu(commands: &mut Commands) {
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             flex_direction: FlexDirection::Column,
-            row_gap: Val::Px(30.0),
+            row_gap: Val::Px(16.0),
             position_type: PositionType::Absolute,
             ..default()
         },
@@ -265,12 +310,284 @@ fn spawn_game_menu(commands: &mut Commands) {
             TextColor(Color::srgb(0.9, 0.9, 0.9)),
         ));
 
+        parent.spawn((
+            MenuMessage,
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.6, 0.5)),
+            Node {
+                max_width: Val::Percent(80.0),
+                ..default()
+            },
+        ));
+
+        parent.spawn((
+            LeaderboardPanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            Visibility::Hidden,
+        )).with_children(|panel| {
+            panel.spawn((
+                LeaderboardTitle,
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.75, 0.5)),
+            ));
+            for place in 0..LEADERBOARD_SIZE {
+                panel.spawn((
+                    LeaderboardRow(place),
+                    Text::new(""),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                ));
+            }
+        });
+
         spawn_menu_button(parent, ResumeButton, "RESUME", Color::srgb(0.3, 0.5, 0.3));
         spawn_menu_button(parent, MenuNewGameButton, "NEW GAME", Color::srgb(0.3, 0.3, 0.35));
+        spawn_menu_button(parent, SaveGameButton, "SAVE", Color::srgb(0.3, 0.3, 0.35));
+        spawn_menu_button(parent, LoadGameButton, "LOAD", Color::srgb(0.3, 0.3, 0.35));
+        spawn_menu_button(parent, DifficultyButton, "DIFFICULTY", Color::srgb(0.3, 0.3, 0.35));
+        spawn_menu_button(parent, MenuControlsButton, "CONTROLS", Color::srgb(0.3, 0.3, 0.35));
+        spawn_menu_button(parent, MenuUpgradesButton, "UPGRADES", Color::srgb(0.3, 0.3, 0.5));
         spawn_menu_button(parent, ExitButton, "EXIT", Color::srgb(0.5, 0.3, 0.3));
     });
 }
 
+/// Upgrade screen shown over the menu between runs; texts are filled in by update_upgrade_screen
+fn spawn_upgrade_screen(commands: &mut Commands) {
+    commands.spawn((
+        UpgradeScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+        Visibility::Hidden,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new("UPGRADES"),
+            TextFont {
+                font_size: 48.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+
+        parent.spawn((
+            UpgradeBankText,
+            Text::new(""),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.75, 0.5)),
+        ));
+
+        for upgrade in Upgrade::ALL {
+            parent.spawn(Node {
+                width: Val::Px(560.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            }).with_children(|row| {
+                row.spawn((
+                    UpgradeRowText(upgrade),
+                    Text::new(upgrade.name()),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ));
+                row.spawn((
+                    UpgradeBuyButton(upgrade),
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.5, 0.3)),
+                )).with_children(|btn| {
+                    btn.spawn((
+                        Text::new("BUY"),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+            });
+        }
+
+        spawn_menu_button(parent, UpgradeBackButton, "BACK", Color::srgb(0.3, 0.3, 0.35));
+    });
+}
+
+/// Controls screen shown over the menu; row texts are filled in by update_controls_screen
+fn spawn_controls_screen(commands: &mut Commands) {
+    commands.spawn((
+        ControlsScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+        Visibility::Hidden,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new("CONTROLS"),
+            TextFont {
+                font_size: 40.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+
+        parent.spawn((
+            ControlsMessage,
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.6, 0.5)),
+            Node {
+                min_height: Val::Px(24.0),
+                ..default()
+            },
+        ));
+
+        for action in GameAction::ALL {
+            parent.spawn(Node {
+                width: Val::Px(560.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..default()
+            }).with_children(|row| {
+                row.spawn((
+                    ControlsRowText(action),
+                    Text::new(action.name()),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    Node {
+                        flex_grow: 1.0,
+                        ..default()
+                    },
+                ));
+                spawn_small_button(row, ControlsAddButton(action), "ADD", Color::srgb(0.3, 0.5, 0.3));
+                spawn_small_button(row, ControlsClearButton(action), "CLEAR", Color::srgb(0.5, 0.3, 0.3));
+            });
+        }
+
+        parent.spawn(Node {
+            column_gap: Val::Px(20.0),
+            margin: UiRect::top(Val::Px(12.0)),
+            ..default()
+        }).with_children(|row| {
+            spawn_menu_button(row, ControlsResetButton, "RESET", Color::srgb(0.3, 0.3, 0.35));
+            spawn_menu_button(row, ControlsBackButton, "BACK", Color::srgb(0.3, 0.3, 0.35));
+        });
+    });
+}
+
+fn spawn_small_button<T: Component>(parent: &mut ChildSpawnerCommands, marker: T, text: &str, color: Color) {
+    parent.spawn((
+        marker,
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(color),
+    )).with_children(|btn| {
+        btn.spawn((
+            Text::new(text),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+    });
+}
+
+/// Statistics of the finished run, shown before the menu; texts are filled in by show_run_summary
+fn spawn_run_summary_screen(commands: &mut Commands) {
+    commands.spawn((
+        RunSummaryScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(24.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+        Visibility::Hidden,
+    )).with_children(|parent| {
+        parent.spawn((
+            RunSummaryTitle,
+            Text::new(""),
+            TextFont {
+                font_size: 48.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+
+        parent.spawn((
+            RunSummaryText,
+            Text::new(""),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.85, 0.85, 0.85)),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                max_width: Val::Percent(80.0),
+                ..default()
+            },
+        ));
+
+        spawn_menu_button(parent, RunSummaryContinueButton, "CONTINUE", Color::srgb(0.3, 0.5, 0.3));
+    });
+}
+
 fn spawn_menu_button<T: Component>(parent: &mut ChildSpawnerCommands, marker: T, text: &str, color: Color) {
     parent.spawn((
         marker,
@@ -1,10 +1,32 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 
 use super::components::*;
 use crate::inventory::weapons::{PlayerWeapon, Weapon};
-use crate::core::{Dead, GameAction, GameState, Health, InputBindings, Shadow};
+use crate::inventory::{ItemId, ItemRegistry};
+use crate::core::{
+    ConfigReport, Dead, Difficulty, GameAction, GameConfig, GameState, Health, InputBinding, InputBindings, Shadow,
+    StrictConfig, CONTROLS_FILE,
+};
 use crate::creatures::Creature;
+use crate::levels::{CheckpointReached, CheckpointState, CurrentLevel, ObjectiveState, TriggerAction, TriggerFired};
 use crate::player::{Player, Stats};
+use crate::leaderboard::{LatestClear, Leaderboard};
+use crate::profile::{Currency, Profile, PurchaseUpgrade};
+use crate::run_stats::{format_run_time, RunStats};
+use crate::save::{LoadGameRequested, SaveGameRequested, SaveNotice};
+
+const CHECKPOINT_PROMPT_DURATION: f32 = 2.5;
+const SAVE_PROMPT_DURATION: f32 = 2.0;
+const CONFIG_NOTICE_DURATION: f32 = 10.0;
+/// Problems listed in the config notice; the rest are only logged
+const CONFIG_NOTICE_LINES: usize = 3;
+
+/// Menu buttons that only make sense while a game is paused, not after it ended
+type PauseOnlyButton = (Or<(With<ResumeButton>, With<SaveGameButton>)>, Without<GameMenu>);
+/// Menu buttons that only make sense once a run is over
+type RunOverButton = (With<MenuUpgradesButton>, Without<GameMenu>, Without<ResumeButton>, Without<SaveGameButton>);
 
 pub fn update_counters(
     stats: Res<Stats>,
@@ -23,6 +45,29 @@ pub fn update_counters(
     }
 }
 
+/// Show objective progress while playing. Finished objectives are ticked off.
+pub fn update_objective_panel(
+    objective_state: Res<ObjectiveState>,
+    game_state: Res<State<GameState>>,
+    mut panel_query: Query<&mut Visibility, With<ObjectivePanel>>,
+    mut text_query: Query<&mut Text, With<ObjectiveText>>,
+) {
+    let Ok(mut visibility) = panel_query.single_mut() else { return };
+    let show = matches!(game_state.get(), GameState::Playing | GameState::Paused) && !objective_state.lines.is_empty();
+    visibility.set_if_neq(if show { Visibility::Inherited } else { Visibility::Hidden });
+
+    let Ok(mut text) = text_query.single_mut() else { return };
+    let lines: Vec<String> = objective_state
+        .lines
+        .iter()
+        .map(|line| format!("{}{} {}", "   ".repeat(line.depth), if line.done { "[x]" } else { "[ ]" }, line.text))
+        .collect();
+    let content = lines.join("\n");
+    if text.0 != content {
+        text.0 = content;
+    }
+}
+
 pub fn update_hp_text(
     health_query: Query<(&Health, &Children, Option<&Dead>), Or<(With<Player>, With<Creature>)>>,
     mut text_query: Query<(&mut Text2d, &mut Visibility), With<HpText>>,
@@ -85,9 +130,14 @@ pub fn toggle_pause_menu(
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
     bindings: Res<InputBindings>,
+    capture: Res<BindingCapture>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    // Escape cancels a rebind on the controls screen instead of leaving the menu
+    if capture.0.is_some() {
+        return;
+    }
     if bindings.just_pressed(GameAction::Pause, &keyboard, &mouse) {
         match current_state.get() {
             GameState::Playing => next_state.set(GameState::Paused),
@@ -102,7 +152,8 @@ pub fn show_pause_menu(
     mut menu_query: Query<&mut Visibility, With<GameMenu>>,
     mut title_query: Query<&mut Text, With<MenuTitle>>,
     mut title_color_query: Query<&mut TextColor, With<MenuTitle>>,
-    mut resume_query: Query<&mut Visibility, (With<ResumeButton>, Without<GameMenu>)>,
+    mut pause_only_query: Query<&mut Visibility, PauseOnlyButton>,
+    mut run_over_query: Query<&mut Visibility, RunOverButton>,
 ) {
     if let Ok(mut visibility) = menu_query.single_mut() {
         *visibility = Visibility::Inherited;
@@ -113,29 +164,42 @@ pub fn show_pause_menu(
     if let Ok(mut color) = title_color_query.single_mut() {
         *color = TextColor(Color::srgb(0.9, 0.9, 0.9));
     }
-    if let Ok(mut visibility) = resume_query.single_mut() {
+    for mut visibility in &mut pause_only_query {
         *visibility = Visibility::Inherited;
     }
+    for mut visibility in &mut run_over_query {
+        *visibility = Visibility::Hidden;
+    }
 }
 
-// Hide menu when exiting Paused state
+// Hide menu and clear any save or load message when exiting Paused state
 pub fn hide_pause_menu(
     mut menu_query: Query<&mut Visibility, With<GameMenu>>,
+    mut message_query: Query<&mut Text, With<MenuMessage>>,
 ) {
     if let Ok(mut visibility) = menu_query.single_mut() {
         *visibility = Visibility::Hidden;
     }
+    if let Ok(mut text) = message_query.single_mut() {
+        text.clear();
+    }
 }
 
-// Show death menu when player dies
+// Show death menu when player dies, unless they are about to respawn at a checkpoint
 pub fn show_death_menu(
     player_query: Query<&Dead, With<Player>>,
+    checkpoint_state: Res<CheckpointState>,
+    current_level: Res<CurrentLevel>,
     mut menu_query: Query<&mut Visibility, With<GameMenu>>,
     mut title_query: Query<&mut Text, With<MenuTitle>>,
     mut title_color_query: Query<&mut TextColor, With<MenuTitle>>,
-    mut resume_query: Query<&mut Visibility, (With<ResumeButton>, Without<GameMenu>)>,
+    mut pause_only_query: Query<&mut Visibility, PauseOnlyButton>,
+    mut run_over_query: Query<&mut Visibility, RunOverButton>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    if checkpoint_state.can_respawn(current_level.data.as_ref()) {
+        return;
+    }
     if player_query.iter().next().is_some() {
         if let Ok(mut visibility) = menu_query.single_mut() {
             *visibility = Visibility::Inherited;
@@ -146,9 +210,12 @@ pub fn show_death_menu(
         if let Ok(mut color) = title_color_query.single_mut() {
             *color = TextColor(Color::srgb(0.9, 0.2, 0.2));
         }
-        if let Ok(mut visibility) = resume_query.single_mut() {
+        for mut visibility in &mut pause_only_query {
             *visibility = Visibility::Hidden;
         }
+        for mut visibility in &mut run_over_query {
+            *visibility = Visibility::Inherited;
+        }
         next_state.set(GameState::Dead);
     }
 }
@@ -158,7 +225,8 @@ pub fn show_victory_menu(
     mut menu_query: Query<&mut Visibility, With<GameMenu>>,
     mut title_query: Query<&mut Text, With<MenuTitle>>,
     mut title_color_query: Query<&mut TextColor, With<MenuTitle>>,
-    mut resume_query: Query<&mut Visibility, (With<ResumeButton>, Without<GameMenu>)>,
+    mut pause_only_query: Query<&mut Visibility, PauseOnlyButton>,
+    mut run_over_query: Query<&mut Visibility, RunOverButton>,
 ) {
     if let Ok(mut visibility) = menu_query.single_mut() {
         *visibility = Visibility::Inherited;
@@ -169,9 +237,100 @@ pub fn show_victory_menu(
     if let Ok(mut color) = title_color_query.single_mut() {
         *color = TextColor(Color::srgb(0.2, 0.9, 0.3));
     }
-    if let Ok(mut visibility) = resume_query.single_mut() {
+    for mut visibility in &mut pause_only_query {
         *visibility = Visibility::Hidden;
     }
+    for mut visibility in &mut run_over_query {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Fill in the cleared level's best times on the victory screen, highlighting the new clear
+pub fn show_leaderboard(
+    leaderboard: Res<Leaderboard>,
+    latest: Res<LatestClear>,
+    mut panel_query: Query<&mut Visibility, With<LeaderboardPanel>>,
+    mut title_query: Query<&mut Text, (With<LeaderboardTitle>, Without<LeaderboardRow>)>,
+    mut row_query: Query<(&mut Text, &mut TextColor, &LeaderboardRow), Without<LeaderboardTitle>>,
+) {
+    let Some(level) = latest.level.as_deref() else { return };
+    let entries = leaderboard.entries(level);
+
+    if let Ok(mut text) = title_query.single_mut() {
+        **text = match latest.rank {
+            Some(rank) => format!("BEST CLEARS - {} - NEW #{}", level, rank + 1),
+            None => format!("BEST CLEARS - {}", level),
+        };
+    }
+    for (mut text, mut color, row) in &mut row_query {
+        **text = match entries.get(row.0) {
+            Some(entry) => format!(
+                "{:>2}. {}   {} damage   {} kills   {} resources",
+                row.0 + 1,
+                format_run_time(entry.time),
+                entry.damage_taken,
+                entry.kills,
+                entry.total_resources()
+            ),
+            None => String::new(),
+        };
+        let highlighted = latest.rank == Some(row.0);
+        *color = TextColor(if highlighted { Color::srgb(1.0, 0.85, 0.3) } else { Color::srgb(0.8, 0.8, 0.8) });
+    }
+    if let Ok(mut visibility) = panel_query.single_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+pub fn hide_leaderboard(
+    mut panel_query: Query<&mut Visibility, With<LeaderboardPanel>>,
+) {
+    if let Ok(mut visibility) = panel_query.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Show level error screen when the level fails to load
+pub fn show_level_error_menu(
+    current_level: Res<CurrentLevel>,
+    mut menu_query: Query<&mut Visibility, With<GameMenu>>,
+    mut title_query: Query<&mut Text, (With<MenuTitle>, Without<MenuMessage>)>,
+    mut title_color_query: Query<&mut TextColor, With<MenuTitle>>,
+    mut message_query: Query<&mut Text, (With<MenuMessage>, Without<MenuTitle>)>,
+    mut pause_only_query: Query<&mut Visibility, PauseOnlyButton>,
+    mut run_over_query: Query<&mut Visibility, RunOverButton>,
+) {
+    if let Ok(mut visibility) = menu_query.single_mut() {
+        *visibility = Visibility::Inherited;
+    }
+    if let Ok(mut text) = title_query.single_mut() {
+        **text = "LEVEL ERROR".to_string();
+    }
+    if let Ok(mut color) = title_color_query.single_mut() {
+        *color = TextColor(Color::srgb(0.9, 0.5, 0.2));
+    }
+    if let Ok(mut text) = message_query.single_mut() {
+        **text = current_level.error.clone().unwrap_or_default();
+    }
+    for mut visibility in &mut pause_only_query {
+        *visibility = Visibility::Hidden;
+    }
+    for mut visibility in &mut run_over_query {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+// Hide menu and clear error message when leaving the level error screen
+pub fn hide_level_error_menu(
+    mut menu_query: Query<&mut Visibility, With<GameMenu>>,
+    mut message_query: Query<&mut Text, With<MenuMessage>>,
+) {
+    if let Ok(mut visibility) = menu_query.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    if let Ok(mut text) = message_query.single_mut() {
+        text.clear();
+    }
 }
 
 pub fn update_weapon_info(
@@ -236,11 +395,11 @@ pub fn handle_menu_new_game_button(
         if *interaction == Interaction::Pressed {
             match current_state.get() {
                 GameState::Paused => {
-                    // Go to Dead first, flag will trigger auto-transition to Playing
+                    // Go to Dead first to end the run; the run summary starts the new game once closed
                     new_game_requested.0 = true;
                     next_state.set(GameState::Dead);
                 }
-                GameState::Dead | GameState::Victory => {
+                GameState::Dead | GameState::Victory | GameState::LevelError => {
                     next_state.set(GameState::Playing);
                 }
                 _ => {}
@@ -249,17 +408,397 @@ pub fn handle_menu_new_game_button(
     }
 }
 
-// Auto-transition from Dead to Playing when new game was requested from pause
-pub fn auto_start_new_game(
-    mut next_state: ResMut<NextState<GameState>>,
+// Save button handler
+pub fn handle_save_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SaveGameButton>)>,
+    mut requests: MessageWriter<SaveGameRequested>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            requests.write(SaveGameRequested);
+        }
+    }
+}
+
+// Load button handler
+pub fn handle_load_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<LoadGameButton>)>,
+    mut requests: MessageWriter<LoadGameRequested>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            requests.write(LoadGameRequested);
+        }
+    }
+}
+
+/// Report saves and loads: as a prompt while playing, in the menu otherwise
+pub fn show_save_notices(
+    mut commands: Commands,
+    mut notices: MessageReader<SaveNotice>,
+    game_state: Res<State<GameState>>,
+    prompt_query: Query<Entity, With<TriggerPrompt>>,
+    mut message_query: Query<&mut Text, With<MenuMessage>>,
+) {
+    for notice in notices.read() {
+        if *game_state.get() == GameState::Playing {
+            spawn_prompt(&mut commands, &prompt_query, notice.message.clone(), SAVE_PROMPT_DURATION);
+        } else if let Ok(mut text) = message_query.single_mut() {
+            **text = notice.message.clone();
+        }
+    }
+}
+
+// Upgrades button handler
+pub fn handle_upgrades_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MenuUpgradesButton>)>,
+    mut screen_query: Query<&mut Visibility, With<UpgradeScreen>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            if let Ok(mut visibility) = screen_query.single_mut() {
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+// Upgrade screen back button handler
+pub fn handle_upgrade_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<UpgradeBackButton>)>,
+    screen_query: Query<&mut Visibility, With<UpgradeScreen>>,
+) {
+    if interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        hide_upgrade_screen(screen_query);
+    }
+}
+
+// Upgrade buy button handler
+pub fn handle_upgrade_buy_buttons(
+    interaction_query: Query<(&Interaction, &UpgradeBuyButton), Changed<Interaction>>,
+    mut purchases: MessageWriter<PurchaseUpgrade>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            purchases.write(PurchaseUpgrade { upgrade: button.0 });
+        }
+    }
+}
+
+/// Show banked resources, upgrade levels and prices while the upgrade screen is open
+pub fn update_upgrade_screen(
+    profile: Res<Profile>,
+    screen_query: Query<&Visibility, With<UpgradeScreen>>,
+    mut bank_query: Query<&mut Text, (With<UpgradeBankText>, Without<UpgradeRowText>)>,
+    mut row_query: Query<(&mut Text, &UpgradeRowText), Without<UpgradeBankText>>,
+    mut button_query: Query<(&mut BackgroundColor, &UpgradeBuyButton)>,
+) {
+    if screen_query.single().is_ok_and(|visibility| *visibility == Visibility::Hidden) {
+        return;
+    }
+
+    if let Ok(mut text) = bank_query.single_mut() {
+        **text = [Currency::Philosophy, Currency::NatureStudy, Currency::Wisdom]
+            .map(|currency| format!("{} {}", profile.balance(currency), currency.name()))
+            .join("   ");
+    }
+    for (mut text, row) in &mut row_query {
+        let upgrade = row.0;
+        let price = match profile.next_cost(upgrade) {
+            Some(cost) => format!("{} {}", cost, upgrade.currency().name()),
+            None => "MAX".to_string(),
+        };
+        **text = format!("{}  {}/{}  {}", upgrade.name(), profile.level(upgrade), upgrade.max_level(), price);
+    }
+    for (mut color, button) in &mut button_query {
+        let affordable = profile.can_afford(button.0);
+        color.set_if_neq(BackgroundColor(if affordable { Color::srgb(0.3, 0.5, 0.3) } else { Color::srgb(0.25, 0.25, 0.28) }));
+    }
+}
+
+// Close the upgrade screen, also when the menu it was opened from goes away
+pub fn hide_upgrade_screen(
+    mut screen_query: Query<&mut Visibility, With<UpgradeScreen>>,
+) {
+    if let Ok(mut visibility) = screen_query.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Show the statistics of the run that just ended
+pub fn show_run_summary(
+    run_stats: Res<RunStats>,
+    registry: Res<ItemRegistry>,
+    mut screen_query: Query<&mut Visibility, With<RunSummaryScreen>>,
+    mut title_query: Query<&mut Text, (With<RunSummaryTitle>, Without<RunSummaryText>)>,
+    mut text_query: Query<&mut Text, (With<RunSummaryText>, Without<RunSummaryTitle>)>,
+) {
+    let item_list = |items: &BTreeMap<ItemId, u32>| {
+        let total: u32 = items.values().sum();
+        if items.is_empty() {
+            return total.to_string();
+        }
+        let names: Vec<String> = items
+            .iter()
+            .map(|(id, count)| {
+                let name = registry.items.get(id).map_or_else(|| format!("{:?}", id), |item| item.name.clone());
+                format!("{} {}", name, count)
+            })
+            .collect();
+        format!("{} ({})", total, names.join(", "))
+    };
+
+    let kills = if run_stats.kills.is_empty() {
+        "0".to_string()
+    } else {
+        let kinds: Vec<String> = run_stats.kills.iter().map(|(kind, count)| format!("{} {}", kind, count)).collect();
+        format!("{} ({})", run_stats.total_kills(), kinds.join(", "))
+    };
+    let resources = &run_stats.resources;
+
+    let lines = [
+        format!("Level: {}", run_stats.level.as_deref().unwrap_or("-")),
+        format!("Time: {}", format_run_time(run_stats.time)),
+        format!("Kills: {}", kills),
+        format!("Damage dealt: {}   Damage taken: {}", run_stats.damage_dealt, run_stats.damage_taken),
+        format!("Hits blocked: {}   Dashes: {}", run_stats.hits_blocked, run_stats.dashes),
+        format!("Items picked up: {}", item_list(&run_stats.items_picked_up)),
+        format!("Items used: {}", item_list(&run_stats.items_used)),
+        format!(
+            "Resources: {} philosophy, {} nature study, {} wisdom",
+            resources.philosophy, resources.nature_study, resources.wisdom
+        ),
+    ];
+
+    if let Ok(mut text) = title_query.single_mut() {
+        **text = run_stats.outcome.map_or("RUN OVER", |outcome| outcome.title()).to_string();
+    }
+    if let Ok(mut text) = text_query.single_mut() {
+        **text = lines.join("\n");
+    }
+    if let Ok(mut visibility) = screen_query.single_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+// Run summary continue button handler: reveal the menu underneath, or start the
+// new game right away when the run was ended from the pause menu
+pub fn handle_run_summary_continue(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RunSummaryContinueButton>)>,
+    screen_query: Query<&mut Visibility, With<RunSummaryScreen>>,
     mut new_game_requested: ResMut<crate::world::NewGameRequested>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
+    if !interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        return;
+    }
+    hide_run_summary(screen_query);
     if new_game_requested.0 {
         new_game_requested.0 = false;
         next_state.set(GameState::Playing);
     }
 }
 
+pub fn hide_run_summary(
+    mut screen_query: Query<&mut Visibility, With<RunSummaryScreen>>,
+) {
+    if let Ok(mut visibility) = screen_query.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Difficulty button handler: steps to the next preset, wrapping around
+pub fn handle_difficulty_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DifficultyButton>)>,
+    strict: Res<StrictConfig>,
+    mut difficulty: ResMut<Difficulty>,
+    mut message_query: Query<&mut Text, With<MenuMessage>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let next = difficulty.next();
+        // Strict mode would refuse to load a preset with problems, so don't switch to it
+        if strict.0 {
+            let (_, report) = GameConfig::load_from_files(&next.config_stack());
+            if !report.diagnostics.is_empty() {
+                report.log();
+                if let Ok(mut text) = message_query.single_mut() {
+                    **text = format!("Strict config: {} preset has problems ({})", next.name(), report.summary());
+                }
+                continue;
+            }
+        }
+        *difficulty = next;
+    }
+}
+
+/// Keep the difficulty button label in step with the selected difficulty
+pub fn update_difficulty_button(
+    difficulty: Res<Difficulty>,
+    button_query: Query<&Children, With<DifficultyButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !difficulty.is_changed() {
+        return;
+    }
+    for children in &button_query {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = format!("DIFFICULTY: {}", difficulty.name().to_uppercase());
+            }
+        }
+    }
+}
+
+fn store_bindings(bindings: &InputBindings) {
+    if let Err(error) = bindings.save_to_file(CONTROLS_FILE) {
+        error!("{}", error);
+    }
+}
+
+pub fn handle_controls_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MenuControlsButton>)>,
+    mut screen_query: Query<&mut Visibility, With<ControlsScreen>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            if let Ok(mut visibility) = screen_query.single_mut() {
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+pub fn handle_controls_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ControlsBackButton>)>,
+    screen_query: Query<&mut Visibility, With<ControlsScreen>>,
+    capture: ResMut<BindingCapture>,
+    message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    if interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        hide_controls_screen(screen_query, capture, message_query);
+    }
+}
+
+/// Start listening for the next key or mouse press to bind to the row's action
+pub fn handle_controls_add_buttons(
+    interaction_query: Query<(&Interaction, &ControlsAddButton), Changed<Interaction>>,
+    mut capture: ResMut<BindingCapture>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            capture.0 = Some(button.0);
+            if let Ok(mut text) = message_query.single_mut() {
+                **text = format!("Press a key or mouse button for {} (Esc cancels)", button.0.name());
+            }
+        }
+    }
+}
+
+pub fn handle_controls_clear_buttons(
+    interaction_query: Query<(&Interaction, &ControlsClearButton), Changed<Interaction>>,
+    mut bindings: ResMut<InputBindings>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            let message = if bindings.clear(button.0) {
+                store_bindings(&bindings);
+                format!("{} is unbound", button.0.name())
+            } else {
+                format!("{} must stay bound", button.0.name())
+            };
+            if let Ok(mut text) = message_query.single_mut() {
+                **text = message;
+            }
+        }
+    }
+}
+
+pub fn handle_controls_reset_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ControlsResetButton>)>,
+    mut bindings: ResMut<InputBindings>,
+    mut capture: ResMut<BindingCapture>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    if interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        *bindings = InputBindings::default();
+        capture.0 = None;
+        store_bindings(&bindings);
+        if let Ok(mut text) = message_query.single_mut() {
+            **text = "Default controls restored".to_string();
+        }
+    }
+}
+
+/// Bind the first key or mouse button pressed while a rebind is pending
+pub fn capture_binding(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut capture: ResMut<BindingCapture>,
+    mut bindings: ResMut<InputBindings>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    let Some(action) = capture.0 else {
+        return;
+    };
+
+    let message = if keyboard.just_pressed(KeyCode::Escape) {
+        "Rebind cancelled".to_string()
+    } else {
+        let binding = match keyboard.get_just_pressed().next() {
+            Some(key) => InputBinding::Key(*key),
+            None => match mouse.get_just_pressed().next() {
+                Some(button) => InputBinding::Mouse(*button),
+                None => return,
+            },
+        };
+        match bindings.add(action, binding) {
+            Ok(()) => {
+                store_bindings(&bindings);
+                format!("{} bound to {}", binding.label(), action.name())
+            }
+            Err(other) => format!("{} is already bound to {}", binding.label(), other.name()),
+        }
+    };
+
+    capture.0 = None;
+    if let Ok(mut text) = message_query.single_mut() {
+        **text = message;
+    }
+}
+
+/// Show each action's bindings while the controls screen is open
+pub fn update_controls_screen(
+    bindings: Res<InputBindings>,
+    screen_query: Query<&Visibility, With<ControlsScreen>>,
+    mut row_query: Query<(&mut Text, &ControlsRowText)>,
+) {
+    if screen_query.single().is_ok_and(|visibility| *visibility == Visibility::Hidden) {
+        return;
+    }
+    for (mut text, row) in &mut row_query {
+        **text = format!("{}  {}", row.0.name(), bindings.label(row.0));
+    }
+}
+
+pub fn hide_controls_screen(
+    mut screen_query: Query<&mut Visibility, With<ControlsScreen>>,
+    mut capture: ResMut<BindingCapture>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    if let Ok(mut visibility) = screen_query.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    capture.0 = None;
+    if let Ok(mut text) = message_query.single_mut() {
+        text.clear();
+    }
+}
+
 // Exit button handler
 pub fn handle_exit_button(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<ExitButton>)>,
@@ -272,6 +811,130 @@ pub fn handle_exit_button(
     }
 }
 
+/// Show text prompts from fired level triggers, replacing any prompt already on screen
+pub fn show_trigger_prompts(
+    mut commands: Commands,
+    mut fired: MessageReader<TriggerFired>,
+    current_level: Res<CurrentLevel>,
+    prompt_query: Query<Entity, With<TriggerPrompt>>,
+) {
+    for event in fired.read() {
+        let Some(volume) = current_level.triggers().get(event.trigger) else { continue };
+
+        for action in &volume.actions {
+            let TriggerAction::ShowText { text, duration } = action else { continue };
+
+            spawn_prompt(&mut commands, &prompt_query, text.clone(), *duration);
+        }
+    }
+}
+
+/// Confirm a reached checkpoint, with the lives left when the level limits them
+pub fn show_checkpoint_prompts(
+    mut commands: Commands,
+    mut reached: MessageReader<CheckpointReached>,
+    checkpoint_state: Res<CheckpointState>,
+    current_level: Res<CurrentLevel>,
+    prompt_query: Query<Entity, With<TriggerPrompt>>,
+) {
+    if reached.read().count() == 0 {
+        return;
+    }
+    let Some(level) = current_level.data.as_ref() else { return };
+
+    let text = match checkpoint_state.lives_left(level) {
+        Some(1) => "Checkpoint reached (1 life left)".to_string(),
+        Some(lives) => format!("Checkpoint reached ({} lives left)", lives),
+        None => "Checkpoint reached".to_string(),
+    };
+    spawn_prompt(&mut commands, &prompt_query, text, CHECKPOINT_PROMPT_DURATION);
+}
+
+/// Show a prompt at the top of the screen, replacing any prompt already there
+fn spawn_prompt(commands: &mut Commands, prompt_query: &Query<Entity, With<TriggerPrompt>>, text: String, duration: f32) {
+    for entity in prompt_query {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        TriggerPrompt {
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        },
+        Text::new(text),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.95, 0.9, 0.75)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    ));
+}
+
+pub fn update_trigger_prompts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut prompt_query: Query<(Entity, &mut TriggerPrompt)>,
+) {
+    for (entity, mut prompt) in &mut prompt_query {
+        prompt.timer.tick(time.delta());
+        if prompt.timer.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Show the config summary and its first problems when the files read at startup had any
+pub fn show_config_problems(mut commands: Commands, report: Res<ConfigReport>) {
+    if report.diagnostics.is_empty() {
+        return;
+    }
+    let mut lines = vec![format!("Config {}", report.summary())];
+    lines.extend(report.diagnostics.iter().take(CONFIG_NOTICE_LINES).map(ToString::to_string));
+    if report.diagnostics.len() > CONFIG_NOTICE_LINES {
+        lines.push(format!("...and {} more in the log", report.diagnostics.len() - CONFIG_NOTICE_LINES));
+    }
+
+    commands.spawn((
+        ConfigNotice {
+            timer: Timer::from_seconds(CONFIG_NOTICE_DURATION, TimerMode::Once),
+        },
+        Text::new(lines.join("\n")),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.6, 0.5)),
+        TextLayout::new_with_justify(Justify::Center),
+        // Below the trigger prompts so a level's first prompt doesn't cover it
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    ));
+}
+
+pub fn update_config_notice(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notice_query: Query<(Entity, &mut ConfigNotice)>,
+) {
+    for (entity, mut notice) in &mut notice_query {
+        notice.timer.tick(time.delta());
+        if notice.timer.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 /// Spawn key bindings guide panel (bottom-left)
 pub fn spawn_key_bindings_panel(mut commands: Commands) {
     commands
@@ -290,17 +953,19 @@ pub fn spawn_key_bindings_panel(mut commands: Commands) {
             BorderRadius::all(Val::Px(4.0)),
         ))
         .with_children(|parent| {
-            let bindings = [
-                ("WASD", "Move"),
-                ("Shift", "Sprint"),
-                ("Space", "Dash"),
-                ("LMB", "Attack"),
-                ("RMB", "Block"),
-                ("R", "Weapon"),
-                ("Tab", "Inventory"),
-                ("Esc", "Pause"),
+            let rows: [(&'static [GameAction], &str); 10] = [
+                (&[GameAction::MoveUp, GameAction::MoveLeft, GameAction::MoveDown, GameAction::MoveRight], "Move"),
+                (&[GameAction::Sprint], "Sprint"),
+                (&[GameAction::Dash], "Dash"),
+                (&[GameAction::Attack], "Attack"),
+                (&[GameAction::Block], "Block"),
+                (&[GameAction::ToggleWeapon], "Weapon"),
+                (&[GameAction::ToggleInventory], "Inventory"),
+                (&[GameAction::Interact], "Interact"),
+                (&[GameAction::Pause], "Pause"),
+                (&[GameAction::QuickSave], "Quick save"),
             ];
-            for (key, action) in bindings {
+            for (actions, action) in rows {
                 parent
                     .spawn(Node {
                         column_gap: Val::Px(8.0),
@@ -308,7 +973,8 @@ pub fn spawn_key_bindings_panel(mut commands: Commands) {
                     })
                     .with_children(|row| {
                         row.spawn((
-                            Text::new(key),
+                            KeyBindingsKeys(actions),
+                            Text::new(""),
                             TextFont {
                                 font_size: 11.0,
                                 ..default()
@@ -327,3 +993,22 @@ pub fn spawn_key_bindings_panel(mut commands: Commands) {
             }
         });
 }
+
+/// Keep the key bindings panel in step with the live bindings. Single-key labels
+/// run together the way movement keys are usually written, e.g. "WASD".
+pub fn update_key_bindings_panel(
+    bindings: Res<InputBindings>,
+    mut keys_query: Query<(&mut Text, Ref<KeyBindingsKeys>)>,
+) {
+    for (mut text, keys) in &mut keys_query {
+        if !bindings.is_changed() && !keys.is_added() {
+            continue;
+        }
+        let labels: Vec<String> = keys.0.iter().map(|action| bindings.label(*action)).collect();
+        **text = if labels.iter().all(|label| label.chars().count() == 1) {
+            labels.concat()
+        } else {
+            labels.join(" ")
+        };
+    }
+}
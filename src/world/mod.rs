@@ -4,6 +4,8 @@ use crate::inventory::ItemId;
 
 #[derive(Resource)]
 pub struct WorldConfig {
+    /// Items placed around the player spawn when a run starts, as (item, quantity, offset from the spawn).
+    /// The starting items upgrade adds to this default kit.
     pub starting_items: Vec<(ItemId, u32, Vec2)>,
 }
 